@@ -1,4 +1,5 @@
 fn main() {
+  println!("cargo:rustc-check-cfg=cfg(build, values(\"debug\", \"release\"))");
   if let Ok(profile) = std::env::var("PROFILE") {
     println!("cargo:rustc-cfg=build={:?}", profile);
   }
@@ -0,0 +1,1256 @@
+//! Procedural macros for the [`build_assert`] crate.
+//!
+//! You should not use this crate directly, use the macros re-exported by
+//! [`build_assert`] instead.
+//!
+//! [`build_assert`]: https://docs.rs/build_assert
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, quote_spanned};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use syn::{
+  parse::{Parse, ParseStream, Parser},
+  punctuated::Punctuated,
+  spanned::Spanned,
+  BinOp, Error, Expr, ExprCall, ExprLit, Ident, Lit, LitStr, Path, Result, Token,
+  TraitBoundModifier, Type, TypeParamBound, UnOp,
+};
+
+/// Asserts that a type implements the given traits at build-time.
+///
+/// Unlike other implementations of this kind of assertion, this macro can be
+/// used for types containing generic parameters of the outer item.
+///
+/// Since unsatisfied trait bounds are always reported by the type checker,
+/// this macro **fails to compile in both debug and release mode**.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<T: Clone + Send>() {
+///   # use build_assert_macros::build_assert_impl;
+///   build_assert_impl!(T: Clone + Send);
+///   build_assert_impl!(Vec<T>: Clone);
+/// }
+///
+/// foo::<i32>(); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// fn foo<T>() {
+///   # use build_assert_macros::build_assert_impl;
+///   build_assert_impl!(T: Clone);
+/// }
+/// ```
+///
+/// Note that the trait bounds can not refer to generic parameters of the
+/// outer item.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! build_assert_impl {
+///   ($ty:ty: $($bounds:tt)+) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn build_assert_impl(tokens: TokenStream) -> TokenStream {
+  match parse_build_assert_impl(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `build_assert_impl` macro.
+fn parse_build_assert_impl(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let assert_impl: AssertImpl = syn::parse2(tokens)?;
+  let ty = assert_impl.ty;
+  let bounds = strip_maybe_sized(assert_impl.bounds);
+  // Generate result.
+  Ok(quote! {
+    {
+      fn build_assert_impl<__T: ?::core::marker::Sized>()
+      where
+        __T: #(#bounds)+*,
+      {
+      }
+      build_assert_impl::<#ty>();
+    }
+  })
+}
+
+/// Asserts that a type implements the given traits at build-time, reports
+/// the given message if it does not.
+///
+/// Like [`build_assert_impl`], this macro can be used for types containing
+/// generic parameters of the outer item, and **fails to compile in both debug
+/// and release mode**. The message replaces the default "trait bound is not
+/// satisfied" error, by using `#[diagnostic::on_unimplemented]` (stable since
+/// Rust 1.78) on a generated helper trait.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<T: Copy + Send>() {
+///   # use build_assert_macros::build_assert_satisfies;
+///   build_assert_satisfies!(T, "must be Copy for SIMD", Copy + Send);
+/// }
+///
+/// foo::<i32>(); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// fn foo<T>() {
+///   # use build_assert_macros::build_assert_satisfies;
+///   build_assert_satisfies!(T, "must be Copy for SIMD", Copy);
+///   // error[E0277]: unsatisfied bounds for `T`: must be Copy for SIMD
+/// }
+/// ```
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! build_assert_satisfies {
+///   ($ty:ty, $msg:literal, $($bounds:tt)+) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn build_assert_satisfies(tokens: TokenStream) -> TokenStream {
+  match parse_build_assert_satisfies(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `build_assert_satisfies` macro.
+fn parse_build_assert_satisfies(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let AssertSatisfies { ty, msg, bounds } = syn::parse2(tokens)?;
+  let bounds = strip_maybe_sized(bounds);
+  // Braces in the message are escaped, since `on_unimplemented` treats them
+  // as format parameters.
+  let label = msg.value().replace('{', "{{").replace('}', "}}");
+  let message = format!("unsatisfied bounds for `{{Self}}`: {label}");
+  // Generate result.
+  Ok(quote! {
+    {
+      #[diagnostic::on_unimplemented(message = #message, label = #label)]
+      trait BuildAssertSatisfies {}
+      impl<__T: ?::core::marker::Sized> BuildAssertSatisfies for __T
+      where
+        __T: #(#bounds)+*,
+      {
+      }
+      fn build_assert_satisfies<__T: ?::core::marker::Sized + BuildAssertSatisfies>() {}
+      build_assert_satisfies::<#ty>();
+    }
+  })
+}
+
+/// Asserts that a trait is dyn compatible (object safe) at build-time.
+///
+/// The macro forms `&dyn Trait` in the generated code, the compiler reports
+/// an error pointing to the trait in the macro input, with reasons why the
+/// trait is not dyn compatible, instead of an error at a distant use site.
+/// Like [`build_assert_impl`], this macro can be used for traits with generic
+/// parameters of the outer item, and **fails to compile in both debug and
+/// release mode**.
+///
+/// # Examples
+///
+/// ```
+/// trait Shape {
+///   fn area(&self) -> f64;
+/// }
+///
+/// fn foo<T>() {
+///   # use build_assert_macros::build_assert_dyn_safe;
+///   build_assert_dyn_safe!(Shape);
+///   build_assert_dyn_safe!(AsRef<T> + Send);
+/// }
+///
+/// foo::<i32>(); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// trait Shape {
+///   fn new() -> Self;
+/// }
+///
+/// # use build_assert_macros::build_assert_dyn_safe;
+/// build_assert_dyn_safe!(Shape);
+/// // error[E0038]: the trait `Shape` is not dyn compatible
+/// ```
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! build_assert_dyn_safe {
+///   ($($bounds:tt)+) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn build_assert_dyn_safe(tokens: TokenStream) -> TokenStream {
+  match parse_build_assert_dyn_safe(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `build_assert_dyn_safe` macro.
+fn parse_build_assert_dyn_safe(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let bounds = Punctuated::<TypeParamBound, Token![+]>::parse_separated_nonempty.parse2(tokens)?;
+  // Generate result.
+  Ok(quote_spanned! {bounds.span()=>
+    {
+      #[allow(unused_parens)]
+      let _: ::core::option::Option<&(dyn #bounds)> = ::core::option::Option::None;
+    }
+  })
+}
+
+/// Removes `?Sized` bounds, since the generated helpers already have one.
+fn strip_maybe_sized(
+  bounds: Punctuated<TypeParamBound, Token![+]>,
+) -> impl Iterator<Item = TypeParamBound> {
+  bounds.into_iter().filter(|b| {
+    !matches!(b, TypeParamBound::Trait(t) if matches!(t.modifier, TraitBoundModifier::Maybe(_)))
+  })
+}
+
+/// Asserts a condition, raises a const panic if the condition is
+/// const-evaluable, otherwise raises a build error.
+///
+/// This macro is used by `build_assert` when feature `strict_debug` is
+/// enabled.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! strict_assert {
+///   ($krate:tt, $cond:expr, $($arg:tt)+) => { ... };
+/// }
+/// ```
+#[doc(hidden)]
+#[proc_macro]
+pub fn strict_assert(tokens: TokenStream) -> TokenStream {
+  match parse_strict_assert(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `strict_assert` macro.
+fn parse_strict_assert(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let StrictAssert { krate, cond, args } = syn::parse2(tokens)?;
+  // Generate result.
+  Ok(if is_primitive_const_expr(&cond) {
+    // Const panics only support messages without format arguments.
+    let msg = if args.len() == 1 {
+      let msg = &args[0];
+      quote!(#msg)
+    } else {
+      // The stringified condition may contain braces, so it can not be used
+      // as a format string.
+      quote!(
+        "{}",
+        ::core::concat!("assertion failed: ", ::core::stringify!(#cond))
+      )
+    };
+    quote! {
+      const {
+        if !(#cond) {
+          ::core::panic!(#msg);
+        }
+      }
+    }
+  } else {
+    quote! {
+      if !(#cond) {
+        #krate::build_error!(#args);
+      }
+    }
+  })
+}
+
+/// Checks if the given expression is const-evaluable.
+///
+/// This is a conservative check, only literals, constants (names in
+/// `SCREAMING_SNAKE_CASE`), `size_of`/`align_of` calls, operators on them,
+/// and calls whose receiver and arguments are all const-evaluable are
+/// considered const-evaluable.
+fn is_const_expr(expr: &Expr) -> bool {
+  match expr {
+    Expr::Lit(_) => true,
+    Expr::Path(path) => path.qself.is_none() && is_const_path(&path.path),
+    Expr::Binary(binary) => is_const_expr(&binary.left) && is_const_expr(&binary.right),
+    Expr::Unary(unary) => !matches!(unary.op, UnOp::Deref(_)) && is_const_expr(&unary.expr),
+    Expr::Paren(paren) => is_const_expr(&paren.expr),
+    Expr::Group(group) => is_const_expr(&group.expr),
+    Expr::Cast(cast) => is_const_expr(&cast.expr),
+    Expr::Call(call) => {
+      matches!(&*call.func, Expr::Path(_))
+        && if call.args.is_empty() {
+          is_size_call(call)
+        } else {
+          call.args.iter().all(is_const_expr)
+        }
+    }
+    Expr::MethodCall(call) => is_const_expr(&call.receiver) && call.args.iter().all(is_const_expr),
+    _ => false,
+  }
+}
+
+/// Checks if the given call is a `size_of`/`align_of` call.
+fn is_size_call(call: &ExprCall) -> bool {
+  call.args.is_empty()
+    && matches!(&*call.func, Expr::Path(path) if path.path.segments.last().is_some_and(|s| {
+      s.ident == "size_of" || s.ident == "align_of"
+    }))
+}
+
+/// Checks if the given expression is const-evaluable and only uses primitive
+/// operators, so it can be evaluated in an inline const block.
+///
+/// Unlike [`is_const_expr`], only integer, `bool` and `char` literals are
+/// accepted. Single-segment constants are assumed to be const generics, and
+/// each comparison or arithmetic operator must have one of them, a literal, a
+/// cast or a `size_of`/`align_of` call on at least one side. Otherwise the
+/// operands may be constants of non-primitive types (e.g. `&str`), whose
+/// operators are not const.
+fn is_primitive_const_expr(expr: &Expr) -> bool {
+  primitive_const_kind(expr).is_some()
+}
+
+/// Returns `Some(true)` if the given expression is known to be of a
+/// primitive type, `Some(false)` if it may be a constant of any type, or
+/// `None` if it can not be evaluated in an inline const block.
+fn primitive_const_kind(expr: &Expr) -> Option<bool> {
+  match expr {
+    Expr::Lit(lit) => matches!(lit.lit, Lit::Int(_) | Lit::Bool(_) | Lit::Char(_)).then_some(true),
+    Expr::Path(path) => is_const_expr(expr).then_some(path.path.segments.len() == 1),
+    Expr::Binary(binary) => {
+      let left = primitive_const_kind(&binary.left)?;
+      let right = primitive_const_kind(&binary.right)?;
+      match binary.op {
+        // Logical operators only accept `bool`.
+        BinOp::And(_) | BinOp::Or(_) => Some(true),
+        _ if left || right => Some(true),
+        _ => None,
+      }
+    }
+    Expr::Unary(unary) if !matches!(unary.op, UnOp::Deref(_)) => primitive_const_kind(&unary.expr),
+    Expr::Paren(paren) => primitive_const_kind(&paren.expr),
+    Expr::Group(group) => primitive_const_kind(&group.expr),
+    // Casts only accept primitive types.
+    Expr::Cast(cast) => primitive_const_kind(&cast.expr).map(|_| true),
+    Expr::Call(call) => is_size_call(call).then_some(true),
+    _ => None,
+  }
+}
+
+/// Checks if the given path refers to a constant.
+fn is_const_path(path: &Path) -> bool {
+  path.segments.last().is_some_and(|s| {
+    let name = s.ident.to_string();
+    name.chars().any(|c| c.is_ascii_uppercase()) && !name.chars().any(|c| c.is_lowercase())
+  })
+}
+
+/// Emits a warning if the given condition is not const-evaluable.
+///
+/// Since proc-macro diagnostics are unstable, the warning is emitted by
+/// using a deprecated constant.
+///
+/// This macro is used by `build_assert` when feature `warn_non_const` is
+/// enabled.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! warn_non_const {
+///   ($cond:expr) => { ... };
+/// }
+/// ```
+#[doc(hidden)]
+#[proc_macro]
+pub fn warn_non_const(tokens: TokenStream) -> TokenStream {
+  match parse_warn_non_const(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `warn_non_const` macro.
+fn parse_warn_non_const(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let cond: Expr = syn::parse2(tokens)?;
+  // Generate result.
+  Ok(if is_const_expr(&cond) {
+    quote!({})
+  } else {
+    quote_spanned! {cond.span()=>
+      {
+        #[deprecated(
+          note = "build_assert condition is not const-evaluable; it may not fail the build"
+        )]
+        #[allow(non_upper_case_globals)]
+        const build_assert_non_const: () = ();
+        build_assert_non_const
+      }
+    }
+  })
+}
+
+/// Appends the site of the given condition to the file specified by the
+/// environment variable `BUILD_ASSERT_DUMP`, expands to nothing.
+///
+/// Each site is written as a line of JSON object with fields `file`, `line`,
+/// `column` and `condition`. Since multiple crates may be compiled in
+/// parallel, the file is locked while appending.
+///
+/// This macro is used by `build_assert` when feature `collect_sites` is
+/// enabled.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! collect_site {
+///   ($cond:expr) => { ... };
+/// }
+/// ```
+#[cfg(feature = "collect_sites")]
+#[doc(hidden)]
+#[proc_macro]
+pub fn collect_site(tokens: TokenStream) -> TokenStream {
+  let Some(path) = std::env::var_os("BUILD_ASSERT_DUMP") else {
+    return TokenStream::new();
+  };
+  // Use the span of the first token of the condition, which is in the user
+  // code. Conditions forwarded by `macro_rules` are wrapped in invisible
+  // groups, whose spans point to the macro definition.
+  let mut first = tokens.clone().into_iter().next();
+  while let Some(proc_macro::TokenTree::Group(g)) = &first {
+    if g.delimiter() != proc_macro::Delimiter::None {
+      break;
+    }
+    first = g.stream().into_iter().next();
+  }
+  let span = first.map_or_else(proc_macro::Span::call_site, |t| t.span());
+  let site = Site {
+    file: span.file(),
+    line: span.line(),
+    column: span.column(),
+    cond: TokenStream2::from(tokens).to_string(),
+  };
+  match dump_site(std::path::Path::new(&path), &site) {
+    Ok(()) => TokenStream::new(),
+    Err(e) => {
+      let msg = format!(
+        "failed to write assertion site to `{}`: {e}",
+        std::path::Path::new(&path).display()
+      );
+      Error::new(Span::call_site(), msg).to_compile_error().into()
+    }
+  }
+}
+
+/// Site of an assertion.
+#[cfg(feature = "collect_sites")]
+struct Site {
+  file: String,
+  line: usize,
+  column: usize,
+  cond: String,
+}
+
+#[cfg(feature = "collect_sites")]
+impl Site {
+  /// Returns the site as a line of JSON object.
+  fn to_json(&self) -> String {
+    format!(
+      "{{\"file\":{},\"line\":{},\"column\":{},\"condition\":{}}}\n",
+      json_str(&self.file),
+      self.line,
+      self.column,
+      json_str(&self.cond),
+    )
+  }
+}
+
+/// Converts the given string to a JSON string literal.
+#[cfg(feature = "collect_sites")]
+fn json_str(s: &str) -> String {
+  let mut json = String::with_capacity(s.len() + 2);
+  json.push('"');
+  for c in s.chars() {
+    match c {
+      '"' => json.push_str("\\\""),
+      '\\' => json.push_str("\\\\"),
+      '\n' => json.push_str("\\n"),
+      '\r' => json.push_str("\\r"),
+      '\t' => json.push_str("\\t"),
+      c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+      c => json.push(c),
+    }
+  }
+  json.push('"');
+  json
+}
+
+/// Appends the given site to the given file, the file is locked while
+/// appending.
+#[cfg(feature = "collect_sites")]
+fn dump_site(path: &std::path::Path, site: &Site) -> std::io::Result<()> {
+  use std::io::Write;
+  let mut file = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)?;
+  file.lock()?;
+  file.write_all(site.to_json().as_bytes())
+}
+
+/// Expands to the prefix of messages raised by `build_error`, i.e. a string
+/// literal `"[TAG] "` if the environment variable `BUILD_ERROR_TAG` is set to
+/// `TAG`, or an empty string literal otherwise.
+///
+/// The tag can only contain ASCII alphanumerics and underscores, so it can
+/// be embedded in format strings and symbol names.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! error_tag {
+///   () => { ... };
+/// }
+/// ```
+#[doc(hidden)]
+#[proc_macro]
+pub fn error_tag(tokens: TokenStream) -> TokenStream {
+  match parse_error_tag(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `error_tag` macro.
+fn parse_error_tag(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Check macro input.
+  if !tokens.is_empty() {
+    return Err(Error::new_spanned(tokens, "unexpected tokens"));
+  }
+  // Generate result.
+  let tag = std::env::var("BUILD_ERROR_TAG").unwrap_or_default();
+  let prefix = tag_prefix(&tag).map_err(|e| Error::new(Span::call_site(), e))?;
+  let prefix = LitStr::new(&prefix, Span::call_site());
+  Ok(quote!(#prefix))
+}
+
+/// Returns the prefix of messages for the given tag, or an error message if
+/// the tag is invalid.
+fn tag_prefix(tag: &str) -> std::result::Result<String, String> {
+  if tag.is_empty() {
+    Ok(String::new())
+  } else if tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+    Ok(format!("[{tag}] "))
+  } else {
+    Err(format!(
+      "`BUILD_ERROR_TAG` value `{tag}` can only contain ASCII alphanumerics and underscores"
+    ))
+  }
+}
+
+/// Evaluates a condition consisting of integer literals at expansion time,
+/// raises a compile error if the condition is `false`, otherwise asserts
+/// the condition using `build_assert`.
+///
+/// This macro is used by `build_assert_eval`.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! eval_assert {
+///   ($krate:tt, $cond:expr $(, $($arg:tt)+)?) => { ... };
+/// }
+/// ```
+#[doc(hidden)]
+#[proc_macro]
+pub fn eval_assert(tokens: TokenStream) -> TokenStream {
+  match parse_eval_assert(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `eval_assert` macro.
+fn parse_eval_assert(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let EvalAssert { krate, cond, args } = syn::parse2(tokens)?;
+  // Evaluate the condition.
+  let msg = match eval_cond(&cond) {
+    Some(Ok(())) => return Ok(quote!({})),
+    Some(Err(msg)) => msg,
+    None => return Ok(quote!(#krate::build_assert!(#cond, #args))),
+  };
+  // Generate compile error.
+  let msg = match args.first() {
+    Some(Expr::Lit(ExprLit {
+      lit: Lit::Str(s), ..
+    })) => format!("{}\n{msg}", s.value()),
+    _ => format!("assertion failed: {}\n{msg}", quote!(#cond)),
+  };
+  Ok(Error::new_spanned(cond, msg).to_compile_error())
+}
+
+/// Evaluates the given condition.
+///
+/// Returns `None` if the condition can not be evaluated, otherwise returns
+/// the result, with a message containing the operands if the condition is
+/// `false`.
+fn eval_cond(cond: &Expr) -> Option<std::result::Result<(), String>> {
+  match eval_expr(cond)? {
+    Value::Bool(true) => Some(Ok(())),
+    Value::Bool(false) => Some(Err(match strip_parens(cond) {
+      Expr::Binary(binary) if is_comparison(&binary.op) => format!(
+        "  left: {}\n right: {}",
+        eval_expr(&binary.left)?,
+        eval_expr(&binary.right)?
+      ),
+      _ => "evaluated to `false`".into(),
+    })),
+    Value::Int(_) => None,
+  }
+}
+
+/// Removes parentheses around the given expression.
+fn strip_parens(expr: &Expr) -> &Expr {
+  match expr {
+    Expr::Paren(paren) => strip_parens(&paren.expr),
+    Expr::Group(group) => strip_parens(&group.expr),
+    _ => expr,
+  }
+}
+
+/// Checks if the given binary operator is a comparison.
+fn is_comparison(op: &BinOp) -> bool {
+  matches!(
+    op,
+    BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_)
+  )
+}
+
+/// Value evaluated at expansion time.
+///
+/// Unsuffixed integer literals are inferred as `i32` if nothing else
+/// constrains them, so integers are evaluated as `i32`.
+#[derive(Clone, Copy)]
+enum Value {
+  Int(i32),
+  Bool(bool),
+}
+
+impl std::fmt::Display for Value {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Int(i) => write!(f, "{i}"),
+      Self::Bool(b) => write!(f, "{b}"),
+    }
+  }
+}
+
+/// Evaluates the given expression.
+///
+/// Returns `None` if the expression can not be evaluated, including integer
+/// literals with a suffix, whose type may differ from `i32`, and operations
+/// that overflow `i32`.
+fn eval_expr(expr: &Expr) -> Option<Value> {
+  use Value::*;
+  match expr {
+    Expr::Lit(ExprLit { lit, .. }) => match lit {
+      Lit::Int(i) if i.suffix().is_empty() => i.base10_parse().ok().map(Int),
+      Lit::Bool(b) => Some(Bool(b.value)),
+      _ => None,
+    },
+    Expr::Paren(paren) => eval_expr(&paren.expr),
+    Expr::Group(group) => eval_expr(&group.expr),
+    Expr::Unary(unary) => match (&unary.op, eval_expr(&unary.expr)?) {
+      (UnOp::Neg(_), Int(i)) => i.checked_neg().map(Int),
+      (UnOp::Not(_), Bool(b)) => Some(Bool(!b)),
+      (UnOp::Not(_), Int(i)) => Some(Int(!i)),
+      _ => None,
+    },
+    Expr::Binary(binary) => {
+      let lhs = eval_expr(&binary.left)?;
+      // Short-circuit logical operators.
+      match (&binary.op, lhs) {
+        (BinOp::And(_), Bool(false)) => return Some(Bool(false)),
+        (BinOp::Or(_), Bool(true)) => return Some(Bool(true)),
+        _ => {}
+      }
+      match (&binary.op, lhs, eval_expr(&binary.right)?) {
+        (BinOp::And(_) | BinOp::Or(_), Bool(_), Bool(r)) => Some(Bool(r)),
+        (BinOp::Add(_), Int(l), Int(r)) => l.checked_add(r).map(Int),
+        (BinOp::Sub(_), Int(l), Int(r)) => l.checked_sub(r).map(Int),
+        (BinOp::Mul(_), Int(l), Int(r)) => l.checked_mul(r).map(Int),
+        (BinOp::Div(_), Int(l), Int(r)) => l.checked_div(r).map(Int),
+        (BinOp::Rem(_), Int(l), Int(r)) => l.checked_rem(r).map(Int),
+        (BinOp::BitAnd(_), Int(l), Int(r)) => Some(Int(l & r)),
+        (BinOp::BitOr(_), Int(l), Int(r)) => Some(Int(l | r)),
+        (BinOp::BitXor(_), Int(l), Int(r)) => Some(Int(l ^ r)),
+        (BinOp::Shl(_), Int(l), Int(r)) => l.checked_shl(r.try_into().ok()?).map(Int),
+        (BinOp::Shr(_), Int(l), Int(r)) => l.checked_shr(r.try_into().ok()?).map(Int),
+        (BinOp::Eq(_), Int(l), Int(r)) => Some(Bool(l == r)),
+        (BinOp::Ne(_), Int(l), Int(r)) => Some(Bool(l != r)),
+        (BinOp::Lt(_), Int(l), Int(r)) => Some(Bool(l < r)),
+        (BinOp::Le(_), Int(l), Int(r)) => Some(Bool(l <= r)),
+        (BinOp::Gt(_), Int(l), Int(r)) => Some(Bool(l > r)),
+        (BinOp::Ge(_), Int(l), Int(r)) => Some(Bool(l >= r)),
+        (BinOp::Eq(_), Bool(l), Bool(r)) => Some(Bool(l == r)),
+        (BinOp::Ne(_), Bool(l), Bool(r)) => Some(Bool(l != r)),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+/// Asserts each conjunct of a condition separately, so that the failing one
+/// can be reported.
+///
+/// Conjuncts consisting of literals are evaluated at expansion time, a
+/// compile error containing the evaluated operands is raised if one of them
+/// is `false`. Other conjuncts are asserted using `build_assert`, with a
+/// message pointing out the conjunct.
+///
+/// This macro is used by `build_assert_detailed`.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! detailed_assert {
+///   ($krate:tt, $cond:expr $(, $msg:literal)?) => { ... };
+/// }
+/// ```
+#[doc(hidden)]
+#[proc_macro]
+pub fn detailed_assert(tokens: TokenStream) -> TokenStream {
+  match parse_detailed_assert(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `detailed_assert` macro.
+fn parse_detailed_assert(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let DetailedAssert { krate, cond, msg } = syn::parse2(tokens)?;
+  let prefix = msg.map_or_else(String::new, |m| format!("{}\n", m.value()));
+  // Split the condition into conjuncts.
+  let mut conjuncts = Vec::new();
+  split_conjuncts(&cond, &mut conjuncts);
+  let total = conjuncts.len();
+  let mut asserts = TokenStream2::new();
+  for (i, conjunct) in conjuncts.into_iter().enumerate() {
+    let failed = format!(
+      "{prefix}assertion failed: {} (conjunct {} of {total})",
+      quote!(#conjunct),
+      i + 1
+    );
+    match eval_cond(conjunct) {
+      Some(Ok(())) => {}
+      Some(Err(operands)) => {
+        return Ok(Error::new_spanned(conjunct, format!("{failed}\n{operands}")).to_compile_error())
+      }
+      None => {
+        // Braces are escaped, since the message is used as a format string.
+        let failed = failed.replace('{', "{{").replace('}', "}}");
+        asserts.extend(quote!(#krate::build_assert!(#conjunct, #failed);));
+      }
+    }
+  }
+  Ok(quote!({ #asserts }))
+}
+
+/// Splits the given condition into conjuncts of `&&`.
+fn split_conjuncts<'a>(cond: &'a Expr, conjuncts: &mut Vec<&'a Expr>) {
+  match strip_parens(cond) {
+    Expr::Binary(binary) if matches!(binary.op, BinOp::And(_)) => {
+      split_conjuncts(&binary.left, conjuncts);
+      split_conjuncts(&binary.right, conjuncts);
+    }
+    _ => conjuncts.push(cond),
+  }
+}
+
+/// Generates an identifier that is unique in the current compilation.
+///
+/// The identifier consists of the given prefix (`__build_uniq_sym` by
+/// default), an optional tag, the crate name and a counter, e.g.
+/// `prefix_tag_my_crate_0`. Since the counter is increased on every
+/// expansion, the identifier may change if other invocations are added or
+/// removed.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert_macros::build_uniq_sym;
+/// macro_rules! def_const {
+///   ($id:ident) => {
+///     #[allow(non_upper_case_globals)]
+///     const $id: usize = 1;
+///   };
+/// }
+///
+/// // Defines two different constants.
+/// build_uniq_sym!(=> def_const);
+/// build_uniq_sym!(my_const => def_const);
+/// build_uniq_sym!(my_const "tag" => def_const);
+/// ```
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! build_uniq_sym {
+///   ($($prefix:ident)? $($tag:literal)? $(=> $apply_to:path)?) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn build_uniq_sym(tokens: TokenStream) -> TokenStream {
+  match parse_build_uniq_sym(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Counter of identifiers generated by `build_uniq_sym`.
+static UNIQ_SYM_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Parses the `build_uniq_sym` macro.
+fn parse_build_uniq_sym(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let UniqSym {
+    prefix,
+    tag,
+    apply_to,
+  } = syn::parse2(tokens)?;
+  // Generate the identifier.
+  let mut prefix = prefix.map_or_else(|| "__build_uniq_sym".into(), |p| p.to_string());
+  if let Some(tag) = tag {
+    let value = tag.value();
+    if !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+      return Err(Error::new(
+        tag.span(),
+        format!("tag `{value}` can only contain ASCII alphanumerics and underscores"),
+      ));
+    }
+    prefix = format!("{prefix}_{value}");
+  }
+  let id = UNIQ_SYM_COUNTER.fetch_add(1, Ordering::Relaxed);
+  let sym = match std::env::var("CARGO_CRATE_NAME") {
+    Ok(krate) => format!("{prefix}_{krate}_{id}"),
+    Err(_) => format!("{prefix}_{id}"),
+  };
+  let sym = Ident::new(&sym, Span::call_site());
+  // Generate result.
+  Ok(match apply_to {
+    Some(m) => quote!(#m!(#sym);),
+    None => quote!(#sym),
+  })
+}
+
+/// AST of the `build_uniq_sym` macro.
+struct UniqSym {
+  prefix: Option<Ident>,
+  tag: Option<LitStr>,
+  apply_to: Option<Path>,
+}
+
+impl Parse for UniqSym {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let prefix = if input.peek(Ident) {
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    let tag = if input.peek(LitStr) {
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    let apply_to = if input.peek(Token![=>]) {
+      input.parse::<Token![=>]>()?;
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    Ok(Self {
+      prefix,
+      tag,
+      apply_to,
+    })
+  }
+}
+
+/// AST of the `eval_assert` macro.
+struct EvalAssert {
+  krate: TokenTree,
+  cond: Expr,
+  args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for EvalAssert {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let krate = input.parse()?;
+    input.parse::<Token![,]>()?;
+    let cond = input.parse()?;
+    let args = if input.is_empty() {
+      Punctuated::new()
+    } else {
+      input.parse::<Token![,]>()?;
+      Punctuated::parse_terminated(input)?
+    };
+    Ok(Self { krate, cond, args })
+  }
+}
+
+/// AST of the `detailed_assert` macro.
+struct DetailedAssert {
+  krate: TokenTree,
+  cond: Expr,
+  msg: Option<LitStr>,
+}
+
+impl Parse for DetailedAssert {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let krate = input.parse()?;
+    input.parse::<Token![,]>()?;
+    let cond = input.parse()?;
+    let msg = if input.is_empty() {
+      None
+    } else {
+      input.parse::<Token![,]>()?;
+      Some(input.parse()?)
+    };
+    Ok(Self { krate, cond, msg })
+  }
+}
+
+/// AST of the `strict_assert` macro.
+struct StrictAssert {
+  krate: TokenTree,
+  cond: Expr,
+  args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for StrictAssert {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let krate = input.parse()?;
+    input.parse::<Token![,]>()?;
+    let cond = input.parse()?;
+    input.parse::<Token![,]>()?;
+    Ok(Self {
+      krate,
+      cond,
+      args: Punctuated::parse_terminated(input)?,
+    })
+  }
+}
+
+/// AST of the `build_assert_impl` macro.
+struct AssertImpl {
+  ty: Type,
+  _colon: Token![:],
+  bounds: Punctuated<TypeParamBound, Token![+]>,
+}
+
+impl Parse for AssertImpl {
+  fn parse(input: ParseStream) -> Result<Self> {
+    Ok(Self {
+      ty: input.parse()?,
+      _colon: input.parse()?,
+      bounds: Punctuated::parse_separated_nonempty(input)?,
+    })
+  }
+}
+
+/// AST of the `build_assert_satisfies` macro.
+struct AssertSatisfies {
+  ty: Type,
+  msg: LitStr,
+  bounds: Punctuated<TypeParamBound, Token![+]>,
+}
+
+impl Parse for AssertSatisfies {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let ty = input.parse()?;
+    input.parse::<Token![,]>()?;
+    let msg = input.parse()?;
+    input.parse::<Token![,]>()?;
+    Ok(Self {
+      ty,
+      msg,
+      bounds: Punctuated::parse_separated_nonempty(input)?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_strip_maybe_sized() {
+    let tokens = parse_build_assert_impl(quote!(str: ?Sized + Send)).unwrap();
+    assert!(!tokens.to_string().contains("? Sized"));
+  }
+
+  fn is_const(tokens: TokenStream2) -> bool {
+    is_const_expr(&syn::parse2(tokens).unwrap())
+  }
+
+  #[test]
+  fn test_is_const_expr() {
+    assert!(is_const(quote!(false)));
+    assert!(is_const(quote!(N > 10)));
+    assert!(is_const(quote!(!(A == B) && C as u8 == 1)));
+    assert!(is_const(quote!(Self::SIZE <= core::mem::size_of::<T>())));
+    assert!(!is_const(quote!(cond)));
+    assert!(is_const(quote!(N.is_power_of_two())));
+    assert!(is_const(quote!(N.pow(2) > usize::max(M, 1))));
+    assert!(!is_const(quote!(*N > 0)));
+    assert!(!is_const(quote!(foo() == N)));
+    assert!(!is_const(quote!(n.is_power_of_two())));
+    assert!(!is_const(quote!(N.checked_add(n).is_some())));
+  }
+
+  fn is_primitive_const(tokens: TokenStream2) -> bool {
+    is_primitive_const_expr(&syn::parse2(tokens).unwrap())
+  }
+
+  #[test]
+  fn test_is_primitive_const_expr() {
+    assert!(is_primitive_const(quote!(false)));
+    assert!(is_primitive_const(quote!(N > 10)));
+    assert!(is_primitive_const(quote!(!FLAG && C as u8 == 1)));
+    assert!(is_primitive_const(quote!(N % 2 == 0 || M < 'a')));
+    assert!(is_primitive_const(quote!(
+      Self::SIZE <= core::mem::size_of::<T>()
+    )));
+    assert!(!is_primitive_const(quote!(NAME == "foo")));
+    assert!(is_primitive_const(quote!(A == B)));
+    assert!(!is_primitive_const(quote!(Self::A == Self::B)));
+    assert!(!is_primitive_const(quote!(1.0 < F)));
+    assert!(!is_primitive_const(quote!(cond)));
+    assert!(!is_primitive_const(quote!(N.is_power_of_two())));
+    assert!(!is_primitive_const(quote!(foo(N) > 0)));
+  }
+
+  #[test]
+  fn test_warn_non_const() {
+    let tokens = parse_warn_non_const(quote!(N > 10)).unwrap();
+    assert_eq!(tokens.to_string(), "{ }");
+    let tokens = parse_warn_non_const(quote!(size_of::<T>() == ALIGN)).unwrap();
+    assert_eq!(tokens.to_string(), "{ }");
+    let tokens = parse_warn_non_const(quote!(n > 10)).unwrap().to_string();
+    assert!(tokens.contains("deprecated"));
+    assert!(tokens.contains("build_assert condition is not const-evaluable"));
+  }
+
+  #[test]
+  fn test_strict_assert_message() {
+    let tokens = parse_strict_assert(quote!(krate, N > 10, "N is too small")).unwrap();
+    assert!(tokens.to_string().contains("panic ! (\"N is too small\")"));
+    let tokens = parse_strict_assert(quote!(krate, N > 10, "N is {}", N)).unwrap();
+    assert!(tokens.to_string().contains("assertion failed: "));
+    let tokens = parse_strict_assert(quote!(krate, C < '\u{80}', "C is {:?}", C)).unwrap();
+    assert!(tokens
+      .to_string()
+      .contains("panic ! (\"{}\" , :: core :: concat ! (\"assertion failed: \""));
+    let tokens = parse_strict_assert(quote!(krate, n > 10, "n is {}", n)).unwrap();
+    assert!(tokens
+      .to_string()
+      .contains("krate :: build_error ! (\"n is {}\" , n)"));
+  }
+
+  fn eval(tokens: TokenStream2) -> Option<std::result::Result<(), String>> {
+    eval_cond(&syn::parse2(tokens).unwrap())
+  }
+
+  #[test]
+  fn test_eval_cond() {
+    assert_eq!(eval(quote!(2 + 2 == 4)), Some(Ok(())));
+    assert_eq!(eval(quote!((1 << 4) - 1 == 0xf && !false)), Some(Ok(())));
+    assert_eq!(eval(quote!(-3 % 2 < 0 || 1 / 0 == 0)), Some(Ok(())));
+    assert_eq!(
+      eval(quote!(2 + 2 == 5)),
+      Some(Err("  left: 4\n right: 5".into()))
+    );
+    assert_eq!(
+      eval(quote!((10 * 3 <= 7))),
+      Some(Err("  left: 30\n right: 7".into()))
+    );
+    assert_eq!(
+      eval(quote!(1 == 1 && 2 > 3)),
+      Some(Err("evaluated to `false`".into()))
+    );
+    assert_eq!(eval(quote!(N == 5)), None);
+    assert_eq!(eval(quote!(1 / 0 == 0)), None);
+    assert_eq!(eval(quote!(1 + 1)), None);
+    assert_eq!(eval(quote!(!0 == -1)), Some(Ok(())));
+    assert_eq!(eval(quote!(!0u8 == 255)), None);
+    assert_eq!(eval(quote!(1u8 << 9 == 512)), None);
+    assert_eq!(eval(quote!(1 << 32 == 0)), None);
+    assert_eq!(eval(quote!(65536 * 65536 == 0)), None);
+  }
+
+  #[test]
+  fn test_eval_assert() {
+    let tokens = parse_eval_assert(quote!(krate, 2 + 2 == 5)).unwrap();
+    let msg = tokens.to_string();
+    assert!(msg.contains("compile_error"));
+    assert!(msg.contains("assertion failed: 2 + 2 == 5\\n  left: 4\\n right: 5"));
+    let tokens = parse_eval_assert(quote!(krate, 2 > 3, "custom {}", 1)).unwrap();
+    assert!(tokens
+      .to_string()
+      .contains("custom {}\\n  left: 2\\n right: 3"));
+    let tokens = parse_eval_assert(quote!(krate, N > 3, "custom {}", N)).unwrap();
+    assert_eq!(
+      tokens.to_string(),
+      "krate :: build_assert ! (N > 3 , \"custom {}\" , N)"
+    );
+  }
+
+  #[test]
+  fn test_detailed_assert() {
+    let tokens = parse_detailed_assert(quote!(krate, 1 > 0 && 12 < 10 && 1 + 12 == 13)).unwrap();
+    let msg = tokens.to_string();
+    assert!(msg.contains("compile_error"));
+    assert!(
+      msg.contains("assertion failed: 12 < 10 (conjunct 2 of 3)\\n  left: 12\\n right: 10"),
+      "{msg}"
+    );
+    let tokens = parse_detailed_assert(quote!(krate, (1 > 0 && true) && 2 == 3, "custom")).unwrap();
+    assert!(tokens
+      .to_string()
+      .contains("custom\\nassertion failed: 2 == 3 (conjunct 3 of 3)\\n  left: 2\\n right: 3"));
+    let tokens = parse_detailed_assert(quote!(krate, A > 0 && 1 < 2 && A + B == C)).unwrap();
+    assert_eq!(
+      tokens.to_string(),
+      "{ krate :: build_assert ! (A > 0 , \"assertion failed: A > 0 (conjunct 1 of 3)\") ; \
+       krate :: build_assert ! (A + B == C , \"assertion failed: A + B == C (conjunct 3 of 3)\") ; }"
+    );
+    let tokens = parse_detailed_assert(quote!(krate, { N } > 0)).unwrap();
+    assert!(tokens
+      .to_string()
+      .contains("\"assertion failed: {{ N }} > 0 (conjunct 1 of 1)\""));
+  }
+
+  #[test]
+  fn test_build_assert_dyn_safe() {
+    let tokens = parse_build_assert_dyn_safe(quote!(AsRef<T> + Send)).unwrap();
+    assert!(tokens
+      .to_string()
+      .contains("let _ : :: core :: option :: Option < & (dyn AsRef < T > + Send) >"));
+    assert!(parse_build_assert_dyn_safe(quote!()).is_err());
+    assert!(parse_build_assert_dyn_safe(quote!(Send, Sync)).is_err());
+  }
+
+  #[test]
+  fn test_empty_bounds() {
+    assert!(parse_build_assert_impl(quote!(i32:)).is_err());
+    assert!(parse_build_assert_satisfies(quote!(i32, "msg",)).is_err());
+  }
+
+  #[test]
+  fn test_build_assert_satisfies() {
+    let tokens = parse_build_assert_satisfies(quote!(T, "must be Copy for SIMD", ?Sized + Copy))
+      .unwrap()
+      .to_string();
+    assert!(tokens.contains(
+      "on_unimplemented (message = \"unsatisfied bounds for `{Self}`: must be Copy for SIMD\" , \
+       label = \"must be Copy for SIMD\")"
+    ));
+    assert!(tokens.contains("__T : Copy"));
+    assert!(!tokens.contains("? Sized + Copy"));
+    let tokens = parse_build_assert_satisfies(quote!(T, "{T} must be Copy", Copy)).unwrap();
+    assert!(tokens
+      .to_string()
+      .contains("label = \"{{T}} must be Copy\""));
+    assert!(parse_build_assert_satisfies(quote!(T, Copy)).is_err());
+  }
+
+  #[test]
+  fn test_build_uniq_sym() {
+    let syms: Vec<_> = (0..4)
+      .map(|_| parse_build_uniq_sym(quote!()).unwrap().to_string())
+      .collect();
+    for (i, sym) in syms.iter().enumerate() {
+      assert!(sym.starts_with("__build_uniq_sym_"));
+      assert!(!syms[i + 1..].contains(sym));
+    }
+    let tokens = parse_build_uniq_sym(quote!(prefix => crate::m)).unwrap();
+    assert!(tokens.to_string().starts_with("crate :: m ! (prefix_"));
+    assert!(parse_build_uniq_sym(quote!(=>)).is_err());
+    let tokens = parse_build_uniq_sym(quote!(prefix "BA0007")).unwrap();
+    assert!(tokens.to_string().starts_with("prefix_BA0007_"));
+    let err = parse_build_uniq_sym(quote!(prefix "BA-0007")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "tag `BA-0007` can only contain ASCII alphanumerics and underscores"
+    );
+  }
+
+  #[test]
+  fn test_tag_prefix() {
+    assert_eq!(tag_prefix(""), Ok(String::new()));
+    assert_eq!(tag_prefix("BA"), Ok("[BA] ".into()));
+    assert_eq!(tag_prefix("my_lint_2"), Ok("[my_lint_2] ".into()));
+    assert_eq!(
+      tag_prefix("{BA}"),
+      Err(
+        "`BUILD_ERROR_TAG` value `{BA}` can only contain ASCII alphanumerics and underscores"
+          .into()
+      )
+    );
+    assert!(parse_error_tag(quote!(x)).is_err());
+  }
+
+  #[cfg(feature = "collect_sites")]
+  #[test]
+  fn test_json_str() {
+    assert_eq!(json_str("N > 5"), r#""N > 5""#);
+    assert_eq!(json_str("s == \"a\\b\"\n"), r#""s == \"a\\b\"\n""#);
+    assert_eq!(json_str("\u{1}"), r#""\u0001""#);
+  }
+
+  #[cfg(feature = "collect_sites")]
+  #[test]
+  fn test_dump_site() {
+    let path = std::env::temp_dir().join(format!("build_assert_dump_{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let site = |line, cond: &str| Site {
+      file: "src/main.rs".into(),
+      line,
+      column: 3,
+      cond: cond.into(),
+    };
+    dump_site(&path, &site(2, "N > 5")).unwrap();
+    dump_site(&path, &site(3, "s == \"a\"")).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+      content,
+      concat!(
+        r#"{"file":"src/main.rs","line":2,"column":3,"condition":"N > 5"}"#,
+        "\n",
+        r#"{"file":"src/main.rs","line":3,"column":3,"condition":"s == \"a\""}"#,
+        "\n",
+      )
+    );
+  }
+}
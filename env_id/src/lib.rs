@@ -38,100 +38,2828 @@
 //!
 //! env_id!("HELLO"?: hello => def_const);
 //! ```
+//!
+//! The macro can also be referred to by a path, e.g. if it is defined in
+//! another module:
+//!
+//! ```
+//! # fn main() {}
+//! # use env_id::env_id;
+//! mod gen {
+//!   macro_rules! def_const {
+//!     ($id:ident) => {
+//!       pub const $id: usize = 1;
+//!     };
+//!   }
+//!   pub(crate) use def_const;
+//! }
+//!
+//! env_id!("HELLO" ?: hello => crate::gen::def_const);
+//! const _: usize = hello;
+//! ```
+//!
+//! A prefix can be removed from the value before it is used:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // `CARGO_PKG_NAME` is `env_id`.
+//! let env_id!("CARGO_PKG_NAME" strip_prefix "env_") = 1;
+//! assert_eq!(id, 1);
+//! ```
+//!
+//! The value can also be used as a lifetime:
+//!
+//! ```
+//! # use env_id::env_id;
+//! macro_rules! def_struct {
+//!   ($lt:lifetime) => {
+//!     struct Foo<$lt>(&$lt str);
+//!   };
+//! }
+//!
+//! env_id!("LIFETIME" ?: a as lifetime => def_struct);
+//! let _ = Foo("hello");
+//! ```
+//!
+//! Integer values can be used as integer literals, optionally validated to be
+//! in the given range:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // `CARGO_PKG_VERSION_MINOR` is `0`.
+//! const LEVEL: u8 = env_id!("CARGO_PKG_VERSION_MINOR" as int in 0..=9);
+//! assert_eq!(LEVEL, 0);
+//! ```
+//!
+//! Multiple environment variables can be joined into a single identifier with
+//! a separator, each of them can have its own default value:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // `CARGO_PKG_NAME` is `env_id`.
+//! let env_id!(join "_" of "MODULE" ?: app, "CARGO_PKG_NAME") = 1;
+//! assert_eq!(app_env_id, 1);
+//! ```
+//!
+//! Free-form values can be coerced into identifiers with `as Ident`, which
+//! replaces characters that are invalid in an identifier with `_`, and
+//! prepends `_` if the value starts with a digit. Use `as Ident(collapse)` to
+//! also collapse consecutive underscores:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // `CARGO_PKG_DESCRIPTION` is `Use environment variables as identifiers.`.
+//! let env_id!("CARGO_PKG_DESCRIPTION" as Ident) = 1;
+//! assert_eq!(Use_environment_variables_as_identifiers_, 1);
+//! ```
+//!
+//! Values can be turned into enum variants with `as Variant`, which converts
+//! the value to `PascalCase`, and uses a raw identifier if the result is a
+//! keyword:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // `CARGO_PKG_NAME` is `env_id`.
+//! macro_rules! def_enum {
+//!   ($id:ident) => {
+//!     enum Package {
+//!       $id,
+//!     }
+//!   };
+//! }
+//!
+//! env_id!("CARGO_PKG_NAME" as Variant => def_enum);
+//! let _ = Package::EnvId;
+//! ```
+//!
+//! If the value is used as a type name, add the hint `as type`, which still
+//! produces an identifier, but emits a warning if the value does not start
+//! with an uppercase letter, since misconfigured type names usually lead to
+//! confusing errors. Use `as type strict` to report an error instead:
+//!
+//! ```
+//! # use ::env_id::env_id;
+//! // `CARGO_PKG_NAME` is `env_id`, which is not a type name.
+//! #[allow(non_camel_case_types)]
+//! struct env_id;
+//!
+//! // Warns that `env_id` does not start with an uppercase letter.
+//! let _: env_id!("CARGO_PKG_NAME" as type) = env_id;
+//! ```
+//!
+//! ```compile_fail
+//! # use ::env_id::env_id;
+//! # #[allow(non_camel_case_types)]
+//! # struct env_id;
+//! let _: env_id!("CARGO_PKG_NAME" as type strict) = env_id;
+//! ```
+//!
+//! Values can also be transformed by a pipeline of stages, which are applied
+//! from left to right: `Trim`, `Lower`, `Upper`, `SnakeCase`, `Prefix("...")`
+//! and `Suffix("...")`. The final result must be a valid identifier:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // `CARGO_PKG_NAME` is `env_id`.
+//! let env_id!("CARGO_PKG_NAME" as Upper | Prefix("get_")) = 1;
+//! assert_eq!(get_ENV_ID, 1);
+//! let env_id!("CARGO_PKG_NAME" as Trim | SnakeCase | Suffix("_v2")) = 2;
+//! assert_eq!(env_id_v2, 2);
+//! ```
+//!
+//! `Lower` and `Upper` only change the case of ASCII letters, so the result
+//! is deterministic and does not depend on Unicode case mappings (e.g.
+//! `Straße` becomes `STRAßE`). Unlike `SnakeCase`, they do not care about
+//! word boundaries.
+//!
+//! Values passed through shells may be encoded to survive escaping. Use
+//! `decode base64` (standard alphabet, padding is optional) or `decode hex`
+//! to decode the value before it is used. The decoded value must be valid
+//! UTF-8, and default identifiers given in the macro input are not decoded:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // Environment variable `ENC_NAME` is set to `68656c6c6f` (`hello`).
+//! let env_id!("ENC_NAME" ?: hello decode hex) = 1;
+//! assert_eq!(hello, 1);
+//! ```
+//!
+//! Specific characters or substrings can be mapped with a replacement table,
+//! whose entries are applied to the value in order, so an entry also sees
+//! the results of the previous ones. Default identifiers given in the macro
+//! input are not replaced:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // `CARGO_PKG_DESCRIPTION` is `Use environment variables as identifiers.`.
+//! let env_id!("CARGO_PKG_DESCRIPTION" replace { "." => "_dot", " " => "_" }) = 1;
+//! assert_eq!(Use_environment_variables_as_identifiers_dot, 1);
+//! ```
+//!
+//! The result must be a valid identifier:
+//!
+//! ```compile_fail
+//! # use env_id::env_id;
+//! // Error: value `Use_environment_variables_as_identifiers.` is not a valid identifier.
+//! let env_id!("CARGO_PKG_DESCRIPTION" replace { " " => "_" }) = 1;
+//! ```
+//!
+//! To enforce naming conventions, the value can be checked against a pattern
+//! with `matches` before it is used. Patterns support a minimal subset of
+//! regular expressions: literal characters, character classes (`[a-z0-9_]`,
+//! or negated `[^-]`), `*` and `+` after a character or class, `^` at the
+//! start and `$` at the end to anchor the match, and `\` to escape special
+//! characters. Default identifiers given in the macro input are not checked:
+//!
+//! ```
+//! # use env_id::env_id;
+//! // `CARGO_PKG_NAME` is `env_id`.
+//! let env_id!("CARGO_PKG_NAME" matches "^[a-z][a-z0-9_]*$") = 1;
+//! assert_eq!(env_id, 1);
+//! ```
+//!
+//! ```compile_fail
+//! # use env_id::env_id;
+//! // Error: value `env_id` does not match pattern `^[A-Z]+$`.
+//! let env_id!("CARGO_PKG_NAME" matches "^[A-Z]+$") = 1;
+//! ```
+//!
+//! For the names Cargo provides, there are shorthands that also turn the
+//! value into a valid identifier (e.g. `my-crate` becomes `my_crate`):
+//!
+//! ```
+//! # use env_id::pkg_name_id;
+//! let pkg_name_id!() = 1;
+//! assert_eq!(env_id, 1);
+//! ```
+//!
+//! The default value can also be derived from the name of the environment
+//! variable, using `self_lower` or `self_upper`:
+//!
+//! ```
+//! # use env_id::env_id;
+//! let env_id!("HELLO" ?: self_lower) = 1;
+//! assert_eq!(hello, 1);
+//! ```
+//!
+//! Or be produced by another macro, whose invocation is emitted in place of
+//! the identifier if the environment variable is not set. Since `env_id` can
+//! not expand the macro, the default can only be used where macros are
+//! allowed (e.g. expressions and patterns), and can not be passed to an
+//! apply-to macro or used as a lifetime:
+//!
+//! ```
+//! # use env_id::env_id;
+//! fn env_id() -> usize { 1 }
+//! fn fallback() -> usize { 2 }
+//!
+//! macro_rules! gen_default {
+//!   () => { fallback };
+//! }
+//!
+//! // `CARGO_PKG_NAME` is `env_id`.
+//! assert_eq!(env_id!("CARGO_PKG_NAME" ?: gen_default!())(), 1);
+//! assert_eq!(env_id!("HELLO" ?: gen_default!())(), 2);
+//! ```
+//!
+//! Use `_` as the default value to skip the environment variable if it is
+//! not set. If nothing is left, the macro expands to nothing, and the
+//! apply-to macro is not invoked:
+//!
+//! ```
+//! # fn main() {}
+//! # use env_id::env_id;
+//! macro_rules! def_const {
+//!   ($id:ident) => {
+//!     pub const $id: usize = 1;
+//!   };
+//! }
+//!
+//! // Defines nothing.
+//! env_id!("ENV_ID_SURELY_NOT_SET" ?: _ => def_const);
+//! ```
+//!
+//! Multiple identifiers can be passed to a macro at once, each environment
+//! variable can have its own default value:
+//!
+//! ```
+//! # use env_id::env_id;
+//! macro_rules! build_table {
+//!   ($($id:ident),*) => {
+//!     const TABLE: &[&str] = &[$(stringify!($id)),*];
+//!   };
+//! }
+//!
+//! env_id!(["A_ID" ?: a, "B_ID" ?: b, "C_ID" ?: c] => build_table);
+//! assert_eq!(TABLE, ["a", "b", "c"]);
+//! ```
+//!
+//! Environment variables can also be used as documentation strings:
+//!
+//! ```
+//! # use env_id::env_doc;
+//! #[doc = env_doc!("DESCRIPTION" ?: "no description")]
+//! struct Foo;
+//! ```
+//!
+//! Or as byte strings, which is useful for embedding configurations into
+//! firmware. Non-ASCII and control bytes are escaped properly:
+//!
+//! ```
+//! # use env_id::env_bytes;
+//! const KEY: &[u8] = env_bytes!("KEY" ?: "");
+//! ```
+//!
+//! Each identifier can also carry a discriminant, which is useful for
+//! generating enum variants. Discriminants of a list are auto-incremented
+//! from the given value:
+//!
+//! ```
+//! # use env_id::env_id;
+//! macro_rules! def_enum {
+//!   ($($id:ident = $value:literal),*) => {
+//!     #[allow(non_camel_case_types)]
+//!     enum Variant { $($id = $value),* }
+//!   };
+//! }
+//!
+//! env_id!(["A_ID" ?: a, "B_ID" ?: b, "C_ID" ?: c], discriminant 1 => def_enum);
+//! assert_eq!(Variant::c as u8, 3);
+//! ```
+//!
+//! The apply-to macro can also receive the provenance of each identifier,
+//! i.e. the original value as a string literal and the name of the
+//! environment variable, which is useful for generating registration code
+//! that logs where identifiers come from. For default identifiers, the value
+//! is the identifier itself:
+//!
+//! ```
+//! # use env_id::env_id;
+//! macro_rules! register {
+//!   ($($id:ident, $value:literal, $name:literal),*) => {
+//!     const SOURCES: &[(&str, &str, &str)] = &[$((stringify!($id), $value, $name)),*];
+//!   };
+//! }
+//!
+//! env_id!(["CARGO_PKG_NAME", "B_ID" ?: b], provenance => register);
+//! assert_eq!(SOURCES[0], ("env_id", "env_id", "CARGO_PKG_NAME"));
+//! assert_eq!(SOURCES[1], ("b", "b", "B_ID"));
+//! ```
+//!
+//! An identifier can also be chosen by whether a Cargo feature is enabled,
+//! i.e. whether `CARGO_FEATURE_<FEATURE>` is set:
+//!
+//! ```
+//! # use env_id::env_id;
+//! fn fast_impl() {}
+//! fn slow_impl() {}
+//!
+//! env_id!(feature_of "fast": fast_impl ?: slow_impl)();
+//! ```
+//!
+//! Note that Cargo only sets `CARGO_FEATURE_<FEATURE>` for build scripts, so
+//! you need to forward it to the compiler in your build script:
+//!
+//! ```text
+//! // build.rs
+//! fn main() {
+//!   if std::env::var_os("CARGO_FEATURE_FAST").is_some() {
+//!     println!("cargo:rustc-env=CARGO_FEATURE_FAST=1");
+//!   }
+//! }
+//! ```
+//!
+//! Use `if_set` to choose between two identifiers by whether an
+//! environment variable is set, regardless of its value:
+//!
+//! ```
+//! # use env_id::env_id;
+//! fn debug_impl() {}
+//! fn release_impl() {}
+//!
+//! env_id!(if_set "DEBUG_MODE" ? debug_impl : release_impl)();
+//! ```
+//!
+//! Similarly, the value of a configuration option of the target can be used
+//! as an identifier, i.e. the value of `CARGO_CFG_<OPTION>`, which is useful
+//! for generating per-target names:
+//!
+//! ```
+//! # use env_id::env_id;
+//! # fn main() {}
+//! macro_rules! def_const {
+//!   ($id:ident) => {
+//!     pub const $id: usize = 1;
+//!   };
+//! }
+//!
+//! env_id!(cfg "target_arch" ?: unknown => def_const);
+//! ```
+//!
+//! Cargo only sets `CARGO_CFG_<OPTION>` for build scripts too, so you need to
+//! forward it in your build script:
+//!
+//! ```text
+//! // build.rs
+//! fn main() {
+//!   let arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+//!   println!("cargo:rustc-env=CARGO_CFG_TARGET_ARCH={arch}");
+//! }
+//! ```
+//!
+//! By default, the generated identifier has the span of the literal string
+//! (or the default identifier). You can use `@call_site` or `@mixed_site` to
+//! change its span, which affects hygiene and error locations:
+//!
+//! ```
+//! # use env_id::env_id;
+//! let env_id!("HELLO" ?: hello @call_site) = 1;
+//! dbg!(hello);
+//! ```
+//!
+//! Def-site spans are not available, since they are unstable.
+//!
+//! # Environment Files
+//!
+//! Values can also be read from a file named `build_id.env` in the directory
+//! of the package being built (i.e. `CARGO_MANIFEST_DIR`). Put `file` before
+//! the environment variables to enable it:
+//!
+//! ```text
+//! # This is a comment.
+//! HELLO=hello
+//! ```
+//!
+//! ```
+//! # use env_id::env_id;
+//! let env_id!(file "HELLO" ?: hello) = 1;
+//! dbg!(hello);
+//! ```
+//!
+//! Each line of the file is in the form of `KEY=VALUE`, empty lines and lines
+//! starting with `#` are ignored. If the file does not exist or the variable
+//! is not defined in the file, the value is read from the process
+//! environment. The file is read only once per process.
+//!
+//! Proc macros can not tell Cargo to rebuild when a file changes, so you may
+//! want to add a build script to your package:
+//!
+//! ```text
+//! // build.rs
+//! fn main() {
+//!   println!("cargo:rerun-if-changed=build_id.env");
+//! }
+//! ```
+//!
+//! # Strict Mode
+//!
+//! If the environment variable `BUILD_ID_STRICT` is set when building, all
+//! values resolved by [`env_id`] are recorded, and the macro reports an error
+//! if the same environment variable resolves to different values.
+//!
+//! Values read from the environment file are recorded separately from the
+//! process environment, so `env_id!(file "A")` and `env_id!("A")` may resolve
+//! to different values.
+//!
+//! Note that proc-macro states are per-process, so values can only be checked
+//! within a single compilation (e.g. a single crate), not across crates.
+//!
+//! # Manifest
+//!
+//! If the environment variable `BUILD_ID_MANIFEST` is set to a path when
+//! building, every environment variable resolved by [`env_id`], [`env_id_try`]
+//! and [`env_id_runtime`] is appended to the file as a line of `NAME=VALUE`,
+//! which lets build tools record the environment that influenced code
+//! generation, and verify the reproducibility of builds. Other macros of this
+//! crate do not record their variables. Backslashes and line breaks in values are
+//! escaped as `\\`, `\n` and `\r`. Default identifiers are not recorded.
+//! Values read from the environment file are recorded as `file:NAME=VALUE`.
+//!
+//! The file is locked while appending, so it can be shared by crates compiled
+//! in parallel. Lines are appended on every expansion, so a variable may be
+//! recorded more than once.
+//!
+//! Note that the manifest is advisory: Cargo does not know the file, and
+//! cached expansions are not recorded again if the crate is not rebuilt. So
+//! you may want to remove the file and do a clean build before collecting
+//! it.
+//!
+//! # Errors
+//!
+//! If the environment variable is not set and no default value is provided,
+//! the macro reports an error:
+//!
+//! ```compile_fail
+//! # use env_id::env_id;
+//! let env_id!("ENV_ID_SURELY_NOT_SET") = 1;
+//! ```
+//!
+//! Use `?!` instead of `?:` to report a custom message if the environment
+//! variable is required:
+//!
+//! ```compile_fail
+//! # use env_id::env_id;
+//! let env_id!("ENV_ID_SURELY_NOT_SET" ?! "set ENV_ID_SURELY_NOT_SET to your widget type") = 1;
+//! // error: set ENV_ID_SURELY_NOT_SET to your widget type
+//! ```
+//!
+//! Values that are path keywords (`Self`, `crate`, `super` and `self`) can
+//! not be used as plain identifiers, the macro reports an error for them too.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned, ToTokens};
+use std::{
+  collections::HashMap,
+  env::VarError,
+  fs, io,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
+use syn::{
+  braced, bracketed, parenthesized,
+  parse::{Parse, ParseStream},
+  punctuated::Punctuated,
+  token, Error, Ident, Lifetime, LitByteStr, LitInt, LitStr, Macro, Result, Token, Visibility,
+};
+
+/// Uses the given environment variable as an identifier.
+///
+/// See the [module-level documentation](self) for more information.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! env_id {
+///   ($name:literal) => { ... };
+///   ($name:literal ?: $default_id:ident) => { ... };
+///   ($name:literal ?: $default_id:ident => $apply_to:path) => { ... };
+///   ($name:literal ?: _ $(=> $apply_to:path)?) => { ... };
+///   ($name:literal ?! $message:literal $($rest:tt)*) => { ... };
+///   ($name:literal ?: $($default:ident)::+!($($args:tt)*) $(as $($mode:tt)+)?) => { ... };
+///   ([$($name:literal $(?: $default_id:ident)?),*] => $apply_to:path) => { ... };
+///   ($name:literal $(?: $default_id:ident)? as lifetime $(=> $apply_to:path)?) => { ... };
+///   ($name:literal $(?: $default_id:ident)? as Ident $((collapse))? $(=> $apply_to:path)?) => { ... };
+///   ($name:literal as int $(in $range:expr)? $(=> $apply_to:path)?) => { ... };
+///   ($name:literal $(?: $default_id:ident)? as Variant $(=> $apply_to:path)?) => { ... };
+///   ($name:literal $(?: $default_id:ident)? as type $(strict)? $(=> $apply_to:path)?) => { ... };
+///   ($name:literal $(?: $default_id:ident)? as $($stage:ident $(($arg:literal))?)|+ $(=> $apply_to:path)?) => { ... };
+///   ($name:literal $(?: $default_id:ident)? strip_prefix $prefix:literal $(as lifetime)? $(=> $apply_to:path)?) => { ... };
+///   ($name:literal $(?: $default_id:ident)? decode $decoding:ident $($rest:tt)*) => { ... };
+///   ($name:literal $(?: $default_id:ident)? $(strip_prefix $prefix:literal)? replace { $($from:literal => $to:literal),* } $($rest:tt)*) => { ... };
+///   ($name:literal $(?: $default_id:ident)? matches $pattern:literal $(as $($mode:tt)+)? $(=> $apply_to:path)?) => { ... };
+///   ($name:literal $(?: $default_id:ident)?, discriminant $value:literal => $apply_to:path) => { ... };
+///   ([$($name:literal $(?: $default_id:ident)?),*], discriminant $value:literal => $apply_to:path) => { ... };
+///   ($name:literal $(?: $default_id:ident)?, provenance => $apply_to:path) => { ... };
+///   ([$($name:literal $(?: $default_id:ident)?),*], provenance => $apply_to:path) => { ... };
+///   ($name:literal $(?: $default_id:ident)? @call_site $($rest:tt)*) => { ... };
+///   ($name:literal $(?: $default_id:ident)? @mixed_site $($rest:tt)*) => { ... };
+///   (feature_of $feature:literal: $id:ident $($rest:tt)*) => { ... };
+///   (if_set $name:literal ? $set_id:ident : $unset_id:ident $($rest:tt)*) => { ... };
+///   (cfg $cfg:literal $($rest:tt)*) => { ... };
+///   (join $sep:literal of $($name:literal $(?: $default_id:ident)?),+ $($rest:tt)*) => { ... };
+///   (file $($rest:tt)*) => { ... };
+/// }
+/// ```
+///
+/// A trailing comma is allowed at the end of the input, and after the last
+/// environment variable of `join`. Each clause can appear at most once, in
+/// the order shown above.
+#[proc_macro]
+pub fn env_id(tokens: TokenStream) -> TokenStream {
+  match parse_env_id(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `env_id` macro.
+fn parse_env_id(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let env_id: EnvId = syn::parse2(tokens)?;
+  // Get values of the environment variables.
+  let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR");
+  let file_dir = manifest_dir
+    .as_deref()
+    .filter(|_| env_id.from_file)
+    .map(Path::new);
+  let (values, sources): (Vec<_>, Vec<_>) = match env_id.vars {
+    EnvVars::Single(var) => resolve_with_sources([var], file_dir)?,
+    EnvVars::List(vars, _) => resolve_with_sources(vars, file_dir)?,
+    EnvVars::Join(sep, vars) => join(&sep, vars, file_dir)?
+      .into_iter()
+      .map(|v| (v, String::new()))
+      .unzip(),
+  };
+  // Emit nothing if all values are skipped.
+  if values.is_empty() {
+    return Ok(TokenStream2::new());
+  }
+  // Record the original values for provenance.
+  let sources: Vec<_> = values
+    .iter()
+    .zip(sources)
+    .map(|(v, name)| (v.original(), name))
+    .collect();
+  // Set spans of values.
+  let values = values
+    .into_iter()
+    .map(|v| v.with_span(&env_id.span))
+    .collect::<Vec<_>>();
+  // Decode values.
+  let values = match &env_id.decode {
+    Some(decode) => values
+      .into_iter()
+      .map(|v| v.decode(decode))
+      .collect::<Result<_>>()?,
+    None => values,
+  };
+  // Strip prefixes of values.
+  let values = match &env_id.strip_prefix {
+    Some(prefix) => values
+      .into_iter()
+      .map(|v| v.strip_prefix(prefix))
+      .collect::<Result<_>>()?,
+    None => values,
+  };
+  // Replace characters of values.
+  let values = match &env_id.replace {
+    Some(replace) => values.into_iter().map(|v| v.replace(replace)).collect(),
+    None => values,
+  };
+  // Check values against the pattern.
+  if let Some(pattern) = &env_id.pattern {
+    for value in &values {
+      pattern.check(value)?;
+    }
+  }
+  // Macro defaults can not be matched by the apply-to macro.
+  if let (Some(_), Some(Value::Macro(mac))) = (
+    &env_id.apply_to,
+    values.iter().find(|v| matches!(v, Value::Macro(_))),
+  ) {
+    return Err(macro_default_error(mac, "passed to an apply-to macro"));
+  }
+  // Collect warnings of the type hint.
+  let warnings: Vec<_> = values
+    .iter()
+    .filter_map(|v| env_id.mode.type_warning(v))
+    .collect();
+  // Convert values to tokens.
+  let tokens = values
+    .into_iter()
+    .map(|v| env_id.mode.convert(v))
+    .collect::<Result<Vec<_>>>()?;
+  // Attach discriminants to tokens.
+  let tokens = match &env_id.discriminant {
+    Some(d) => d.attach(tokens)?,
+    None => tokens,
+  };
+  // Attach provenance to tokens.
+  let tokens = match &env_id.provenance {
+    Some(p) => p.attach(tokens, sources),
+    None => tokens,
+  };
+  // Generate result.
+  Ok(match (&warnings[..], env_id.apply_to) {
+    ([], apply_to) => apply(&tokens, apply_to),
+    // Emit warnings as items before invoking the apply-to macro.
+    (warnings, Some(apply_to)) => {
+      let result = apply(&tokens, Some(apply_to));
+      quote!(#(const _: usize = #warnings;)* #result)
+    }
+    // Without an apply-to macro, there is only one value. Wrap it in a
+    // projection that resolves to the same type, so that the warning can be
+    // emitted in type position.
+    ([warning, ..], None) => {
+      let ty = &tokens[0];
+      quote!(<[#ty; #warning] as ::core::iter::IntoIterator>::Item)
+    }
+  })
+}
+
+/// Resolves the given environment variables, returns their values along with
+/// the names of the environment variables they are read from. Variables
+/// skipped by the default `_` are omitted.
+fn resolve_with_sources(
+  vars: impl IntoIterator<Item = EnvVar>,
+  file_dir: Option<&Path>,
+) -> Result<(Vec<Value>, Vec<String>)> {
+  let mut values = vec![];
+  let mut sources = vec![];
+  for var in vars {
+    let source = var.source();
+    if let Some(value) = var.resolve(file_dir)? {
+      values.push(value);
+      sources.push(source);
+    }
+  }
+  Ok((values, sources))
+}
+
+/// Returns the message for a value that is used as a type name but does not
+/// start with an uppercase letter, or `None` if the value looks like a type.
+fn type_name_message(value: &str) -> Option<String> {
+  if value.chars().next().is_some_and(char::is_uppercase) {
+    None
+  } else {
+    Some(format!(
+      "value `{value}` is used as a type, but does not start with an uppercase letter"
+    ))
+  }
+}
+
+/// Returns an error for a macro default that can not be used in the given
+/// way, since its expansion is not available to `env_id`.
+fn macro_default_error(mac: &Macro, usage: &str) -> Error {
+  Error::new_spanned(
+    mac,
+    format!(
+      "macro default can not be {usage}, since `env_id` can not expand it; \
+       use an identifier instead"
+    ),
+  )
+}
+
+/// Uses the given environment variable as a documentation string.
+///
+/// The macro expands to a string literal, so it can be used as the value of
+/// a `doc` attribute.
+///
+/// # Examples
+///
+/// ```
+/// # use env_id::env_doc;
+/// #[doc = env_doc!("CARGO_PKG_NAME")]
+/// struct Foo;
+///
+/// #[doc = env_doc!("DESCRIPTION" ?: "no description")]
+/// struct Bar;
+/// ```
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! env_doc {
+///   ($name:literal) => { ... };
+///   ($name:literal ?: $default:literal) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn env_doc(tokens: TokenStream) -> TokenStream {
+  match parse_env_doc(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `env_doc` macro.
+fn parse_env_doc(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let env_doc: EnvDoc = syn::parse2(tokens)?;
+  // Get value of the environment variable.
+  let doc = match std::env::var(env_doc.name.value()) {
+    Ok(value) => LitStr::new(&value, env_doc.name.span()),
+    Err(e) => match env_doc.default {
+      Some(default) => default,
+      None => return Err(var_error(&env_doc.name, e, "\"text\"")),
+    },
+  };
+  Ok(doc.into_token_stream())
+}
+
+/// Uses the given environment variable as a byte string.
+///
+/// The macro expands to a byte string literal (`b"..."`), non-ASCII and
+/// control bytes are escaped, which is useful for embedding configurations
+/// into firmware. Like [`env_doc`], the default value is a string literal.
+///
+/// # Examples
+///
+/// ```
+/// # use env_id::env_bytes;
+/// // `CARGO_PKG_NAME` is `env_id`.
+/// const NAME: &[u8] = env_bytes!("CARGO_PKG_NAME");
+/// assert_eq!(NAME, b"env_id");
+///
+/// const DATA: &[u8] = env_bytes!("DATA" ?: "");
+/// assert_eq!(DATA, b"");
+/// ```
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! env_bytes {
+///   ($name:literal) => { ... };
+///   ($name:literal ?: $default:literal) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn env_bytes(tokens: TokenStream) -> TokenStream {
+  match parse_env_bytes(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `env_bytes` macro.
+fn parse_env_bytes(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let env_bytes: EnvDoc = syn::parse2(tokens)?;
+  // Get value of the environment variable.
+  let (value, span) = match std::env::var(env_bytes.name.value()) {
+    Ok(value) => (value, env_bytes.name.span()),
+    Err(e) => match env_bytes.default {
+      Some(default) => (default.value(), default.span()),
+      None => return Err(var_error(&env_bytes.name, e, "\"bytes\"")),
+    },
+  };
+  Ok(LitByteStr::new(value.as_bytes(), span).into_token_stream())
+}
+
+/// Splits the given environment variable into an array of string literals.
+///
+/// The value is split on the delimiter (`,` by default), and each element is
+/// trimmed. An empty value produces an empty array.
+///
+/// # Examples
+///
+/// ```
+/// # use env_id::env_list;
+/// // `CARGO_PKG_AUTHORS` is `MaxXing <x@MaxXSoft.net>`.
+/// const AUTHORS: [&str; 1] = env_list!("CARGO_PKG_AUTHORS" sep ":");
+/// assert_eq!(AUTHORS, ["MaxXing <x@MaxXSoft.net>"]);
+///
+/// const PLUGINS: &[&str] = &env_list!("PLUGINS" ?: "a, b");
+/// assert_eq!(PLUGINS, ["a", "b"]);
+/// ```
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! env_list {
+///   ($name:literal $(?: $default:literal)? $(sep $sep:literal)?) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn env_list(tokens: TokenStream) -> TokenStream {
+  match parse_env_list(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `env_list` macro.
+fn parse_env_list(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let env_list: EnvList = syn::parse2(tokens)?;
+  // Get value of the environment variable.
+  let value = match std::env::var(env_list.name.value()) {
+    Ok(value) => value,
+    Err(e) => match &env_list.default {
+      Some(default) => default.value(),
+      None => return Err(var_error(&env_list.name, e, "\"a,b\"")),
+    },
+  };
+  // Split the value.
+  let sep = env_list.sep.map_or_else(|| ",".into(), |s| s.value());
+  let span = env_list.name.span();
+  let items = split_list(&value, &sep)
+    .into_iter()
+    .map(|item| LitStr::new(item, span));
+  Ok(quote!([#(#items),*]))
+}
+
+/// Splits the given value on the delimiter, and trims each element.
+fn split_list<'a>(value: &'a str, sep: &str) -> Vec<&'a str> {
+  if value.trim().is_empty() {
+    Vec::new()
+  } else {
+    value.split(sep).map(str::trim).collect()
+  }
+}
+
+/// Uses the given environment variable as an identifier if it is set,
+/// otherwise expands to nothing.
+///
+/// Unlike [`env_id`], this macro never reports an error for a missing
+/// environment variable. With an apply-to macro, the macro is called with the
+/// identifier if the environment variable is set, or with no arguments if it
+/// is not, so the apply-to macro can match on both cases to generate optional
+/// items.
+///
+/// # Examples
+///
+/// ```
+/// # use env_id::env_id_try;
+/// macro_rules! def_fn {
+///   () => {};
+///   ($id:ident) => {
+///     fn $id() -> usize { 1 }
+///   };
+/// }
+///
+/// // `CARGO_PKG_NAME` is `env_id`, so function `env_id` is defined.
+/// env_id_try!("CARGO_PKG_NAME" => def_fn);
+/// // Defines nothing.
+/// env_id_try!("ENV_ID_SURELY_NOT_SET" => def_fn);
+///
+/// assert_eq!(env_id(), 1);
+/// ```
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! env_id_try {
+///   ($name:literal) => { ... };
+///   ($name:literal => $apply_to:path) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn env_id_try(tokens: TokenStream) -> TokenStream {
+  match parse_env_id_try(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `env_id_try` macro.
+fn parse_env_id_try(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let EnvIdTry { name, apply_to } = syn::parse2(tokens)?;
+  // Get value of the environment variable.
+  let ident = match std::env::var(name.value()) {
+    Ok(value) => Some(value_ident(&name, &value)?),
+    Err(VarError::NotPresent) => None,
+    Err(e) => return Err(var_error(&name, e, "ident")),
+  };
+  // Generate result.
+  Ok(apply(ident.as_slice(), apply_to))
+}
+
+/// Defines a function named by the given environment variable at build
+/// time, which returns the value of the environment variable at runtime.
+///
+/// The name of the function is resolved in the same way as [`env_id`], so it
+/// is fixed at build time. The function looks up the environment variable
+/// when it is called for the first time, and falls back to the value at build
+/// time (if any). The result is cached, so later changes of the environment
+/// are not observed. The generated function uses `std`.
+///
+/// # Examples
+///
+/// ```
+/// # use env_id::env_id_runtime;
+/// // `CARGO_PKG_NAME` is `env_id` at build time, so function `env_id` is
+/// // defined, which returns the value at runtime, or `env_id` if it is not
+/// // set at runtime.
+/// env_id_runtime!("CARGO_PKG_NAME");
+/// assert!(env_id().is_some());
+///
+/// // The environment variable is not set at build time, so the default
+/// // identifier is used, and the value is only available at runtime.
+/// env_id_runtime!(pub "ENV_ID_RUNTIME_ONLY" ?: runtime_only);
+/// std::env::set_var("ENV_ID_RUNTIME_ONLY", "hello");
+/// assert_eq!(runtime_only(), Some("hello"));
+/// ```
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! env_id_runtime {
+///   ($(pub $(($($restriction:tt)+))?)? $name:literal $(?: $default_id:ident)?) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn env_id_runtime(tokens: TokenStream) -> TokenStream {
+  match parse_env_id_runtime(tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses the `env_id_runtime` macro.
+fn parse_env_id_runtime(tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let EnvIdRuntime { vis, name, default } = syn::parse2(tokens)?;
+  // Get the name of the function.
+  let ident = match std::env::var(name.value()) {
+    Ok(value) => value_ident(&name, &value)?,
+    Err(e) => match default {
+      Some(d) => match d.resolve_ident(&name)? {
+        Some(ident) => ident,
+        None => return Ok(TokenStream2::new()),
+      },
+      None => return Err(var_error(&name, e, "ident")),
+    },
+  };
+  // Generate result.
+  Ok(quote! {
+    #vis fn #ident() -> ::core::option::Option<&'static str> {
+      static VALUE: ::std::sync::OnceLock<::core::option::Option<::std::string::String>> =
+        ::std::sync::OnceLock::new();
+      VALUE
+        .get_or_init(|| {
+          ::std::env::var(#name)
+            .ok()
+            .or_else(|| ::core::option_env!(#name).map(::std::string::String::from))
+        })
+        .as_deref()
+    }
+  })
+}
+
+/// Uses the crate name (`CARGO_CRATE_NAME`) as an identifier.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! crate_name_id {
+///   () => { ... };
+///   (=> $apply_to:path) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn crate_name_id(tokens: TokenStream) -> TokenStream {
+  match parse_cargo_id("CARGO_CRATE_NAME", tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Uses the package name (`CARGO_PKG_NAME`) as an identifier.
+///
+/// Characters `-` in the package name are replaced with `_`.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! pkg_name_id {
+///   () => { ... };
+///   (=> $apply_to:path) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn pkg_name_id(tokens: TokenStream) -> TokenStream {
+  match parse_cargo_id("CARGO_PKG_NAME", tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Uses the binary name (`CARGO_BIN_NAME`) as an identifier.
+///
+/// Characters `-` in the binary name are replaced with `_`. Cargo only sets
+/// `CARGO_BIN_NAME` when building binary targets.
+///
+/// # Definition
+///
+/// ```
+/// macro_rules! bin_name_id {
+///   () => { ... };
+///   (=> $apply_to:path) => { ... };
+/// }
+/// ```
+#[proc_macro]
+pub fn bin_name_id(tokens: TokenStream) -> TokenStream {
+  match parse_cargo_id("CARGO_BIN_NAME", tokens.into()) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// Parses macros that use the given Cargo environment variable.
+fn parse_cargo_id(name: &str, tokens: TokenStream2) -> Result<TokenStream2> {
+  // Parse macro input.
+  let cargo_id: CargoId = syn::parse2(tokens)?;
+  // Get value of the environment variable.
+  let value = std::env::var(name).map_err(|e| {
+    let name = LitStr::new(name, Span::call_site());
+    var_error(&name, e, "ident")
+  })?;
+  let ident = Ident::new(&sanitize(&value), Span::call_site());
+  // Generate result.
+  Ok(apply(&[ident], cargo_id.apply_to))
+}
+
+/// Returns the name of the environment variable Cargo sets for the given
+/// feature (e.g. `CARGO_FEATURE_MY_FEATURE` for `my-feature`).
+fn feature_var(feature: &str) -> String {
+  format!("CARGO_FEATURE_{}", sanitize(&feature.to_uppercase()))
+}
+
+/// Returns the name of the environment variable Cargo sets for the given
+/// configuration option (e.g. `CARGO_CFG_TARGET_ARCH` for `target_arch`).
+fn cfg_var(cfg: &str) -> String {
+  format!("CARGO_CFG_{}", sanitize(&cfg.to_uppercase()))
+}
+
+/// Sanitizes the given Cargo name so that it can be used as an identifier.
+fn sanitize(name: &str) -> String {
+  name.replace('-', "_")
+}
+
+/// Coerces the given value into a valid identifier.
+///
+/// Characters that can not appear in an identifier are replaced with `_`,
+/// and `_` is prepended if the result would start with a digit. Consecutive
+/// underscores are collapsed into one if `collapse` is set. Returns `None` if
+/// the value is empty.
+fn sanitize_ident(value: &str, collapse: bool) -> Option<String> {
+  let mut ident = String::with_capacity(value.len());
+  for c in value.chars() {
+    let c = if unicode_ident::is_xid_continue(c) {
+      c
+    } else {
+      '_'
+    };
+    if !(collapse && c == '_' && ident.ends_with('_')) {
+      ident.push(c);
+    }
+  }
+  match ident.chars().next() {
+    None => None,
+    Some(c) if c == '_' || unicode_ident::is_xid_start(c) => Some(ident),
+    Some(_) => Some(format!("_{ident}")),
+  }
+}
+
+/// Converts the given value to `snake_case`.
+///
+/// Words are separated by spaces, `-`, `_` and case boundaries. Returns
+/// `None` if the value is empty or contains other non-alphanumeric
+/// characters.
+fn snake_case(value: &str) -> Option<String> {
+  let chars: Vec<_> = value.chars().collect();
+  let mut snake = String::with_capacity(value.len());
+  for (i, &c) in chars.iter().enumerate() {
+    if matches!(c, ' ' | '-' | '_') {
+      if !snake.is_empty() && !snake.ends_with('_') {
+        snake.push('_');
+      }
+      continue;
+    }
+    if !c.is_alphanumeric() {
+      return None;
+    }
+    // Start a new word at `aB`, `1B` and the `C` of `ABCd`.
+    if c.is_uppercase() && i > 0 && !snake.is_empty() && !snake.ends_with('_') {
+      let prev = chars[i - 1];
+      let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+      if !prev.is_uppercase() || next_lower {
+        snake.push('_');
+      }
+    }
+    snake.extend(c.to_lowercase());
+  }
+  let snake = snake.trim_end_matches('_');
+  (!snake.is_empty()).then(|| snake.into())
+}
+
+/// Converts the given value to `PascalCase`.
+///
+/// Words are split in the same way as [`snake_case`].
+fn pascal_case(value: &str) -> Option<String> {
+  let pascal = snake_case(value)?
+    .split('_')
+    .flat_map(|word| {
+      let mut chars = word.chars();
+      let first = chars.next().into_iter().flat_map(char::to_uppercase);
+      first.chain(chars)
+    })
+    .collect();
+  Some(pascal)
+}
+
+/// Creates an identifier that can be used as an enum variant from the given
+/// value, the identifier is raw if the value is a keyword.
+fn variant_ident(value: &str, span: Span) -> Result<Ident> {
+  check_path_keyword(value, span)?;
+  if syn::parse_str::<Ident>(value).is_ok() {
+    Ok(Ident::new(value, span))
+  } else if syn::parse_str::<Ident>(&format!("r#{value}")).is_ok() {
+    Ok(Ident::new_raw(value, span))
+  } else {
+    Err(Error::new(
+      span,
+      format!("converted value `{value}` is not a valid enum variant"),
+    ))
+  }
+}
+
+/// Decodes the given standard base64 string, padding is optional.
+fn decode_base64(value: &str) -> std::result::Result<Vec<u8>, String> {
+  let trimmed = value.trim_end_matches('=');
+  let padding = value.len() - trimmed.len();
+  if padding > 2 || (padding > 0 && !value.len().is_multiple_of(4)) || trimmed.len() % 4 == 1 {
+    return Err("invalid length or padding".into());
+  }
+  let mut bytes = Vec::new();
+  let (mut buf, mut bits) = (0u32, 0);
+  for (i, c) in trimmed.char_indices() {
+    let v = match c {
+      'A'..='Z' => c as u32 - 'A' as u32,
+      'a'..='z' => c as u32 - 'a' as u32 + 26,
+      '0'..='9' => c as u32 - '0' as u32 + 52,
+      '+' => 62,
+      '/' => 63,
+      _ => return Err(format!("invalid character `{c}` at index {i}")),
+    };
+    buf = (buf << 6) | v;
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      bytes.push((buf >> bits) as u8);
+      buf &= (1 << bits) - 1;
+    }
+  }
+  Ok(bytes)
+}
+
+/// Decodes the given hex string, digits are case-insensitive.
+fn decode_hex(value: &str) -> std::result::Result<Vec<u8>, String> {
+  let digits = value
+    .char_indices()
+    .map(|(i, c)| {
+      c.to_digit(16)
+        .ok_or_else(|| format!("invalid character `{c}` at index {i}"))
+    })
+    .collect::<std::result::Result<Vec<_>, _>>()?;
+  if !digits.len().is_multiple_of(2) {
+    return Err("odd number of digits".into());
+  }
+  Ok(digits.chunks(2).map(|d| (d[0] * 16 + d[1]) as u8).collect())
+}
+
+/// Resolves the given environment variables, and joins their values with
+/// the given separator into a single identifier.
+///
+/// Skipped values are omitted, returns `None` if all values are skipped.
+fn join(
+  sep: &LitStr,
+  vars: Punctuated<EnvVar, Token![,]>,
+  file_dir: Option<&Path>,
+) -> Result<Option<Value>> {
+  let sep_value = sep.value();
+  if let Some(c) = sep_value
+    .chars()
+    .find(|c| !unicode_ident::is_xid_continue(*c))
+  {
+    return Err(Error::new(
+      sep.span(),
+      format!("separator `{sep_value}` contains `{c}`, which can not be used in an identifier"),
+    ));
+  }
+  let span = vars.first().map_or_else(|| sep.span(), |v| v.name.span());
+  let parts = vars
+    .into_iter()
+    .filter_map(|v| v.resolve(file_dir).transpose())
+    .map(|v| {
+      Ok(match v? {
+        Value::Env(value, _) => value,
+        Value::Ident(ident) => ident.to_string(),
+        Value::Macro(mac) => return Err(macro_default_error(&mac, "joined")),
+      })
+    })
+    .collect::<Result<Vec<_>>>()?;
+  if parts.is_empty() {
+    return Ok(None);
+  }
+  let joined = parts.join(&sep_value);
+  if syn::parse_str::<Ident>(&joined).is_err() {
+    return Err(Error::new(
+      span,
+      format!("joined value `{joined}` is not a valid identifier"),
+    ));
+  }
+  Ok(Some(Value::Env(joined, span)))
+}
+
+/// Generates the output tokens, applies the given macro if necessary.
+fn apply<T: ToTokens>(items: &[T], apply_to: Option<ApplyTo>) -> TokenStream2 {
+  if let Some(apply_to) = apply_to {
+    let m = apply_to.path;
+    quote!(#m!(#(#items),*);)
+  } else {
+    quote!(#(#items),*)
+  }
+}
+
+/// Converts the value of the given environment variable to an identifier,
+/// returns an error if it is not a valid identifier.
+fn value_ident(name: &LitStr, value: &str) -> Result<Ident> {
+  if std::env::var_os("BUILD_ID_STRICT").is_some() {
+    check_strict(&name.value(), value, name.span())?;
+  }
+  record_manifest(&name.value(), value, name.span())?;
+  check_path_keyword(value, name.span())?;
+  let ident: Ident = syn::parse_str(value).map_err(|_| {
+    Error::new(
+      name.span(),
+      format!("value `{value}` is not a valid identifier"),
+    )
+  })?;
+  Ok(Ident::new(&ident.to_string(), name.span()))
+}
+
+/// Returns an error if the given value is a path keyword (`Self`, `crate`,
+/// `super` or `self`), which can not be used as a plain identifier.
+fn check_path_keyword(value: &str, span: Span) -> Result<()> {
+  match value {
+    "Self" | "crate" | "super" | "self" => Err(Error::new(
+      span,
+      format!(
+        "value `{value}` is a path keyword, which can not be used as a plain identifier; \
+         use it in a path directly instead of generating it with `env_id`"
+      ),
+    )),
+    _ => Ok(()),
+  }
+}
+
+/// Returns an error if the given value is not a valid identifier.
+///
+/// Keywords are not rejected, since they can still be passed to macros.
+fn check_ident(value: &str, span: Span) -> Result<()> {
+  let mut chars = value.chars();
+  let valid = chars
+    .next()
+    .is_some_and(|c| c == '_' || unicode_ident::is_xid_start(c))
+    && chars.all(unicode_ident::is_xid_continue);
+  if valid {
+    Ok(())
+  } else {
+    Err(Error::new(
+      span,
+      format!("value `{value}` is not a valid identifier"),
+    ))
+  }
+}
+
+/// Creates a lifetime from the given name.
+fn lifetime(name: &str, span: Span) -> Result<Lifetime> {
+  if name.contains('\'') {
+    return Err(Error::new(
+      span,
+      format!("lifetime name `{name}` must not contain `'`"),
+    ));
+  }
+  let mut lifetime: Lifetime = syn::parse_str(&format!("'{name}"))
+    .map_err(|_| Error::new(span, format!("`'{name}` is not a valid lifetime")))?;
+  lifetime.apostrophe = span;
+  lifetime.ident.set_span(span);
+  Ok(lifetime)
+}
+
+/// Creates an error for the given environment variable lookup failure,
+/// `default` is shown as the suggested default value.
+fn var_error(name: &LitStr, e: VarError, default: &str) -> Error {
+  let msg = match e {
+    VarError::NotPresent => format!(
+      "environment variable `{}` is not set; provide a default with `?: {default}`",
+      name.value()
+    ),
+    VarError::NotUnicode(_) => {
+      format!("environment variable `{}` is not valid UTF-8", name.value())
+    }
+  };
+  Error::new(name.span(), msg)
+}
+
+/// Values resolved in strict mode.
+static RESOLVED: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Checks if the given variable was resolved to another value.
+///
+/// The key is returned by [`source_key`].
+fn check_strict(key: &str, value: &str, span: Span) -> Result<()> {
+  let mut resolved = RESOLVED.lock().unwrap_or_else(|e| e.into_inner());
+  let resolved = resolved.get_or_insert_with(HashMap::new);
+  record_value(resolved, key, value).map_err(|prev| {
+    let var = match key.strip_prefix("file:") {
+      Some(name) => format!("variable `{name}` in `{ENV_FILE}`"),
+      None => format!("environment variable `{key}`"),
+    };
+    Error::new(
+      span,
+      format!("{var} resolved to `{value}`, but it was previously resolved to `{prev}`"),
+    )
+  })
+}
+
+/// Returns the key of the given variable in strict mode and the manifest.
+///
+/// Values read from the environment file are keyed by `file:NAME`, so they
+/// are not mixed up with values of the process environment.
+fn source_key(name: &str, from_file: bool) -> String {
+  match from_file {
+    true => format!("file:{name}"),
+    false => name.into(),
+  }
+}
+
+/// Records the value of the given environment variable, returns the
+/// previous value if it differs from the given one.
+fn record_value(
+  resolved: &mut HashMap<String, String>,
+  name: &str,
+  value: &str,
+) -> std::result::Result<(), String> {
+  match resolved.get(name) {
+    Some(prev) if prev != value => Err(prev.clone()),
+    Some(_) => Ok(()),
+    None => {
+      resolved.insert(name.into(), value.into());
+      Ok(())
+    }
+  }
+}
+
+/// Appends the given variable and its value to the manifest file specified by
+/// the environment variable `BUILD_ID_MANIFEST`, does nothing if it is not
+/// set.
+fn record_manifest(name: &str, value: &str, span: Span) -> Result<()> {
+  let Some(path) = std::env::var_os("BUILD_ID_MANIFEST") else {
+    return Ok(());
+  };
+  let path = Path::new(&path);
+  append_manifest(path, name, value).map_err(|e| {
+    Error::new(
+      span,
+      format!("failed to write manifest `{}`: {e}", path.display()),
+    )
+  })
+}
+
+/// Appends a line of `NAME=VALUE` to the given manifest file.
+///
+/// Since multiple crates may be compiled in parallel, the file is locked
+/// while appending.
+fn append_manifest(path: &Path, name: &str, value: &str) -> io::Result<()> {
+  use std::io::Write;
+  let mut file = fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)?;
+  file.lock()?;
+  file.write_all(manifest_line(name, value).as_bytes())
+}
+
+/// Returns a line of the manifest file for the given variable.
+///
+/// Backslashes and line breaks in the value are escaped, so that each
+/// variable takes exactly one line.
+fn manifest_line(name: &str, value: &str) -> String {
+  let mut line = format!("{name}=");
+  for c in value.chars() {
+    match c {
+      '\\' => line.push_str("\\\\"),
+      '\n' => line.push_str("\\n"),
+      '\r' => line.push_str("\\r"),
+      c => line.push(c),
+    }
+  }
+  line.push('\n');
+  line
+}
+
+/// Name of the environment file.
+const ENV_FILE: &str = "build_id.env";
+
+/// Parsed environment files, indexed by their paths.
+static ENV_FILES: Mutex<Option<HashMap<PathBuf, HashMap<String, String>>>> = Mutex::new(None);
+
+/// Reads the given variable from the environment file in the given
+/// directory.
+///
+/// Returns `None` if the file does not exist or the variable is not defined.
+fn file_var(dir: &Path, name: &LitStr) -> Result<Option<String>> {
+  let path = dir.join(ENV_FILE);
+  let mut files = ENV_FILES.lock().unwrap_or_else(|e| e.into_inner());
+  let files = files.get_or_insert_with(HashMap::new);
+  if !files.contains_key(&path) {
+    let vars = load_env_file(&path).map_err(|e| Error::new(name.span(), e))?;
+    files.insert(path.clone(), vars);
+  }
+  Ok(files[&path].get(&name.value()).cloned())
+}
+
+/// Looks up the given variable, in the environment file in `file_dir` first
+/// if given, then in the process environment.
+///
+/// Returns the key of the variable (see [`source_key`]) along with its value.
+fn lookup_var(
+  name: &LitStr,
+  file_dir: Option<&Path>,
+) -> Result<(String, std::result::Result<String, VarError>)> {
+  let value = match file_dir {
+    Some(dir) => file_var(dir, name)?,
+    None => None,
+  };
+  let key = source_key(&name.value(), value.is_some());
+  Ok((key, value.map_or_else(|| std::env::var(name.value()), Ok)))
+}
+
+/// Loads the given environment file.
+///
+/// Returns an empty map if the file does not exist.
+fn load_env_file(path: &Path) -> std::result::Result<HashMap<String, String>, String> {
+  match fs::read_to_string(path) {
+    Ok(content) => parse_env_file(&content).map_err(|line| {
+      format!(
+        "invalid line {line} in `{}`, expected `KEY=VALUE`",
+        path.display()
+      )
+    }),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+    Err(e) => Err(format!("failed to read `{}`: {e}", path.display())),
+  }
+}
+
+/// Parses the content of an environment file.
+///
+/// Returns the line number of the first invalid line on error.
+fn parse_env_file(content: &str) -> std::result::Result<HashMap<String, String>, usize> {
+  let mut vars = HashMap::new();
+  for (i, line) in content.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    match line.split_once('=') {
+      Some((key, value)) if !key.trim().is_empty() => {
+        vars.insert(key.trim().into(), value.trim().into());
+      }
+      _ => return Err(i + 1),
+    }
+  }
+  Ok(vars)
+}
+
+/// AST of the `env_id` macro.
+struct EnvId {
+  from_file: bool,
+  vars: EnvVars,
+  span: SpanMode,
+  discriminant: Option<Discriminant>,
+  provenance: Option<Provenance>,
+  decode: Option<Decode>,
+  strip_prefix: Option<LitStr>,
+  replace: Option<Replace>,
+  pattern: Option<Pattern>,
+  mode: Mode,
+  apply_to: Option<ApplyTo>,
+}
+
+impl Parse for EnvId {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // Parse the optional `file` keyword.
+    let from_file = input.peek(kw::file);
+    if from_file {
+      input.parse::<kw::file>()?;
+    }
+    // Parse a single environment variable, a list of them, or the ones to be
+    // joined.
+    let vars = if input.peek(kw::join) {
+      input.parse::<kw::join>()?;
+      let sep = input.parse()?;
+      input.parse::<kw::of>()?;
+      let mut vars = Punctuated::new();
+      vars.push_value(input.parse()?);
+      while input.peek(Token![,]) && !input.peek2(kw::discriminant) && !input.peek2(kw::provenance)
+      {
+        vars.push_punct(input.parse()?);
+        // Allow a trailing comma.
+        if !EnvVar::peek(input) {
+          break;
+        }
+        vars.push_value(input.parse()?);
+      }
+      EnvVars::Join(sep, vars)
+    } else if input.peek(token::Bracket) {
+      let content;
+      let bracket = bracketed!(content in input);
+      let vars = content.parse_terminated(EnvVar::parse, Token![,])?;
+      EnvVars::List(vars, bracket)
+    } else {
+      EnvVars::Single(input.parse()?)
+    };
+    // Parse the optional span mode.
+    let span = if input.peek(Token![@]) {
+      input.parse()?
+    } else {
+      SpanMode::Literal
+    };
+    // Parse the optional discriminant.
+    let discriminant = if input.peek(Token![,]) && input.peek2(kw::discriminant) {
+      Some(input.parse::<Discriminant>()?)
+    } else {
+      None
+    };
+    // Parse the optional provenance.
+    let provenance = if input.peek(Token![,]) && input.peek2(kw::provenance) {
+      Some(input.parse::<Provenance>()?)
+    } else {
+      None
+    };
+    // Parse the optional decoding.
+    let decode = if input.peek(kw::decode) {
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    // Parse the optional prefix to be stripped.
+    let strip_prefix = if input.peek(kw::strip_prefix) {
+      input.parse::<kw::strip_prefix>()?;
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    // Parse the optional replacement table.
+    let replace = if input.peek(kw::replace) {
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    // Parse the optional pattern.
+    let pattern = if input.peek(kw::matches) {
+      input.parse::<kw::matches>()?;
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    // Parse the optional mode.
+    let mode = if input.peek(Token![as]) {
+      input.parse()?
+    } else {
+      Mode::Ident
+    };
+    // Parse the optional apply-to macro.
+    let apply_to = if input.peek(Token![=>]) {
+      Some(input.parse::<ApplyTo>()?)
+    } else {
+      None
+    };
+    // Allow a trailing comma.
+    if input.peek(Token![,]) {
+      let fork = input.fork();
+      fork.parse::<Token![,]>()?;
+      if fork.is_empty() {
+        input.parse::<Token![,]>()?;
+      }
+    }
+    // Report clauses that are duplicated or out of order.
+    if let Some(clause) = clause_name(input) {
+      return Err(input.error(format!(
+        "unexpected `{clause}`, each clause can appear only once and in the documented order"
+      )));
+    }
+    // A list of identifiers can only be used by the apply-to macro.
+    if let (EnvVars::List(_, bracket), None) = (&vars, &apply_to) {
+      return Err(Error::new(
+        bracket.span.join(),
+        "a list of environment variables requires an apply-to macro",
+      ));
+    }
+    // So does the discriminant.
+    if let (Some(d), None) = (&discriminant, &apply_to) {
+      return Err(Error::new(
+        d.value.span(),
+        "a discriminant requires an apply-to macro",
+      ));
+    }
+    // So does the provenance, which can not be used with joined values.
+    if let Some(p) = &provenance {
+      if apply_to.is_none() {
+        return Err(Error::new(
+          p.provenance.span,
+          "provenance requires an apply-to macro",
+        ));
+      }
+      if matches!(vars, EnvVars::Join(..)) {
+        return Err(Error::new(
+          p.provenance.span,
+          "provenance can not be used with joined values",
+        ));
+      }
+    }
+    Ok(Self {
+      from_file,
+      vars,
+      span,
+      discriminant,
+      provenance,
+      decode,
+      strip_prefix,
+      replace,
+      pattern,
+      mode,
+      apply_to,
+    })
+  }
+}
+
+/// Returns the name of the clause of the `env_id` macro at the beginning of
+/// the given input, or `None` if there is no such clause.
+fn clause_name(input: ParseStream) -> Option<&'static str> {
+  if input.peek(Token![?]) {
+    Some("?:")
+  } else if input.peek(Token![@]) {
+    Some("@")
+  } else if input.peek(Token![,]) && input.peek2(kw::discriminant) {
+    Some("discriminant")
+  } else if input.peek(Token![,]) && input.peek2(kw::provenance) {
+    Some("provenance")
+  } else if input.peek(kw::decode) {
+    Some("decode")
+  } else if input.peek(kw::strip_prefix) {
+    Some("strip_prefix")
+  } else if input.peek(kw::replace) {
+    Some("replace")
+  } else if input.peek(kw::matches) {
+    Some("matches")
+  } else if input.peek(Token![as]) {
+    Some("as")
+  } else if input.peek(Token![=>]) {
+    Some("=>")
+  } else {
+    None
+  }
+}
+
+/// Environment variables of the `env_id` macro.
+enum EnvVars {
+  Single(EnvVar),
+  List(Punctuated<EnvVar, Token![,]>, token::Bracket),
+  /// Environment variables to be joined with the given separator.
+  Join(LitStr, Punctuated<EnvVar, Token![,]>),
+}
+
+/// Environment variable with an optional default identifier.
+struct EnvVar {
+  /// Name of the environment variable, name of the feature, or name of the
+  /// configuration option.
+  name: LitStr,
+  /// Identifier to be used if the feature is enabled, or if the environment
+  /// variable is set when `if_set` is `true`.
+  feature_id: Option<Ident>,
+  /// Whether the identifier is chosen by the presence of the environment
+  /// variable instead of a feature.
+  if_set: bool,
+  /// Whether the value is read from `CARGO_CFG_<NAME>`.
+  cfg: bool,
+  default_id: Option<DefaultId>,
+}
+
+impl EnvVar {
+  /// Returns the name of the environment variable the value is read from.
+  fn source(&self) -> String {
+    let name = self.name.value();
+    if self.feature_id.is_some() && !self.if_set {
+      feature_var(&name)
+    } else if self.cfg {
+      cfg_var(&name)
+    } else {
+      name
+    }
+  }
+
+  /// Returns `true` if the given input starts with an environment variable.
+  fn peek(input: ParseStream) -> bool {
+    input.peek(LitStr)
+      || input.peek(kw::feature_of)
+      || input.peek(kw::if_set)
+      || input.peek(kw::cfg)
+  }
+
+  /// Resolves the environment variable to a value, returns `None` if it is
+  /// skipped by the default `_`.
+  ///
+  /// If `file_dir` is given, the environment file in it is looked up first.
+  fn resolve(self, file_dir: Option<&Path>) -> Result<Option<Value>> {
+    if let Some(id) = self.feature_id {
+      let var = match self.if_set {
+        true => self.name.value(),
+        false => feature_var(&self.name.value()),
+      };
+      return match std::env::var_os(var) {
+        Some(_) => Ok(Some(Value::Ident(id))),
+        None => match self.default_id {
+          Some(d) => Ok(d.resolve(&self.name)?),
+          None => Err(Error::new(
+            self.name.span(),
+            format!(
+              "feature `{}` is not enabled; provide a default with `?: ident`",
+              self.name.value()
+            ),
+          )),
+        },
+      };
+    }
+    if self.cfg {
+      let var = cfg_var(&self.name.value());
+      return match std::env::var(&var) {
+        Ok(value) if value.contains(',') => Err(Error::new(
+          self.name.span(),
+          format!("`{var}` has multiple values `{value}`, which can not be an identifier"),
+        )),
+        Ok(value) => {
+          record_manifest(&var, &value, self.name.span())?;
+          Ok(Some(Value::Env(value, self.name.span())))
+        }
+        Err(_) => match self.default_id {
+          Some(d) => Ok(d.resolve(&self.name)?),
+          None => Err(Error::new(
+            self.name.span(),
+            format!(
+              "environment variable `{var}` is not set; Cargo only sets it for build scripts, \
+               forward it with `cargo:rustc-env={var}=...` or provide a default with `?: ident`"
+            ),
+          )),
+        },
+      };
+    }
+    let (key, value) = lookup_var(&self.name, file_dir)?;
+    match value {
+      Ok(value) => {
+        if std::env::var_os("BUILD_ID_STRICT").is_some() {
+          check_strict(&key, &value, self.name.span())?;
+        }
+        record_manifest(&key, &value, self.name.span())?;
+        Ok(Some(Value::Env(value, self.name.span())))
+      }
+      Err(e) => match self.default_id {
+        Some(d) => Ok(d.resolve(&self.name)?),
+        None => Err(var_error(&self.name, e, "ident")),
+      },
+    }
+  }
+}
+
+impl Parse for EnvVar {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // Parse the literal string, the feature name and its identifier, or the
+    // configuration option name.
+    let (name, feature_id, cfg) = if input.peek(kw::feature_of) {
+      input.parse::<kw::feature_of>()?;
+      let name = input.parse()?;
+      input.parse::<Token![:]>()?;
+      (name, Some(input.parse()?), false)
+    } else if input.peek(kw::if_set) {
+      // Parse `if_set "NAME" ? set_id : unset_id`.
+      input.parse::<kw::if_set>()?;
+      let name = input.parse()?;
+      let _question = input.parse()?;
+      let set_id = input.parse()?;
+      let _colon = Some(input.parse()?);
+      let kind = input.parse()?;
+      reject_default(input)?;
+      return Ok(Self {
+        name,
+        feature_id: Some(set_id),
+        if_set: true,
+        cfg: false,
+        default_id: Some(DefaultId {
+          _question,
+          _colon,
+          kind,
+        }),
+      });
+    } else if input.peek(kw::cfg) {
+      input.parse::<kw::cfg>()?;
+      (input.parse()?, None, true)
+    } else {
+      (input.parse()?, None, false)
+    };
+    // Parse the optional default identifier.
+    let default_id = if input.peek(Token![?]) {
+      let default_id = input.parse::<DefaultId>()?;
+      reject_default(input)?;
+      Some(default_id)
+    } else {
+      None
+    };
+    Ok(Self {
+      name,
+      feature_id,
+      if_set: false,
+      cfg,
+      default_id,
+    })
+  }
+}
+
+/// Reports an error if another default identifier follows.
+fn reject_default(input: ParseStream) -> Result<()> {
+  if input.peek(Token![?]) {
+    return Err(input.error("duplicate default identifier, only one `?:` is allowed"));
+  }
+  Ok(())
+}
+
+/// Resolved value of an environment variable.
+enum Value {
+  /// Value of the environment variable, and span of its name.
+  Env(String, Span),
+  /// Identifier given in the macro input.
+  Ident(Ident),
+  /// Macro invocation given in the macro input, which expands to an
+  /// identifier.
+  Macro(Macro),
+}
+
+impl Value {
+  /// Sets the span of the value according to the given span mode.
+  fn with_span(self, mode: &SpanMode) -> Self {
+    match self {
+      Self::Env(value, span) => Self::Env(value, mode.span(span)),
+      Self::Ident(mut ident) => {
+        ident.set_span(mode.span(ident.span()));
+        Self::Ident(ident)
+      }
+      Self::Macro(mac) => Self::Macro(mac),
+    }
+  }
+
+  /// Returns the original string of the value, i.e. the value of the
+  /// environment variable, or the identifier given in the macro input.
+  fn original(&self) -> String {
+    match self {
+      Self::Env(value, _) => value.clone(),
+      Self::Ident(ident) => ident.to_string(),
+      Self::Macro(mac) => mac.to_token_stream().to_string(),
+    }
+  }
+
+  /// Decodes the value of the environment variable.
+  ///
+  /// Identifiers given in the macro input are left unchanged.
+  fn decode(self, decode: &Decode) -> Result<Self> {
+    match self {
+      Self::Env(value, span) => {
+        let (name, bytes) = match decode {
+          Decode::Base64 => ("base64", decode_base64(&value)),
+          Decode::Hex => ("hex", decode_hex(&value)),
+        };
+        let bytes = bytes
+          .map_err(|e| Error::new(span, format!("value `{value}` is not valid {name}: {e}")))?;
+        let decoded = String::from_utf8(bytes).map_err(|_| {
+          Error::new(
+            span,
+            format!("decoded value of `{value}` is not valid UTF-8"),
+          )
+        })?;
+        Ok(Self::Env(decoded, span))
+      }
+      value => Ok(value),
+    }
+  }
+
+  /// Removes the given prefix from the value of the environment variable.
+  ///
+  /// Identifiers given in the macro input are left unchanged.
+  fn strip_prefix(self, prefix: &LitStr) -> Result<Self> {
+    match self {
+      Self::Env(value, span) => match value.strip_prefix(&prefix.value()) {
+        Some("") => Err(Error::new(
+          prefix.span(),
+          format!("value `{value}` is empty after stripping the prefix"),
+        )),
+        Some(stripped) => Ok(Self::Env(stripped.into(), span)),
+        None => Ok(Self::Env(value, span)),
+      },
+      value => Ok(value),
+    }
+  }
+
+  /// Applies the given replacement table to the value of the environment
+  /// variable.
+  ///
+  /// Identifiers given in the macro input are left unchanged.
+  fn replace(self, replace: &Replace) -> Self {
+    match self {
+      Self::Env(value, span) => Self::Env(replace.apply(value), span),
+      value => value,
+    }
+  }
+}
+
+/// Span of the identifiers generated by the `env_id` macro.
+enum SpanMode {
+  /// Span of the literal string, or span of the given identifier.
+  Literal,
+  /// Call-site span, resolved as if written at the macro invocation.
+  CallSite,
+  /// Mixed-site span, local variables are resolved at the macro definition.
+  MixedSite,
+}
+
+impl SpanMode {
+  /// Returns the span to be used, `span` is the span of the literal or the
+  /// identifier in the macro input.
+  fn span(&self, span: Span) -> Span {
+    match self {
+      Self::Literal => span,
+      Self::CallSite => Span::call_site(),
+      Self::MixedSite => Span::mixed_site(),
+    }
+  }
+}
+
+impl Parse for SpanMode {
+  fn parse(input: ParseStream) -> Result<Self> {
+    input.parse::<Token![@]>()?;
+    let mode: Ident = input.parse()?;
+    match mode.to_string().as_str() {
+      "call_site" => Ok(Self::CallSite),
+      "mixed_site" => Ok(Self::MixedSite),
+      _ => Err(Error::new(
+        mode.span(),
+        format!("unknown span `{mode}`, expected `call_site` or `mixed_site`"),
+      )),
+    }
+  }
+}
+
+/// Output mode of the `env_id` macro.
+enum Mode {
+  /// Outputs an identifier.
+  Ident,
+  /// Outputs an identifier, invalid characters in the value are replaced
+  /// with `_`. Consecutive underscores are collapsed if `collapse` is set.
+  SanitizedIdent { collapse: bool },
+  /// Outputs a lifetime.
+  Lifetime,
+  /// Outputs an integer literal, which must be in the given range if any.
+  Int(Option<IntRange>),
+  /// Outputs an identifier, the value is transformed by the given stages
+  /// from left to right.
+  Transform(Vec<Transform>),
+  /// Outputs an identifier in `PascalCase` that can be used as an enum
+  /// variant, the identifier is raw if it is a keyword.
+  Variant,
+  /// Outputs an identifier that is used as a type, warns if the value does
+  /// not start with an uppercase letter, or reports an error if `strict` is
+  /// set.
+  Type { strict: bool },
+}
+
+impl Mode {
+  /// Converts the given value to tokens.
+  fn convert(&self, value: Value) -> Result<TokenStream2> {
+    match (self, value) {
+      (Self::Ident, Value::Env(value, span)) => {
+        check_path_keyword(&value, span)?;
+        check_ident(&value, span)?;
+        Ok(Ident::new(&value, span).into_token_stream())
+      }
+      (Self::Ident, Value::Ident(ident)) => Ok(ident.into_token_stream()),
+      (Self::SanitizedIdent { collapse }, Value::Env(value, span)) => {
+        let ident = sanitize_ident(&value, *collapse)
+          .ok_or_else(|| Error::new(span, "can not use an empty value as an identifier"))?;
+        check_path_keyword(&ident, span)?;
+        Ok(Ident::new(&ident, span).into_token_stream())
+      }
+      (Self::SanitizedIdent { .. }, Value::Ident(ident)) => Ok(ident.into_token_stream()),
+      (Self::Lifetime, Value::Env(value, span)) => Ok(lifetime(&value, span)?.into_token_stream()),
+      (Self::Lifetime, Value::Ident(ident)) => {
+        Ok(lifetime(&ident.to_string(), ident.span())?.into_token_stream())
+      }
+      (Self::Int(range), Value::Env(value, span)) => {
+        let int: i128 = value
+          .trim()
+          .parse()
+          .map_err(|_| Error::new(span, format!("value `{value}` is not an integer")))?;
+        if let Some(range) = range {
+          range.check(int, span)?;
+        }
+        let lit = LitInt::new(&int.unsigned_abs().to_string(), span);
+        Ok(if int < 0 { quote!(-#lit) } else { quote!(#lit) })
+      }
+      (Self::Int(_), Value::Ident(ident)) => Err(Error::new(
+        ident.span(),
+        format!("expected an integer, found identifier `{ident}`"),
+      )),
+      (Self::Transform(stages), Value::Env(value, span)) => {
+        let value = stages
+          .iter()
+          .try_fold(value, |value, stage| stage.apply(value, span))?;
+        check_path_keyword(&value, span)?;
+        if syn::parse_str::<Ident>(&value).is_err() {
+          return Err(Error::new(
+            span,
+            format!("transformed value `{value}` is not a valid identifier"),
+          ));
+        }
+        Ok(Ident::new(&value, span).into_token_stream())
+      }
+      (Self::Transform(_), Value::Ident(ident)) => Ok(ident.into_token_stream()),
+      (Self::Variant, Value::Env(value, span)) => {
+        let variant = pascal_case(&value).ok_or_else(|| {
+          Error::new(
+            span,
+            format!("value `{value}` can not be converted to an enum variant"),
+          )
+        })?;
+        Ok(variant_ident(&variant, span)?.into_token_stream())
+      }
+      (Self::Variant, Value::Ident(ident)) => Ok(ident.into_token_stream()),
+      (Self::Type { strict }, Value::Env(value, span)) => {
+        check_path_keyword(&value, span)?;
+        check_ident(&value, span)?;
+        match type_name_message(&value) {
+          Some(msg) if *strict => Err(Error::new(span, msg)),
+          _ => Ok(Ident::new(&value, span).into_token_stream()),
+        }
+      }
+      (Self::Type { .. }, Value::Ident(ident)) => Ok(ident.into_token_stream()),
+      // Macro invocations are emitted in place of the identifier.
+      (Self::Lifetime, Value::Macro(mac)) => Err(macro_default_error(&mac, "used as a lifetime")),
+      (_, Value::Macro(mac)) => Ok(mac.into_token_stream()),
+    }
+  }
+
+  /// Returns an expression of `usize` that emits a warning, if the given
+  /// value is used as a type but does not look like a type name.
+  ///
+  /// Since proc-macro diagnostics are unstable, the warning is emitted by
+  /// using a deprecated constant. Default identifiers given in the macro
+  /// input are not checked.
+  fn type_warning(&self, value: &Value) -> Option<TokenStream2> {
+    let (Self::Type { strict: false }, Value::Env(value, span)) = (self, value) else {
+      return None;
+    };
+    let msg = type_name_message(value)?;
+    Some(quote_spanned! {*span=>
+      {
+        #[deprecated(note = #msg)]
+        #[allow(non_upper_case_globals)]
+        const env_id_type_hint: usize = 1;
+        env_id_type_hint
+      }
+    })
+  }
+}
+
+/// Range of integers, used by the integer mode of the `env_id` macro.
+struct IntRange {
+  start: Option<i128>,
+  end: Option<i128>,
+  inclusive: bool,
+  span: Span,
+}
+
+impl IntRange {
+  /// Checks if the given integer is in the range.
+  fn check(&self, int: i128, span: Span) -> Result<()> {
+    let above_start = self.start.is_none_or(|s| int >= s);
+    let below_end = match (self.end, self.inclusive) {
+      (Some(e), true) => int <= e,
+      (Some(e), false) => int < e,
+      (None, _) => true,
+    };
+    if above_start && below_end {
+      Ok(())
+    } else {
+      let mut err = Error::new(span, format!("value `{int}` is out of range"));
+      err.combine(Error::new(self.span, "expected a value in this range"));
+      Err(err)
+    }
+  }
+
+  /// Parses an optional integer bound, which may be negative.
+  fn parse_bound(input: ParseStream) -> Result<Option<i128>> {
+    if !input.peek(Token![-]) && !input.peek(LitInt) {
+      return Ok(None);
+    }
+    let neg = input.parse::<Option<Token![-]>>()?.is_some();
+    let int: i128 = input.parse::<LitInt>()?.base10_parse()?;
+    Ok(Some(if neg { -int } else { int }))
+  }
+}
+
+impl Parse for IntRange {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let span = input.span();
+    let start = Self::parse_bound(input)?;
+    let inclusive = if input.peek(Token![..=]) {
+      input.parse::<Token![..=]>()?;
+      true
+    } else {
+      input.parse::<Token![..]>()?;
+      false
+    };
+    let end = Self::parse_bound(input)?;
+    if inclusive && end.is_none() {
+      return Err(Error::new(span, "inclusive range must have an end"));
+    }
+    Ok(Self {
+      start,
+      end,
+      inclusive,
+      span,
+    })
+  }
+}
+
+impl Parse for Mode {
+  fn parse(input: ParseStream) -> Result<Self> {
+    input.parse::<Token![as]>()?;
+    // Parse the type hint and the optional `strict`.
+    if input.peek(Token![type]) {
+      input.parse::<Token![type]>()?;
+      let strict = input.peek(kw::strict);
+      if strict {
+        input.parse::<kw::strict>()?;
+      }
+      return Ok(Self::Type { strict });
+    }
+    let mode: Ident = input.parse()?;
+    match mode.to_string().as_str() {
+      "Ident" => {
+        // Parse the optional `(collapse)`.
+        let collapse = if input.peek(token::Paren) {
+          let content;
+          parenthesized!(content in input);
+          content.parse::<kw::collapse>()?;
+          true
+        } else {
+          false
+        };
+        Ok(Self::SanitizedIdent { collapse })
+      }
+      "lifetime" => Ok(Self::Lifetime),
+      "Variant" => Ok(Self::Variant),
+      "int" => {
+        // Parse the optional range.
+        let range = if input.peek(Token![in]) {
+          input.parse::<Token![in]>()?;
+          Some(input.parse()?)
+        } else {
+          None
+        };
+        Ok(Self::Int(range))
+      }
+      "Trim" | "Lower" | "Upper" | "SnakeCase" | "Prefix" | "Suffix" => {
+        // Parse stages of the pipeline.
+        let mut stages = vec![Transform::parse_stage(mode, input)?];
+        while input.peek(Token![|]) {
+          input.parse::<Token![|]>()?;
+          stages.push(Transform::parse_stage(input.parse()?, input)?);
+        }
+        Ok(Self::Transform(stages))
+      }
+      _ => Err(Error::new(
+        mode.span(),
+        format!(
+          "unknown mode `{mode}`, expected `Ident`, `Variant`, `type`, `lifetime`, \
+           `int` or a transformation"
+        ),
+      )),
+    }
+  }
+}
+
+/// Stage of a transformation pipeline.
+enum Transform {
+  /// Removes leading and trailing whitespaces.
+  Trim,
+  /// Converts ASCII letters of the value to lowercase.
+  Lower,
+  /// Converts ASCII letters of the value to uppercase.
+  Upper,
+  /// Converts the value to `snake_case`.
+  SnakeCase,
+  /// Adds the given prefix.
+  Prefix(LitStr),
+  /// Adds the given suffix.
+  Suffix(LitStr),
+}
+
+impl Transform {
+  /// Applies the transformation to the given value.
+  fn apply(&self, value: String, span: Span) -> Result<String> {
+    match self {
+      Self::Trim => match value.trim() {
+        "" => Err(Error::new(
+          span,
+          format!("stage `Trim` got value `{value}`, which is empty after trimming"),
+        )),
+        trimmed => Ok(trimmed.into()),
+      },
+      Self::Lower => Ok(value.to_ascii_lowercase()),
+      Self::Upper => Ok(value.to_ascii_uppercase()),
+      Self::SnakeCase => snake_case(&value).ok_or_else(|| {
+        Error::new(
+          span,
+          format!("stage `SnakeCase` can not convert value `{value}`"),
+        )
+      }),
+      Self::Prefix(prefix) => Ok(prefix.value() + &value),
+      Self::Suffix(suffix) => Ok(value + &suffix.value()),
+    }
+  }
+
+  /// Parses a stage with the given name.
+  fn parse_stage(name: Ident, input: ParseStream) -> Result<Self> {
+    match name.to_string().as_str() {
+      "Trim" => Ok(Self::Trim),
+      "Lower" => Ok(Self::Lower),
+      "Upper" => Ok(Self::Upper),
+      "SnakeCase" => Ok(Self::SnakeCase),
+      "Prefix" | "Suffix" => {
+        let content;
+        parenthesized!(content in input);
+        let lit = content.parse()?;
+        Ok(if name == "Prefix" {
+          Self::Prefix(lit)
+        } else {
+          Self::Suffix(lit)
+        })
+      }
+      _ => Err(Error::new(
+        name.span(),
+        format!(
+          "unknown transformation `{name}`, expected `Trim`, `Lower`, `Upper`, \
+           `SnakeCase`, `Prefix` or `Suffix`"
+        ),
+      )),
+    }
+  }
+}
+
+/// Decoding of the `env_id` macro.
+enum Decode {
+  /// Standard base64, padding is optional.
+  Base64,
+  /// Hex, digits are case-insensitive.
+  Hex,
+}
+
+impl Parse for Decode {
+  fn parse(input: ParseStream) -> Result<Self> {
+    input.parse::<kw::decode>()?;
+    let name: Ident = input.parse()?;
+    match name.to_string().as_str() {
+      "base64" => Ok(Self::Base64),
+      "hex" => Ok(Self::Hex),
+      _ => Err(Error::new(
+        name.span(),
+        format!("unknown decoding `{name}`, expected `base64` or `hex`"),
+      )),
+    }
+  }
+}
+
+/// Replacement table of the `env_id` macro.
+struct Replace {
+  entries: Vec<(LitStr, LitStr)>,
+}
+
+impl Replace {
+  /// Applies entries of the table to the given value in order.
+  fn apply(&self, value: String) -> String {
+    self.entries.iter().fold(value, |value, (from, to)| {
+      value.replace(&from.value(), &to.value())
+    })
+  }
+}
+
+impl Parse for Replace {
+  fn parse(input: ParseStream) -> Result<Self> {
+    input.parse::<kw::replace>()?;
+    let content;
+    braced!(content in input);
+    let mut entries = Vec::new();
+    while !content.is_empty() {
+      let from: LitStr = content.parse()?;
+      if from.value().is_empty() {
+        return Err(Error::new(from.span(), "can not replace an empty string"));
+      }
+      content.parse::<Token![=>]>()?;
+      entries.push((from, content.parse()?));
+      if content.is_empty() {
+        break;
+      }
+      content.parse::<Token![,]>()?;
+    }
+    Ok(Self { entries })
+  }
+}
+
+/// Pattern of the `env_id` macro, a minimal subset of regular expressions.
+struct Pattern {
+  lit: LitStr,
+  /// Whether the pattern is anchored at the start (`^`).
+  start: bool,
+  /// Whether the pattern is anchored at the end (`$`).
+  end: bool,
+  items: Vec<(Atom, Repeat)>,
+}
+
+impl Pattern {
+  /// Parses the pattern in the given literal string.
+  fn parse_lit(lit: LitStr) -> std::result::Result<Self, String> {
+    let pattern = lit.value();
+    let mut chars = pattern.chars().peekable();
+    let start = chars.next_if_eq(&'^').is_some();
+    let mut end = false;
+    let mut items: Vec<(Atom, Repeat)> = Vec::new();
+    while let Some(c) = chars.next() {
+      let atom = match c {
+        '$' if chars.peek().is_none() => {
+          end = true;
+          break;
+        }
+        '\\' => Atom::Char(chars.next().ok_or("trailing `\\`")?),
+        '[' => Atom::parse_class(&mut chars)?,
+        '*' | '+' => {
+          let repeat = if c == '*' {
+            Repeat::ZeroOrMore
+          } else {
+            Repeat::OneOrMore
+          };
+          match items.last_mut() {
+            Some((_, r @ Repeat::One)) => *r = repeat,
+            _ => return Err(format!("`{c}` must follow a character or a class")),
+          }
+          continue;
+        }
+        '^' | '$' => return Err(format!("`{c}` is only supported as an anchor")),
+        '.' | '?' | '|' | '(' | ')' | '{' | '}' | ']' => {
+          return Err(format!(
+            "`{c}` is not supported, use `\\{c}` to match it literally"
+          ))
+        }
+        c => Atom::Char(c),
+      };
+      items.push((atom, Repeat::One));
+    }
+    Ok(Self {
+      lit,
+      start,
+      end,
+      items,
+    })
+  }
+
+  /// Checks if the given value matches the pattern.
+  ///
+  /// Identifiers given in the macro input are not checked.
+  fn check(&self, value: &Value) -> Result<()> {
+    match value {
+      Value::Env(value, span) if !self.is_match(value) => Err(Error::new(
+        *span,
+        format!(
+          "value `{value}` does not match pattern `{}`",
+          self.lit.value()
+        ),
+      )),
+      _ => Ok(()),
+    }
+  }
+
+  /// Returns `true` if the given string matches the pattern.
+  fn is_match(&self, s: &str) -> bool {
+    let chars: Vec<_> = s.chars().collect();
+    if self.start {
+      self.match_here(&self.items, &chars)
+    } else {
+      (0..=chars.len()).any(|i| self.match_here(&self.items, &chars[i..]))
+    }
+  }
+
+  /// Returns `true` if the given items match the start of the given
+  /// characters, with backtracking.
+  fn match_here(&self, items: &[(Atom, Repeat)], chars: &[char]) -> bool {
+    let Some(((atom, repeat), rest)) = items.split_first() else {
+      return !self.end || chars.is_empty();
+    };
+    let (min, max) = match repeat {
+      Repeat::One => (1, 1),
+      Repeat::ZeroOrMore => (0, chars.len()),
+      Repeat::OneOrMore => (1, chars.len()),
+    };
+    let count = chars
+      .iter()
+      .take(max)
+      .take_while(|c| atom.matches(**c))
+      .count();
+    (min..=count)
+      .rev()
+      .any(|n| self.match_here(rest, &chars[n..]))
+  }
+}
+
+impl Parse for Pattern {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let lit: LitStr = input.parse()?;
+    let span = lit.span();
+    Self::parse_lit(lit).map_err(|e| Error::new(span, format!("invalid pattern: {e}")))
+  }
+}
+
+/// Atom of a pattern.
+enum Atom {
+  /// Matches the given character.
+  Char(char),
+  /// Matches characters in (or not in, if negated) the given ranges.
+  Class {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+  },
+}
+
+impl Atom {
+  /// Parses a character class, the leading `[` has been consumed.
+  fn parse_class(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+  ) -> std::result::Result<Self, String> {
+    let negated = chars.next_if_eq(&'^').is_some();
+    let mut ranges = Vec::new();
+    loop {
+      let c = match chars.next() {
+        Some(']') if !ranges.is_empty() => break,
+        Some(']') => return Err("empty character class".into()),
+        Some('\\') => chars.next().ok_or("trailing `\\`")?,
+        Some(c) => c,
+        None => return Err("unclosed character class".into()),
+      };
+      // Parse a range, `-` before `]` is a literal.
+      if chars.peek() == Some(&'-') && chars.clone().nth(1).is_some_and(|c| c != ']') {
+        chars.next();
+        let end = match chars.next() {
+          Some('\\') => chars.next().ok_or("trailing `\\`")?,
+          Some(end) => end,
+          None => return Err("unclosed character class".into()),
+        };
+        if end < c {
+          return Err(format!("invalid range `{c}-{end}`"));
+        }
+        ranges.push((c, end));
+      } else {
+        ranges.push((c, c));
+      }
+    }
+    Ok(Self::Class { negated, ranges })
+  }
+
+  /// Returns `true` if the atom matches the given character.
+  fn matches(&self, c: char) -> bool {
+    match self {
+      Self::Char(ch) => *ch == c,
+      Self::Class { negated, ranges } => {
+        ranges.iter().any(|(s, e)| (*s..=*e).contains(&c)) != *negated
+      }
+    }
+  }
+}
+
+/// Repetition of an atom.
+enum Repeat {
+  /// Exactly once.
+  One,
+  /// Zero or more times (`*`).
+  ZeroOrMore,
+  /// One or more times (`+`).
+  OneOrMore,
+}
+
+/// Custom keywords.
+mod kw {
+  syn::custom_keyword!(cfg);
+  syn::custom_keyword!(collapse);
+  syn::custom_keyword!(decode);
+  syn::custom_keyword!(discriminant);
+  syn::custom_keyword!(feature_of);
+  syn::custom_keyword!(file);
+  syn::custom_keyword!(if_set);
+  syn::custom_keyword!(join);
+  syn::custom_keyword!(matches);
+  syn::custom_keyword!(of);
+  syn::custom_keyword!(provenance);
+  syn::custom_keyword!(replace);
+  syn::custom_keyword!(sep);
+  syn::custom_keyword!(strict);
+  syn::custom_keyword!(strip_prefix);
+}
+
+/// AST of the `env_doc` and `env_bytes` macros.
+struct EnvDoc {
+  name: LitStr,
+  default: Option<LitStr>,
+}
+
+impl Parse for EnvDoc {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // Parse literal string.
+    let name = input.parse()?;
+    // Parse the optional default string.
+    let default = if input.peek(Token![?]) {
+      input.parse::<Token![?]>()?;
+      input.parse::<Token![:]>()?;
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    Ok(Self { name, default })
+  }
+}
+
+/// AST of the `env_list` macro.
+struct EnvList {
+  name: LitStr,
+  default: Option<LitStr>,
+  sep: Option<LitStr>,
+}
+
+impl Parse for EnvList {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // Parse literal string.
+    let name = input.parse()?;
+    // Parse the optional default string.
+    let default = if input.peek(Token![?]) {
+      input.parse::<Token![?]>()?;
+      input.parse::<Token![:]>()?;
+      Some(input.parse()?)
+    } else {
+      None
+    };
+    // Parse the optional delimiter.
+    let sep = if input.peek(kw::sep) {
+      input.parse::<kw::sep>()?;
+      let sep: LitStr = input.parse()?;
+      if sep.value().is_empty() {
+        return Err(Error::new(sep.span(), "delimiter can not be empty"));
+      }
+      Some(sep)
+    } else {
+      None
+    };
+    Ok(Self { name, default, sep })
+  }
+}
+
+/// AST of macros that use Cargo environment variables.
+struct CargoId {
+  apply_to: Option<ApplyTo>,
+}
+
+impl Parse for CargoId {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let apply_to = if input.is_empty() {
+      None
+    } else {
+      Some(input.parse::<ApplyTo>()?)
+    };
+    Ok(Self { apply_to })
+  }
+}
+
+/// Default identifier, or the custom error message given by `?!`.
+struct DefaultId {
+  _question: Token![?],
+  /// `:` of `?:`, `None` for `?!`.
+  _colon: Option<Token![:]>,
+  kind: DefaultKind,
+}
+
+/// Kind of the default identifier.
+enum DefaultKind {
+  /// An identifier.
+  Ident(Ident),
+  /// A macro invocation that expands to an identifier.
+  Macro(Macro),
+  /// `_`, for skipping the environment variable.
+  Skip,
+  /// Custom error message, reported if the environment variable is not set.
+  Error(LitStr),
+}
 
-use proc_macro::TokenStream;
-use quote::quote;
-use syn::{
-  parse::{Parse, ParseStream},
-  Error, Ident, LitStr, Result, Token,
-};
+impl DefaultId {
+  /// Resolves the default identifier, returns `None` if the default is `_`.
+  ///
+  /// `self_lower` and `self_upper` are derived from the given name by
+  /// converting it to lowercase or uppercase.
+  fn resolve(self, name: &LitStr) -> Result<Option<Value>> {
+    let ident = match self.kind {
+      DefaultKind::Ident(ident) => ident,
+      DefaultKind::Macro(mac) => return Ok(Some(Value::Macro(mac))),
+      DefaultKind::Skip => return Ok(None),
+      DefaultKind::Error(msg) => return Err(Error::new(name.span(), msg.value())),
+    };
+    let derived = match ident.to_string().as_str() {
+      "self_lower" => name.value().to_lowercase(),
+      "self_upper" => name.value().to_uppercase(),
+      _ => return Ok(Some(Value::Ident(ident))),
+    };
+    let mut derived_ident: Ident = syn::parse_str(&derived).map_err(|_| {
+      Error::new(
+        ident.span(),
+        format!(
+          "`{derived}` derived from `{}` is not a valid identifier",
+          name.value()
+        ),
+      )
+    })?;
+    derived_ident.set_span(ident.span());
+    Ok(Some(Value::Ident(derived_ident)))
+  }
 
-/// Uses the given environment variable as an identifier.
-/// 
-/// See the [module-level documentation](self) for more information.
-/// 
-/// # Definition
-/// 
-/// ```
-/// macro_rules! env_id {
-///   ($name:literal) => { ... };
-///   ($name:literal ?: $default_id:ident) => { ... };
-///   ($name:literal ?: $default_id:ident => $apply_to:ident) => { ... };
-/// }
-/// ```
-#[proc_macro]
-pub fn env_id(tokens: TokenStream) -> TokenStream {
-  match parse_env_id(tokens) {
-    Ok(tokens) => tokens,
-    Err(err) => err.to_compile_error().into(),
+  /// Resolves the default identifier like [`DefaultId::resolve`], but
+  /// reports an error for macro invocations.
+  fn resolve_ident(self, name: &LitStr) -> Result<Option<Ident>> {
+    match self.resolve(name)? {
+      Some(Value::Ident(ident)) => Ok(Some(ident)),
+      Some(Value::Macro(mac)) => Err(macro_default_error(&mac, "used here")),
+      Some(Value::Env(..)) => unreachable!(),
+      None => Ok(None),
+    }
   }
 }
 
-/// Parses the `env_id` macro.
-fn parse_env_id(tokens: TokenStream) -> Result<TokenStream> {
-  // Parse macro input.
-  let env_id: EnvId = syn::parse(tokens)?;
-  // Get value of the environment variable.
-  let ident = match std::env::var(env_id.name.value()) {
-    Ok(value) => Ident::new(&value, env_id.name.span()),
-    Err(e) => env_id.default_id.map(|d| d.ident).ok_or(Error::new(
-      env_id.name.span(),
-      format!("failed to get environment variable: {e}"),
-    ))?,
-  };
-  // Generate result.
-  Ok(TokenStream::from(if let Some(apply_to) = env_id.apply_to {
-    let m = apply_to.ident;
-    quote!(#m!(#ident);)
-  } else {
-    quote!(#ident)
-  }))
+impl Parse for DefaultId {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let _question = input.parse()?;
+    // Parse `?! "message"`.
+    if input.peek(Token![!]) {
+      input.parse::<Token![!]>()?;
+      return Ok(Self {
+        _question,
+        _colon: None,
+        kind: DefaultKind::Error(input.parse()?),
+      });
+    }
+    Ok(Self {
+      _question,
+      _colon: Some(input.parse()?),
+      kind: input.parse()?,
+    })
+  }
 }
 
-/// AST of the `env_id` macro.
-struct EnvId {
+impl Parse for DefaultKind {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // Parse the identifier, a macro invocation, or `_` for skipping.
+    if input.peek(Token![_]) {
+      input.parse::<Token![_]>()?;
+      Ok(Self::Skip)
+    } else if input.fork().parse::<Macro>().is_ok() {
+      Ok(Self::Macro(input.parse()?))
+    } else {
+      Ok(Self::Ident(input.parse()?))
+    }
+  }
+}
+
+/// Discriminant of the first identifier, the following identifiers get
+/// auto-incremented discriminants.
+struct Discriminant {
+  _comma: Token![,],
+  _discriminant: kw::discriminant,
+  value: LitInt,
+}
+
+impl Discriminant {
+  /// Attaches discriminants to the given tokens, produces `tokens = value`.
+  fn attach(&self, tokens: Vec<TokenStream2>) -> Result<Vec<TokenStream2>> {
+    let base: u128 = self.value.base10_parse()?;
+    let suffix = self.value.suffix();
+    tokens
+      .into_iter()
+      .enumerate()
+      .map(|(i, tokens)| {
+        let value = base
+          .checked_add(i as u128)
+          .ok_or_else(|| Error::new(self.value.span(), "discriminant overflowed"))?;
+        let value = LitInt::new(&format!("{value}{suffix}"), self.value.span());
+        Ok(quote!(#tokens = #value))
+      })
+      .collect()
+  }
+}
+
+impl Parse for Discriminant {
+  fn parse(input: ParseStream) -> Result<Self> {
+    Ok(Self {
+      _comma: input.parse()?,
+      _discriminant: input.parse()?,
+      value: input.parse()?,
+    })
+  }
+}
+
+/// AST of the `env_id_try` macro.
+struct EnvIdTry {
   name: LitStr,
-  default_id: Option<DefaultId>,
   apply_to: Option<ApplyTo>,
 }
 
-impl Parse for EnvId {
+impl Parse for EnvIdTry {
   fn parse(input: ParseStream) -> Result<Self> {
     // Parse literal string.
     let name = input.parse()?;
-    // Parse the optional default identifier.
-    let default_id = if input.peek(Token![?]) {
-      Some(input.parse::<DefaultId>()?)
+    // Parse the optional apply-to macro.
+    let apply_to = if input.peek(Token![=>]) {
+      Some(input.parse()?)
     } else {
       None
     };
-    // Parse the optional apply-to macro.
-    let apply_to = if input.peek(Token![=>]) {
-      Some(input.parse::<ApplyTo>()?)
+    Ok(Self { name, apply_to })
+  }
+}
+
+/// AST of the `env_id_runtime` macro.
+struct EnvIdRuntime {
+  vis: Visibility,
+  name: LitStr,
+  default: Option<DefaultId>,
+}
+
+impl Parse for EnvIdRuntime {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // Parse the visibility and the literal string.
+    let vis = input.parse()?;
+    let name = input.parse()?;
+    // Parse the optional default identifier.
+    let default = if input.peek(Token![?]) {
+      Some(input.parse()?)
     } else {
       None
     };
-    Ok(Self {
-      name,
-      default_id,
-      apply_to,
-    })
+    Ok(Self { vis, name, default })
   }
 }
 
-/// Default identifier.
-struct DefaultId {
-  _question: Token![?],
-  _colon: Token![:],
-  ident: Ident,
+/// Provenance of identifiers, i.e. the original values and the names of
+/// environment variables.
+struct Provenance {
+  _comma: Token![,],
+  provenance: kw::provenance,
 }
 
-impl Parse for DefaultId {
+impl Provenance {
+  /// Attaches the given sources to the given tokens, produces
+  /// `tokens, "value", "NAME"`.
+  fn attach(&self, tokens: Vec<TokenStream2>, sources: Vec<(String, String)>) -> Vec<TokenStream2> {
+    let span = self.provenance.span;
+    tokens
+      .into_iter()
+      .zip(sources)
+      .map(|(tokens, (value, name))| {
+        let value = LitStr::new(&value, span);
+        let name = LitStr::new(&name, span);
+        quote!(#tokens, #value, #name)
+      })
+      .collect()
+  }
+}
+
+impl Parse for Provenance {
   fn parse(input: ParseStream) -> Result<Self> {
     Ok(Self {
-      _question: input.parse()?,
-      _colon: input.parse()?,
-      ident: input.parse()?,
+      _comma: input.parse()?,
+      provenance: input.parse()?,
     })
   }
 }
@@ -139,14 +2867,1262 @@ impl Parse for DefaultId {
 /// Apply-to macro.
 struct ApplyTo {
   _fat_arrow: Token![=>],
-  ident: Ident,
+  path: syn::Path,
 }
 
 impl Parse for ApplyTo {
   fn parse(input: ParseStream) -> Result<Self> {
     Ok(Self {
       _fat_arrow: input.parse()?,
-      ident: input.parse()?,
+      path: input.parse()?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::ffi::OsString;
+
+  fn lit(name: &str) -> LitStr {
+    LitStr::new(name, Span::call_site())
+  }
+
+  #[test]
+  fn test_var_error_not_present() {
+    let err = var_error(&lit("HELLO"), VarError::NotPresent, "ident");
+    assert_eq!(
+      err.to_string(),
+      "environment variable `HELLO` is not set; provide a default with `?: ident`"
+    );
+  }
+
+  #[test]
+  fn test_var_error_not_unicode() {
+    let err = var_error(
+      &lit("HELLO"),
+      VarError::NotUnicode(OsString::new()),
+      "ident",
+    );
+    assert_eq!(
+      err.to_string(),
+      "environment variable `HELLO` is not valid UTF-8"
+    );
+  }
+
+  #[test]
+  fn test_sanitize() {
+    assert_eq!(sanitize("my-crate"), "my_crate");
+    assert_eq!(sanitize("my_crate"), "my_crate");
+    assert_eq!(sanitize("a-b-c"), "a_b_c");
+  }
+
+  #[test]
+  fn test_sanitize_ident() {
+    let sanitized = |v| sanitize_ident(v, false).unwrap();
+    assert_eq!(sanitized("my data-source 1"), "my_data_source_1");
+    assert_eq!(sanitized("a - b"), "a___b");
+    assert_eq!(sanitized("1st"), "_1st");
+    assert_eq!(sanitized("-1"), "_1");
+    assert_eq!(sanitized("café au lait"), "café_au_lait");
+    assert_eq!(sanitized("数据源"), "数据源");
+    assert_eq!(sanitized("λ→μ"), "λ_μ");
+    assert_eq!(sanitize_ident("", false), None);
+  }
+
+  #[test]
+  fn test_sanitize_ident_collapse() {
+    let collapsed = |v| sanitize_ident(v, true).unwrap();
+    assert_eq!(collapsed("my data-source 1"), "my_data_source_1");
+    assert_eq!(collapsed("a - b"), "a_b");
+    assert_eq!(collapsed("a__b"), "a_b");
+    assert_eq!(collapsed("1 - st"), "_1_st");
+    assert_eq!(collapsed("--"), "_");
+  }
+
+  #[test]
+  fn test_join() {
+    let tokens =
+      parse_env_id(quote!(join "_" of "CARGO_PKG_NAME", "CARGO_PKG_VERSION_MAJOR")).unwrap();
+    assert_eq!(tokens.to_string(), "env_id_0");
+    let tokens = parse_env_id(quote!(join "" of "CARGO_PKG_NAME", "CARGO_PKG_NAME" => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (env_idenv_id) ;");
+  }
+
+  #[test]
+  fn test_join_missing() {
+    let tokens =
+      parse_env_id(quote!(join "_" of "CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: fallback))
+        .unwrap();
+    assert_eq!(tokens.to_string(), "env_id_fallback");
+    let tokens =
+      parse_env_id(quote!(join "__" of "ENV_ID_SURELY_NOT_SET" ?: a, "ENV_ID_SURELY_NOT_SET" ?: b))
+        .unwrap();
+    assert_eq!(tokens.to_string(), "a__b");
+    let err =
+      parse_env_id(quote!(join "_" of "CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET")).unwrap_err();
+    assert!(err.to_string().contains("is not set"));
+  }
+
+  #[test]
+  fn test_join_invalid() {
+    let err = parse_env_id(quote!(join "-" of "CARGO_PKG_NAME", "CARGO_PKG_NAME")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "separator `-` contains `-`, which can not be used in an identifier"
+    );
+    let err =
+      parse_env_id(quote!(join "_" of "CARGO_PKG_VERSION_MAJOR", "CARGO_PKG_NAME")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "joined value `0_env_id` is not a valid identifier"
+    );
+  }
+
+  #[test]
+  fn test_int_mode() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_VERSION_MAJOR" as int)).unwrap();
+    assert_eq!(tokens.to_string(), "0");
+    let tokens = parse_env_id(quote!("CARGO_PKG_VERSION_MAJOR" as int in 0..=9 => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (0) ;");
+    let tokens = parse_env_id(quote!("CARGO_PKG_VERSION_MAJOR" as int in ..1)).unwrap();
+    assert_eq!(tokens.to_string(), "0");
+    std::env::set_var("ENV_ID_TEST_INT", "-5");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_INT" as int in -9..)).unwrap();
+    assert_eq!(tokens.to_string(), "- 5");
+    std::env::remove_var("ENV_ID_TEST_INT");
+  }
+
+  #[test]
+  fn test_int_mode_error() {
+    let err = parse_env_id(quote!("CARGO_PKG_VERSION_MAJOR" as int in 1..=9)).unwrap_err();
+    assert_eq!(err.to_string(), "value `0` is out of range");
+    let err = parse_env_id(quote!("CARGO_PKG_VERSION_MAJOR" as int in -1..0)).unwrap_err();
+    assert_eq!(err.to_string(), "value `0` is out of range");
+    let err = parse_env_id(quote!("CARGO_PKG_NAME" as int in 0..=9)).unwrap_err();
+    assert_eq!(err.to_string(), "value `env_id` is not an integer");
+    let err = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: a as int)).unwrap_err();
+    assert_eq!(err.to_string(), "expected an integer, found identifier `a`");
+    assert!(parse_env_id(quote!("CARGO_PKG_VERSION_MAJOR" as int in 0..=)).is_err());
+  }
+
+  #[test]
+  fn test_path_keyword() {
+    for keyword in ["Self", "crate", "super", "self"] {
+      std::env::set_var("ENV_ID_TEST_PATH_KEYWORD", keyword);
+      let err = parse_env_id(quote!("ENV_ID_TEST_PATH_KEYWORD")).unwrap_err();
+      assert!(
+        err.to_string().starts_with(&format!(
+          "value `{keyword}` is a path keyword, which can not be used as a plain identifier"
+        )),
+        "{err}"
+      );
+      assert!(parse_env_id(quote!("ENV_ID_TEST_PATH_KEYWORD" as Ident)).is_err());
+      std::env::set_var("ENV_ID_TEST_PATH_KEYWORD", format!("APP_{keyword}"));
+      assert!(parse_env_id(quote!("ENV_ID_TEST_PATH_KEYWORD" strip_prefix "APP_")).is_err());
+    }
+    std::env::remove_var("ENV_ID_TEST_PATH_KEYWORD");
+  }
+
+  #[test]
+  fn test_snake_case() {
+    assert_eq!(snake_case("hello").as_deref(), Some("hello"));
+    assert_eq!(snake_case("HelloWorld").as_deref(), Some("hello_world"));
+    assert_eq!(snake_case("HTTPServer2").as_deref(), Some("http_server2"));
+    assert_eq!(
+      snake_case("my-data source_").as_deref(),
+      Some("my_data_source")
+    );
+    assert_eq!(snake_case("v2Api").as_deref(), Some("v2_api"));
+    assert_eq!(snake_case("a.b"), None);
+    assert_eq!(snake_case(" - "), None);
+  }
+
+  #[test]
+  fn test_pascal_case() {
+    assert_eq!(pascal_case("hello").as_deref(), Some("Hello"));
+    assert_eq!(pascal_case("hello_world").as_deref(), Some("HelloWorld"));
+    assert_eq!(pascal_case("HTTP server").as_deref(), Some("HttpServer"));
+    assert_eq!(pascal_case("a.b"), None);
+  }
+
+  #[test]
+  fn test_variant_ident() {
+    let span = Span::call_site();
+    assert_eq!(variant_ident("Hello", span).unwrap().to_string(), "Hello");
+    assert_eq!(variant_ident("type", span).unwrap().to_string(), "r#type");
+    assert!(variant_ident("Self", span).is_err());
+    assert!(variant_ident("2d", span).is_err());
+  }
+
+  #[test]
+  fn test_variant_mode() {
+    std::env::set_var("ENV_ID_TEST_VARIANT", "lowercase");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_VARIANT" as Variant)).unwrap();
+    assert_eq!(tokens.to_string(), "Lowercase");
+    std::env::set_var("ENV_ID_TEST_VARIANT", "snake_case_value");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_VARIANT" as Variant => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (SnakeCaseValue) ;");
+    std::env::set_var("ENV_ID_TEST_VARIANT", "type");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_VARIANT" as Variant)).unwrap();
+    assert_eq!(tokens.to_string(), "Type");
+    std::env::set_var("ENV_ID_TEST_VARIANT", "self");
+    let err = parse_env_id(quote!("ENV_ID_TEST_VARIANT" as Variant)).unwrap_err();
+    assert!(
+      err
+        .to_string()
+        .starts_with("value `Self` is a path keyword"),
+      "{err}"
+    );
+    std::env::set_var("ENV_ID_TEST_VARIANT", "2d mode");
+    let err = parse_env_id(quote!("ENV_ID_TEST_VARIANT" as Variant)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "converted value `2dMode` is not a valid enum variant"
+    );
+    std::env::set_var("ENV_ID_TEST_VARIANT", "a.b");
+    let err = parse_env_id(quote!("ENV_ID_TEST_VARIANT" as Variant)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "value `a.b` can not be converted to an enum variant"
+    );
+    std::env::remove_var("ENV_ID_TEST_VARIANT");
+  }
+
+  #[test]
+  fn test_type_mode() {
+    std::env::set_var("ENV_ID_TEST_TYPE", "Config");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_TYPE" as type)).unwrap();
+    assert_eq!(tokens.to_string(), "Config");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_TYPE" as type strict => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (Config) ;");
+    // Lowercase values only warn.
+    std::env::set_var("ENV_ID_TEST_TYPE", "config");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_TYPE" as type)).unwrap();
+    let tokens = tokens.to_string();
+    assert!(tokens.starts_with("< [config ;"), "{tokens}");
+    assert!(
+      tokens.ends_with("] as :: core :: iter :: IntoIterator > :: Item"),
+      "{tokens}"
+    );
+    assert!(tokens.contains("deprecated"), "{tokens}");
+    assert!(
+      tokens
+        .contains("value `config` is used as a type, but does not start with an uppercase letter"),
+      "{tokens}"
+    );
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_TYPE" as type => m)).unwrap();
+    let tokens = tokens.to_string();
+    assert!(tokens.starts_with("const _ : usize ="), "{tokens}");
+    assert!(tokens.contains("deprecated"), "{tokens}");
+    assert!(tokens.ends_with("m ! (config) ;"), "{tokens}");
+    // Default identifiers are not checked.
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_TYPE_UNSET" ?: config as type)).unwrap();
+    assert_eq!(tokens.to_string(), "config");
+    // Lowercase values are errors in strict mode.
+    let err = parse_env_id(quote!("ENV_ID_TEST_TYPE" as type strict)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "value `config` is used as a type, but does not start with an uppercase letter"
+    );
+    std::env::set_var("ENV_ID_TEST_TYPE", "_Config");
+    assert!(parse_env_id(quote!("ENV_ID_TEST_TYPE" as type strict)).is_err());
+    std::env::remove_var("ENV_ID_TEST_TYPE");
+  }
+
+  #[test]
+  fn test_transform_mode() {
+    std::env::set_var("ENV_ID_TEST_TRANSFORM", "  UserName ");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_TRANSFORM" as Trim | SnakeCase)).unwrap();
+    assert_eq!(tokens.to_string(), "user_name");
+    let tokens = parse_env_id(quote!(
+      "ENV_ID_TEST_TRANSFORM" as Trim | SnakeCase | Prefix("get_") => m
+    ))
+    .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (get_user_name) ;");
+    let tokens = parse_env_id(quote!(
+      "ENV_ID_TEST_TRANSFORM" as Trim | Upper | Suffix("_ID")
+    ))
+    .unwrap();
+    assert_eq!(tokens.to_string(), "USERNAME_ID");
+    std::env::remove_var("ENV_ID_TEST_TRANSFORM");
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: a as Prefix("get_"))).unwrap();
+    assert_eq!(tokens.to_string(), "a");
+  }
+
+  #[test]
+  fn test_transform_case() {
+    std::env::set_var("ENV_ID_TEST_CASE", "Foo");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_CASE" as Upper)).unwrap();
+    assert_eq!(tokens.to_string(), "FOO");
+    std::env::set_var("ENV_ID_TEST_CASE", "BAR");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_CASE" as Lower)).unwrap();
+    assert_eq!(tokens.to_string(), "bar");
+    // Non-ASCII characters are left untouched.
+    std::env::set_var("ENV_ID_TEST_CASE", "Straße");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_CASE" as Upper)).unwrap();
+    assert_eq!(tokens.to_string(), "STRAßE");
+    std::env::set_var("ENV_ID_TEST_CASE", "ÉCOLE");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_CASE" as Lower)).unwrap();
+    assert_eq!(tokens.to_string(), "École");
+    std::env::remove_var("ENV_ID_TEST_CASE");
+  }
+
+  #[test]
+  fn test_transform_mode_error() {
+    std::env::set_var("ENV_ID_TEST_TRANSFORM_ERROR", "user.name");
+    let err = parse_env_id(quote!("ENV_ID_TEST_TRANSFORM_ERROR" as Trim | SnakeCase)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "stage `SnakeCase` can not convert value `user.name`"
+    );
+    let err = parse_env_id(quote!("ENV_ID_TEST_TRANSFORM_ERROR" as Trim | Lower)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "transformed value `user.name` is not a valid identifier"
+    );
+    std::env::set_var("ENV_ID_TEST_TRANSFORM_ERROR", "   ");
+    let err = parse_env_id(quote!("ENV_ID_TEST_TRANSFORM_ERROR" as Trim)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "stage `Trim` got value `   `, which is empty after trimming"
+    );
+    std::env::remove_var("ENV_ID_TEST_TRANSFORM_ERROR");
+    assert!(parse_env_id(quote!("CARGO_PKG_NAME" as Trim | Unknown)).is_err());
+    assert!(parse_env_id(quote!("CARGO_PKG_NAME" as Prefix)).is_err());
+  }
+
+  #[test]
+  fn test_decode_base64() {
+    assert_eq!(decode_base64("bXlfaWRlbnQ=").unwrap(), b"my_ident");
+    assert_eq!(decode_base64("bXlfaWRlbnQ").unwrap(), b"my_ident");
+    assert_eq!(decode_base64("bXk=").unwrap(), b"my");
+    assert_eq!(decode_base64("").unwrap(), b"");
+    assert_eq!(
+      decode_base64("bX!f").unwrap_err(),
+      "invalid character `!` at index 2"
+    );
+    assert_eq!(decode_base64("bXk").unwrap(), b"my");
+    assert!(decode_base64("bXk==").is_err());
+    assert!(decode_base64("b").is_err());
+    assert!(decode_base64("bX===").is_err());
+  }
+
+  #[test]
+  fn test_decode_hex() {
+    assert_eq!(decode_hex("6d795f6964656e74").unwrap(), b"my_ident");
+    assert_eq!(decode_hex("6D795F").unwrap(), b"my_");
+    assert_eq!(decode_hex("6d7").unwrap_err(), "odd number of digits");
+    assert_eq!(
+      decode_hex("6g").unwrap_err(),
+      "invalid character `g` at index 1"
+    );
+  }
+
+  #[test]
+  fn test_decode_mode() {
+    std::env::set_var("ENV_ID_TEST_DECODE_BASE64", "bXlfaWRlbnQ=");
+    let tokens = parse_env_id(quote!("ENV_ID_TEST_DECODE_BASE64" decode base64)).unwrap();
+    assert_eq!(tokens.to_string(), "my_ident");
+    std::env::set_var("ENV_ID_TEST_DECODE_HEX", "6d795f6964656e74");
+    let tokens = parse_env_id(quote!(
+      "ENV_ID_TEST_DECODE_HEX" decode hex strip_prefix "my_" as Upper => m
+    ))
+    .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (IDENT) ;");
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: a decode hex)).unwrap();
+    assert_eq!(tokens.to_string(), "a");
+    std::env::remove_var("ENV_ID_TEST_DECODE_BASE64");
+    std::env::remove_var("ENV_ID_TEST_DECODE_HEX");
+  }
+
+  #[test]
+  fn test_decode_mode_error() {
+    std::env::set_var("ENV_ID_TEST_DECODE_ERROR", "bXlf!WRlbnQ=");
+    let err = parse_env_id(quote!("ENV_ID_TEST_DECODE_ERROR" decode base64)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "value `bXlf!WRlbnQ=` is not valid base64: invalid character `!` at index 4"
+    );
+    let err = parse_env_id(quote!("ENV_ID_TEST_DECODE_ERROR" decode hex)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "value `bXlf!WRlbnQ=` is not valid hex: invalid character `X` at index 1"
+    );
+    std::env::set_var("ENV_ID_TEST_DECODE_ERROR", "ff");
+    let err = parse_env_id(quote!("ENV_ID_TEST_DECODE_ERROR" decode hex)).unwrap_err();
+    assert_eq!(err.to_string(), "decoded value of `ff` is not valid UTF-8");
+    std::env::remove_var("ENV_ID_TEST_DECODE_ERROR");
+    let err = parse_env_id(quote!("CARGO_PKG_NAME" decode base32)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "unknown decoding `base32`, expected `base64` or `hex`"
+    );
+  }
+
+  #[test]
+  fn test_pattern() {
+    let pattern = |s: &str| syn::parse_str::<Pattern>(&format!("{s:?}")).unwrap();
+    let p = pattern("^[a-z][a-z0-9_]*$");
+    assert!(p.is_match("env_id"));
+    assert!(p.is_match("a"));
+    assert!(!p.is_match("Env_id"));
+    assert!(!p.is_match("1st"));
+    assert!(!p.is_match("env-id"));
+    assert!(!p.is_match(""));
+    let p = pattern("_id+");
+    assert!(p.is_match("env_id"));
+    assert!(p.is_match("my_idd_x"));
+    assert!(!p.is_match("my_i"));
+    let p = pattern("^ab*c$");
+    assert!(p.is_match("ac"));
+    assert!(p.is_match("abbc"));
+    assert!(!p.is_match("abbd"));
+    let p = pattern("^[^-]+\\.rs$");
+    assert!(p.is_match("main.rs"));
+    assert!(!p.is_match("main-a.rs"));
+    assert!(!p.is_match("mainXrs"));
+    let p = pattern("^[a-]+$");
+    assert!(p.is_match("a-a"));
+    assert!(!p.is_match("b"));
+  }
+
+  #[test]
+  fn test_pattern_error() {
+    let err = |s: &str| {
+      syn::parse_str::<Pattern>(&format!("{s:?}"))
+        .err()
+        .unwrap()
+        .to_string()
+    };
+    assert_eq!(
+      err("*a"),
+      "invalid pattern: `*` must follow a character or a class"
+    );
+    assert_eq!(
+      err("a**"),
+      "invalid pattern: `*` must follow a character or a class"
+    );
+    assert_eq!(
+      err("a^"),
+      "invalid pattern: `^` is only supported as an anchor"
+    );
+    assert_eq!(
+      err("a$b"),
+      "invalid pattern: `$` is only supported as an anchor"
+    );
+    assert_eq!(
+      err("a.b"),
+      "invalid pattern: `.` is not supported, use `\\.` to match it literally"
+    );
+    assert_eq!(err("[]"), "invalid pattern: empty character class");
+    assert_eq!(err("[a-z"), "invalid pattern: unclosed character class");
+    assert_eq!(err("[z-a]"), "invalid pattern: invalid range `z-a`");
+    assert_eq!(err("a\\"), "invalid pattern: trailing `\\`");
+  }
+
+  #[test]
+  fn test_pattern_check() {
+    std::env::set_var("ENV_ID_TEST_PATTERN", "APP_user_name");
+    let tokens = parse_env_id(quote!(
+      "ENV_ID_TEST_PATTERN" strip_prefix "APP_" matches "^[a-z][a-z0-9_]*$"
+    ))
+    .unwrap();
+    assert_eq!(tokens.to_string(), "user_name");
+    let tokens = parse_env_id(quote!(
+      "ENV_ID_TEST_PATTERN" matches "^APP_" as Trim | Lower => m
+    ))
+    .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (app_user_name) ;");
+    let err = parse_env_id(quote!("ENV_ID_TEST_PATTERN" matches "^[a-z][a-z0-9_]*$")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "value `APP_user_name` does not match pattern `^[a-z][a-z0-9_]*$`"
+    );
+    std::env::remove_var("ENV_ID_TEST_PATTERN");
+    let tokens =
+      parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: Default matches "^[a-z]+$")).unwrap();
+    assert_eq!(tokens.to_string(), "Default");
+  }
+
+  #[test]
+  fn test_sanitized_ident_mode() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_DESCRIPTION" as Ident)).unwrap();
+    assert_eq!(
+      tokens.to_string(),
+      "Use_environment_variables_as_identifiers_"
+    );
+    let tokens =
+      parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: a as Ident(collapse) => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (a) ;");
+    assert!(parse_env_id(quote!("CARGO_PKG_NAME" as Ident(unknown))).is_err());
+  }
+
+  #[test]
+  fn test_env_id_try() {
+    let tokens = parse_env_id_try(quote!("CARGO_PKG_NAME")).unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let tokens = parse_env_id_try(quote!("CARGO_PKG_NAME" => def_fn)).unwrap();
+    assert_eq!(tokens.to_string(), "def_fn ! (env_id) ;");
+    let tokens = parse_env_id_try(quote!("ENV_ID_SURELY_NOT_SET")).unwrap();
+    assert!(tokens.is_empty());
+    let tokens = parse_env_id_try(quote!("ENV_ID_SURELY_NOT_SET" => def_fn)).unwrap();
+    assert_eq!(tokens.to_string(), "def_fn ! () ;");
+  }
+
+  #[test]
+  fn test_env_id_try_error() {
+    let err = parse_env_id_try(quote!("CARGO_PKG_DESCRIPTION")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "value `Use environment variables as identifiers.` is not a valid identifier"
+    );
+    std::env::set_var("ENV_ID_TEST_TRY_KEYWORD", "Self");
+    assert!(parse_env_id_try(quote!("ENV_ID_TEST_TRY_KEYWORD")).is_err());
+    std::env::remove_var("ENV_ID_TEST_TRY_KEYWORD");
+  }
+
+  #[test]
+  fn test_env_id_runtime() {
+    // Set at build time.
+    let tokens = parse_env_id_runtime(quote!("CARGO_PKG_NAME"))
+      .unwrap()
+      .to_string();
+    assert!(tokens.starts_with("fn env_id ()"), "{tokens}");
+    assert!(tokens.contains("var (\"CARGO_PKG_NAME\")"), "{tokens}");
+    assert!(
+      tokens.contains("option_env ! (\"CARGO_PKG_NAME\")"),
+      "{tokens}"
+    );
+    // Only available at runtime.
+    let tokens = parse_env_id_runtime(quote!(pub(crate) "ENV_ID_SURELY_NOT_SET" ?: runtime_only))
+      .unwrap()
+      .to_string();
+    assert!(
+      tokens.starts_with("pub (crate) fn runtime_only ()"),
+      "{tokens}"
+    );
+    let tokens = parse_env_id_runtime(quote!("ENV_ID_SURELY_NOT_SET" ?: _)).unwrap();
+    assert!(tokens.is_empty());
+  }
+
+  #[test]
+  fn test_env_id_runtime_error() {
+    let err = parse_env_id_runtime(quote!("ENV_ID_SURELY_NOT_SET")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "environment variable `ENV_ID_SURELY_NOT_SET` is not set; provide a default with `?: ident`"
+    );
+    assert!(parse_env_id_runtime(quote!("CARGO_PKG_DESCRIPTION")).is_err());
+  }
+
+  #[test]
+  fn test_cargo_id_apply_to() {
+    let tokens = parse_cargo_id("CARGO_PKG_NAME", quote!(=> def_const)).unwrap();
+    assert_eq!(tokens.to_string(), "def_const ! (env_id) ;");
+  }
+
+  #[test]
+  fn test_cargo_id_missing() {
+    assert!(parse_cargo_id("ENV_ID_SURELY_NOT_SET", quote!()).is_err());
+  }
+
+  #[test]
+  fn test_list() {
+    let tokens = parse_env_id(quote! {
+      ["CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: b, "CARGO_PKG_NAME"] => build_table
+    })
+    .unwrap();
+    assert_eq!(tokens.to_string(), "build_table ! (env_id , b , env_id) ;");
+  }
+
+  #[test]
+  fn test_list_missing_without_default() {
+    let err = parse_env_id(quote!(["CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET"] => m));
+    assert!(err.unwrap_err().to_string().contains("is not set"));
+  }
+
+  #[test]
+  fn test_list_without_apply_to() {
+    assert!(parse_env_id(quote!(["CARGO_PKG_NAME"])).is_err());
+  }
+
+  #[test]
+  fn test_record_value() {
+    let mut resolved = HashMap::new();
+    assert_eq!(record_value(&mut resolved, "A", "a"), Ok(()));
+    assert_eq!(record_value(&mut resolved, "A", "a"), Ok(()));
+    assert_eq!(record_value(&mut resolved, "B", "a"), Ok(()));
+    assert_eq!(record_value(&mut resolved, "A", "b"), Err("a".into()));
+    assert_eq!(resolved["A"], "a");
+  }
+
+  #[test]
+  fn test_strict_mixed_sources() {
+    let span = Span::call_site();
+    let file = source_key("ENV_ID_TEST_STRICT", true);
+    let env = source_key("ENV_ID_TEST_STRICT", false);
+    assert_eq!(file, "file:ENV_ID_TEST_STRICT");
+    assert_eq!(env, "ENV_ID_TEST_STRICT");
+    // Values of the environment file and the process environment do not
+    // conflict with each other.
+    check_strict(&file, "a", span).unwrap();
+    check_strict(&env, "b", span).unwrap();
+    check_strict(&file, "a", span).unwrap();
+    check_strict(&env, "b", span).unwrap();
+    let err = check_strict(&file, "b", span).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "variable `ENV_ID_TEST_STRICT` in `build_id.env` resolved to `b`, \
+       but it was previously resolved to `a`"
+    );
+    let err = check_strict(&env, "a", span).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "environment variable `ENV_ID_TEST_STRICT` resolved to `a`, \
+       but it was previously resolved to `b`"
+    );
+  }
+
+  #[test]
+  fn test_manifest_line() {
+    assert_eq!(manifest_line("A", "a"), "A=a\n");
+    assert_eq!(manifest_line("A", ""), "A=\n");
+    assert_eq!(manifest_line("A", "a\\b\nc\rd"), "A=a\\\\b\\nc\\rd\n");
+  }
+
+  #[test]
+  fn test_append_manifest() {
+    let path = std::env::temp_dir().join(format!("env_id_manifest_{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    append_manifest(&path, "A", "a").unwrap();
+    append_manifest(&path, "B", "b\nc").unwrap();
+    append_manifest(&path, "A", "a").unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(content, "A=a\nB=b\\nc\nA=a\n");
+  }
+
+  #[test]
+  fn test_lifetime() {
+    let lt = lifetime("a", Span::call_site()).unwrap();
+    assert_eq!(lt.to_string(), "'a");
+    assert_eq!(
+      lifetime("static", Span::call_site()).unwrap().ident,
+      "static"
+    );
+    assert!(lifetime("'a", Span::call_site()).is_err());
+    assert!(lifetime("1a", Span::call_site()).is_err());
+    assert!(lifetime("a b", Span::call_site()).is_err());
+    assert!(lifetime("", Span::call_site()).is_err());
+  }
+
+  #[test]
+  fn test_lifetime_mode() {
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: a as lifetime => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! ('a) ;");
+    let tokens = parse_env_id(quote!(["CARGO_PKG_NAME"] as lifetime => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! ('env_id) ;");
+    assert!(parse_env_id(quote!("CARGO_PKG_NAME" as unknown)).is_err());
+  }
+
+  #[test]
+  fn test_strip_prefix() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" strip_prefix "env_")).unwrap();
+    assert_eq!(tokens.to_string(), "id");
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" strip_prefix "app_")).unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let tokens =
+      parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: env_a strip_prefix "env_")).unwrap();
+    assert_eq!(tokens.to_string(), "env_a");
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" strip_prefix "env_" as lifetime)).unwrap();
+    assert_eq!(tokens.to_string(), "'id");
+    let err = parse_env_id(quote!("CARGO_PKG_NAME" strip_prefix "env_id")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "value `env_id` is empty after stripping the prefix"
+    );
+  }
+
+  #[test]
+  fn test_replace() {
+    std::env::set_var("ENV_ID_TEST_REPLACE", "api.example-v2:8080");
+    let tokens = parse_env_id(quote! {
+      "ENV_ID_TEST_REPLACE" replace { "." => "_dot_", "-" => "_", ":" => "_port_" }
+    })
+    .unwrap();
+    assert_eq!(tokens.to_string(), "api_dot_example_v2_port_8080");
+    // Entries are applied in order.
+    let tokens = parse_env_id(quote! {
+      "ENV_ID_TEST_REPLACE" replace { "." => "_dot_", "_dot_" => "_", "-" => "", ":" => "_" }
+    })
+    .unwrap();
+    assert_eq!(tokens.to_string(), "api_examplev2_8080");
+    let tokens = parse_env_id(quote! {
+      "ENV_ID_TEST_REPLACE" replace { "_dot_" => "_", "." => "_dot_", "-" => "", ":" => "_" }
+    })
+    .unwrap();
+    assert_eq!(tokens.to_string(), "api_dot_examplev2_8080");
+    // Works with other modifiers.
+    let tokens = parse_env_id(quote! {
+      "ENV_ID_TEST_REPLACE" strip_prefix "api." replace { "-" => "_", ":" => "_", } as Upper => m
+    })
+    .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (EXAMPLE_V2_8080) ;");
+    // Default identifiers are not replaced.
+    let tokens =
+      parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: a_b replace { "_" => "x" })).unwrap();
+    assert_eq!(tokens.to_string(), "a_b");
+    // Unmapped special characters are reported.
+    let err = parse_env_id(quote!("ENV_ID_TEST_REPLACE" replace { "." => "_" })).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "value `api_example-v2:8080` is not a valid identifier"
+    );
+    let err = parse_env_id(quote!("ENV_ID_TEST_REPLACE" replace { "" => "_" })).unwrap_err();
+    assert_eq!(err.to_string(), "can not replace an empty string");
+    std::env::remove_var("ENV_ID_TEST_REPLACE");
+  }
+
+  #[test]
+  fn test_check_ident() {
+    let span = Span::call_site();
+    assert!(check_ident("hello", span).is_ok());
+    assert!(check_ident("_1", span).is_ok());
+    assert!(check_ident("type", span).is_ok());
+    assert!(check_ident("", span).is_err());
+    assert!(check_ident("1a", span).is_err());
+    assert!(check_ident("a.b", span).is_err());
+    assert!(check_ident("r#type", span).is_err());
+  }
+
+  #[test]
+  fn test_missing_without_default() {
+    let err = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET")).unwrap_err();
+    assert!(err.to_string().contains("is not set"));
+  }
+
+  #[test]
+  fn test_skip_default() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" ?: _ => maybe_def)).unwrap();
+    assert_eq!(tokens.to_string(), "maybe_def ! (env_id) ;");
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: _ => maybe_def)).unwrap();
+    assert!(tokens.is_empty());
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: _)).unwrap();
+    assert!(tokens.is_empty());
+    let tokens = parse_env_id(quote!(
+      ["CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: _, "ENV_ID_SURELY_NOT_SET" ?: b] => m
+    ))
+    .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (env_id , b) ;");
+    let tokens = parse_env_id(quote!(
+      join "_" of "CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: _
+    ))
+    .unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let tokens = parse_env_id(quote!(feature_of "surely_not_enabled": a ?: _ => m)).unwrap();
+    assert!(tokens.is_empty());
+  }
+
+  #[test]
+  fn test_macro_default() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" ?: gen_default!())).unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: gen_default!())).unwrap();
+    assert_eq!(tokens.to_string(), "gen_default ! ()");
+    let tokens =
+      parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: crate::gen_default!(a) as Upper)).unwrap();
+    assert_eq!(tokens.to_string(), "crate :: gen_default ! (a)");
+    // Macro defaults are fine if the environment variable is set.
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" ?: gen_default!() => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (env_id) ;");
+  }
+
+  #[test]
+  fn test_macro_default_error() {
+    let err = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: gen_default!() => m)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "macro default can not be passed to an apply-to macro, since `env_id` can not expand it; \
+       use an identifier instead"
+    );
+    let err =
+      parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: gen_default!() as lifetime)).unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("macro default can not be used as a lifetime"));
+    let err = parse_env_id(quote!(
+      join "_" of "CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: gen_default!()
+    ))
+    .unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("macro default can not be joined"));
+    let err = parse_env_id_runtime(quote!("ENV_ID_SURELY_NOT_SET" ?: gen_default!())).unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("macro default can not be used here"));
+  }
+
+  #[test]
+  fn test_missing_with_default() {
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: hello)).unwrap();
+    assert_eq!(tokens.to_string(), "hello");
+  }
+
+  #[test]
+  fn test_custom_error() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" ?! "set CARGO_PKG_NAME")).unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let input = r#"  "ENV_ID_SURELY_NOT_SET" ?! "set ENV_ID_SURELY_NOT_SET to your widget type""#;
+    let err = parse_env_id(input.parse().unwrap()).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "set ENV_ID_SURELY_NOT_SET to your widget type"
+    );
+    assert_eq!(err.span().start().column, 2);
+    let err = parse_env_id(quote! {
+      ["CARGO_PKG_NAME", cfg "env_id_test" ?! "cfg `env_id_test` is required"] => m
+    })
+    .unwrap_err();
+    assert_eq!(err.to_string(), "cfg `env_id_test` is required");
+    assert!(parse_env_id(quote!("CARGO_PKG_NAME" ?! hello)).is_err());
+    let err = parse_env_id(quote!("CARGO_PKG_NAME" ?! "a" ?: b)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "duplicate default identifier, only one `?:` is allowed"
+    );
+  }
+
+  #[test]
+  fn test_env_doc() {
+    let tokens = parse_env_doc(quote!("CARGO_PKG_NAME")).unwrap();
+    assert_eq!(tokens.to_string(), "\"env_id\"");
+    let tokens = parse_env_doc(quote!("CARGO_PKG_NAME" ?: "no description")).unwrap();
+    assert_eq!(tokens.to_string(), "\"env_id\"");
+    let tokens = parse_env_doc(quote!("ENV_ID_SURELY_NOT_SET" ?: "no description")).unwrap();
+    assert_eq!(tokens.to_string(), "\"no description\"");
+  }
+
+  #[test]
+  fn test_env_doc_missing_without_default() {
+    let err = parse_env_doc(quote!("ENV_ID_SURELY_NOT_SET")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "environment variable `ENV_ID_SURELY_NOT_SET` is not set; \
+       provide a default with `?: \"text\"`"
+    );
+  }
+
+  #[test]
+  fn test_env_bytes() {
+    let tokens = parse_env_bytes(quote!("CARGO_PKG_NAME")).unwrap();
+    assert_eq!(tokens.to_string(), "b\"env_id\"");
+    let tokens = parse_env_bytes(quote!("ENV_ID_SURELY_NOT_SET" ?: "")).unwrap();
+    assert_eq!(tokens.to_string(), "b\"\"");
+    std::env::set_var("ENV_ID_TEST_BYTES", "say \"hi\"\\\n\u{7f}\u{e9}");
+    let tokens = parse_env_bytes(quote!("ENV_ID_TEST_BYTES" ?: "")).unwrap();
+    std::env::remove_var("ENV_ID_TEST_BYTES");
+    let lit: LitByteStr = syn::parse2(tokens).unwrap();
+    assert_eq!(lit.value(), "say \"hi\"\\\n\u{7f}\u{e9}".as_bytes());
+    let tokens = lit.into_token_stream().to_string();
+    assert_eq!(tokens, "b\"say \\\"hi\\\"\\\\\\n\\x7F\\xC3\\xA9\"");
+  }
+
+  #[test]
+  fn test_env_bytes_missing_without_default() {
+    let err = parse_env_bytes(quote!("ENV_ID_SURELY_NOT_SET")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "environment variable `ENV_ID_SURELY_NOT_SET` is not set; \
+       provide a default with `?: \"bytes\"`"
+    );
+  }
+
+  #[test]
+  fn test_split_list() {
+    assert_eq!(split_list("a,b,c", ","), ["a", "b", "c"]);
+    assert_eq!(split_list(" a , b ,c ", ","), ["a", "b", "c"]);
+    assert_eq!(split_list("a", ","), ["a"]);
+    assert_eq!(split_list("a,,b", ","), ["a", "", "b"]);
+    assert_eq!(split_list("a; b;c", ";"), ["a", "b", "c"]);
+    assert_eq!(split_list("a::b", "::"), ["a", "b"]);
+    assert!(split_list("", ",").is_empty());
+    assert!(split_list("  ", ",").is_empty());
+  }
+
+  #[test]
+  fn test_env_list() {
+    let tokens = parse_env_list(quote!("ENV_ID_SURELY_NOT_SET" ?: "a, b,c")).unwrap();
+    assert_eq!(tokens.to_string(), r#"["a" , "b" , "c"]"#);
+    let tokens = parse_env_list(quote!("CARGO_PKG_NAME" ?: "a, b")).unwrap();
+    assert_eq!(tokens.to_string(), r#"["env_id"]"#);
+    let tokens = parse_env_list(quote!("ENV_ID_SURELY_NOT_SET" ?: "")).unwrap();
+    assert_eq!(tokens.to_string(), "[]");
+    let tokens = parse_env_list(quote!("ENV_ID_SURELY_NOT_SET" ?: "a;b, c" sep ";")).unwrap();
+    assert_eq!(tokens.to_string(), r#"["a" , "b, c"]"#);
+  }
+
+  #[test]
+  fn test_env_list_error() {
+    let err = parse_env_list(quote!("ENV_ID_SURELY_NOT_SET")).unwrap_err();
+    assert!(err
+      .to_string()
+      .contains("provide a default with `?: \"a,b\"`"));
+    let err = parse_env_list(quote!("CARGO_PKG_NAME" sep "")).unwrap_err();
+    assert_eq!(err.to_string(), "delimiter can not be empty");
+  }
+
+  fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/build_id.env")
+  }
+
+  #[test]
+  fn test_parse_env_file() {
+    let vars = parse_env_file("# comment\n\nA=a\n  B = b c \nC=\nD=d=e\n").unwrap();
+    assert_eq!(vars.len(), 4);
+    assert_eq!(vars["A"], "a");
+    assert_eq!(vars["B"], "b c");
+    assert_eq!(vars["C"], "");
+    assert_eq!(vars["D"], "d=e");
+    assert_eq!(parse_env_file("A=a\nB\n"), Err(2));
+    assert_eq!(parse_env_file("=a\n"), Err(1));
+  }
+
+  #[test]
+  fn test_load_env_file() {
+    let vars = load_env_file(&fixture()).unwrap();
+    assert_eq!(vars["FIXTURE_ID"], "from_file");
+    assert_eq!(vars["CARGO_PKG_NAME"], "overridden");
+    assert!(load_env_file(&fixture().with_file_name("missing.env"))
+      .unwrap()
+      .is_empty());
+  }
+
+  #[test]
+  fn test_file_var() {
+    let dir = fixture().parent().unwrap().to_path_buf();
+    let var = |name| file_var(&dir, &LitStr::new(name, Span::call_site())).unwrap();
+    assert_eq!(var("FIXTURE_ID").as_deref(), Some("from_file"));
+    assert_eq!(var("CARGO_PKG_NAME").as_deref(), Some("overridden"));
+    assert_eq!(var("CARGO_PKG_VERSION"), None);
+  }
+
+  #[test]
+  fn test_lookup_var() {
+    let dir = fixture().parent().unwrap().to_path_buf();
+    let lookup =
+      |name, file_dir| lookup_var(&LitStr::new(name, Span::call_site()), file_dir).unwrap();
+    // Variables in the environment file are keyed by `file:NAME`.
+    let (key, value) = lookup("FIXTURE_ID", Some(&dir));
+    assert_eq!(key, "file:FIXTURE_ID");
+    assert_eq!(value.unwrap(), "from_file");
+    let (key, value) = lookup("CARGO_PKG_NAME", Some(&dir));
+    assert_eq!(key, "file:CARGO_PKG_NAME");
+    assert_eq!(value.unwrap(), "overridden");
+    // Others fall back to the process environment.
+    let (key, value) = lookup("CARGO_PKG_VERSION", Some(&dir));
+    assert_eq!(key, "CARGO_PKG_VERSION");
+    assert_eq!(value.unwrap(), env!("CARGO_PKG_VERSION"));
+    let (key, value) = lookup("CARGO_PKG_NAME", None);
+    assert_eq!(key, "CARGO_PKG_NAME");
+    assert_eq!(value.unwrap(), "env_id");
+    let (key, value) = lookup("FIXTURE_ID", None);
+    assert_eq!(key, "FIXTURE_ID");
+    assert_eq!(value, Err(VarError::NotPresent));
+  }
+
+  #[test]
+  fn test_file_mode() {
+    // The environment file of this package does not exist,
+    // values fall back to the process environment.
+    let tokens = parse_env_id(quote!(file "CARGO_PKG_NAME")).unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let tokens = parse_env_id(quote!(file "ENV_ID_SURELY_NOT_SET" ?: hello)).unwrap();
+    assert_eq!(tokens.to_string(), "hello");
+    let tokens = parse_env_id(quote!(file ["CARGO_PKG_NAME"] => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (env_id) ;");
+  }
+
+  #[test]
+  fn test_discriminant() {
+    let tokens =
+      parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: unknown, discriminant 0 => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (unknown = 0) ;");
+    let tokens = parse_env_id(quote! {
+      ["CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: b], discriminant 7u8 => m
+    })
+    .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (env_id = 7u8 , b = 8u8) ;");
+  }
+
+  #[test]
+  fn test_provenance() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME", provenance => m)).unwrap();
+    assert_eq!(
+      tokens.to_string(),
+      r#"m ! (env_id , "env_id" , "CARGO_PKG_NAME") ;"#
+    );
+    let tokens = parse_env_id(quote! {
+      ["ENV_ID_SURELY_NOT_SET" ?: a, "ENV_ID_SURELY_NOT_SET" ?: _, cfg "env_id_test"
+        ?: c], discriminant 1, provenance => m
+    })
+    .unwrap();
+    assert_eq!(
+      tokens.to_string(),
+      r#"m ! (a = 1 , "a" , "ENV_ID_SURELY_NOT_SET" , c = 2 , "c" , "CARGO_CFG_ENV_ID_TEST") ;"#
+    );
+    // The original value is passed, regardless of transformations.
+    std::env::set_var("ENV_ID_TEST_PROVENANCE", "APP_hello");
+    let tokens = parse_env_id(quote! {
+      "ENV_ID_TEST_PROVENANCE", provenance strip_prefix "APP_" as Upper => m
+    })
+    .unwrap();
+    assert_eq!(
+      tokens.to_string(),
+      r#"m ! (HELLO , "APP_hello" , "ENV_ID_TEST_PROVENANCE") ;"#
+    );
+    std::env::remove_var("ENV_ID_TEST_PROVENANCE");
+  }
+
+  #[test]
+  fn test_provenance_error() {
+    let err = parse_env_id(quote!("CARGO_PKG_NAME", provenance)).unwrap_err();
+    assert_eq!(err.to_string(), "provenance requires an apply-to macro");
+    let err = parse_env_id(quote!(join "_" of "A" ?: a, "B" ?: b, provenance => m)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "provenance can not be used with joined values"
+    );
+  }
+
+  #[test]
+  fn test_discriminant_without_apply_to() {
+    let err = parse_env_id(quote!("CARGO_PKG_NAME", discriminant 0)).unwrap_err();
+    assert_eq!(err.to_string(), "a discriminant requires an apply-to macro");
+  }
+
+  #[test]
+  fn test_feature_var() {
+    assert_eq!(feature_var("fast"), "CARGO_FEATURE_FAST");
+    assert_eq!(feature_var("my-feature"), "CARGO_FEATURE_MY_FEATURE");
+  }
+
+  #[test]
+  fn test_feature_of() {
+    let input = quote!(feature_of "env-id-test-fast": fast_impl ?: slow_impl);
+    let tokens = parse_env_id(input.clone()).unwrap();
+    assert_eq!(tokens.to_string(), "slow_impl");
+    std::env::set_var("CARGO_FEATURE_ENV_ID_TEST_FAST", "1");
+    let tokens = parse_env_id(input).unwrap();
+    assert_eq!(tokens.to_string(), "fast_impl");
+    let tokens = parse_env_id(quote! {
+      [feature_of "env-id-test-fast": a, feature_of "env-id-test-slow": b ?: c] => m
     })
+    .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (a , c) ;");
+    std::env::remove_var("CARGO_FEATURE_ENV_ID_TEST_FAST");
+  }
+
+  #[test]
+  fn test_if_set() {
+    let input = quote!(if_set "ENV_ID_TEST_DEBUG_MODE" ? debug_impl : release_impl);
+    std::env::remove_var("ENV_ID_TEST_DEBUG_MODE");
+    let tokens = parse_env_id(input.clone()).unwrap();
+    assert_eq!(tokens.to_string(), "release_impl");
+    // Only the presence matters, not the value.
+    std::env::set_var("ENV_ID_TEST_DEBUG_MODE", "");
+    let tokens = parse_env_id(input).unwrap();
+    assert_eq!(tokens.to_string(), "debug_impl");
+    let tokens = parse_env_id(quote! {
+      [if_set "ENV_ID_TEST_DEBUG_MODE" ? a : b, if_set "ENV_ID_TEST_UNSET" ? c : _] => m
+    })
+    .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (a) ;");
+    std::env::remove_var("ENV_ID_TEST_DEBUG_MODE");
+    let err = parse_env_id(quote!(if_set "ENV_ID_TEST_DEBUG_MODE" ? a)).unwrap_err();
+    assert_eq!(err.to_string(), "expected `:`");
+  }
+
+  #[test]
+  fn test_feature_of_missing_without_default() {
+    let err = parse_env_id(quote!(feature_of "env-id-test-missing": a)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "feature `env-id-test-missing` is not enabled; provide a default with `?: ident`"
+    );
+  }
+
+  #[test]
+  fn test_cfg_var() {
+    assert_eq!(cfg_var("target_arch"), "CARGO_CFG_TARGET_ARCH");
+    assert_eq!(cfg_var("target-os"), "CARGO_CFG_TARGET_OS");
+  }
+
+  #[test]
+  fn test_cfg() {
+    let input = quote!(cfg "env_id_test_arch" ?: generic => m);
+    let tokens = parse_env_id(input.clone()).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (generic) ;");
+    std::env::set_var("CARGO_CFG_ENV_ID_TEST_ARCH", "x86_64");
+    let tokens = parse_env_id(input).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (x86_64) ;");
+    let tokens =
+      parse_env_id(quote!(join "_" of "CARGO_PKG_NAME", cfg "env_id_test_arch")).unwrap();
+    assert_eq!(tokens.to_string(), "env_id_x86_64");
+    std::env::remove_var("CARGO_CFG_ENV_ID_TEST_ARCH");
+  }
+
+  #[test]
+  fn test_cfg_error() {
+    let err = parse_env_id(quote!(cfg "env_id_test_missing")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "environment variable `CARGO_CFG_ENV_ID_TEST_MISSING` is not set; \
+       Cargo only sets it for build scripts, \
+       forward it with `cargo:rustc-env=CARGO_CFG_ENV_ID_TEST_MISSING=...` \
+       or provide a default with `?: ident`"
+    );
+    std::env::set_var("CARGO_CFG_ENV_ID_TEST_FEATURE", "sse,sse2");
+    let err = parse_env_id(quote!(cfg "env_id_test_feature")).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "`CARGO_CFG_ENV_ID_TEST_FEATURE` has multiple values `sse,sse2`, \
+       which can not be an identifier"
+    );
+    std::env::remove_var("CARGO_CFG_ENV_ID_TEST_FEATURE");
+  }
+
+  #[test]
+  fn test_span_mode() {
+    let lit: LitStr = syn::parse_str("   \"NAME\"").unwrap();
+    let debug = |span: Span| format!("{span:?}");
+    assert_ne!(debug(lit.span()), debug(Span::call_site()));
+    assert_eq!(debug(SpanMode::Literal.span(lit.span())), debug(lit.span()));
+    assert_eq!(
+      debug(SpanMode::CallSite.span(lit.span())),
+      debug(Span::call_site())
+    );
+    assert_eq!(
+      debug(SpanMode::MixedSite.span(lit.span())),
+      debug(Span::mixed_site())
+    );
+  }
+
+  #[test]
+  fn test_span_mode_parse() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" @call_site)).unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let tokens =
+      parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: a @mixed_site, discriminant 0 => m)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (a = 0) ;");
+    let err = parse_env_id(quote!("CARGO_PKG_NAME" @def_site)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "unknown span `def_site`, expected `call_site` or `mixed_site`"
+    );
+  }
+
+  #[test]
+  fn test_trailing_comma() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME",)).unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: a as Ident,)).unwrap();
+    assert_eq!(tokens.to_string(), "a");
+    let tokens =
+      parse_env_id(quote!(["CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: a,] => m,)).unwrap();
+    assert_eq!(tokens.to_string(), "m ! (env_id , a) ;");
+    let tokens =
+      parse_env_id(quote!(join "_" of "CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: a,)).unwrap();
+    assert_eq!(tokens.to_string(), "env_id_a");
+    let tokens =
+      parse_env_id(quote!(join "_" of "CARGO_PKG_NAME", "ENV_ID_SURELY_NOT_SET" ?: a, => m))
+        .unwrap();
+    assert_eq!(tokens.to_string(), "m ! (env_id_a) ;");
+    let tokens = parse_env_id(quote!(join "_" of "ENV_ID_SURELY_NOT_SET" ?: a, as Ident,)).unwrap();
+    assert_eq!(tokens.to_string(), "a");
+  }
+
+  #[test]
+  fn test_malformed_clauses() {
+    // Returns the error message and the column of its span.
+    let parse_err = |input: &str| {
+      let err = parse_env_id(input.parse().unwrap()).unwrap_err();
+      (err.to_string(), err.span().start().column)
+    };
+    assert_eq!(
+      parse_err(r#""A" ?: a ?: b"#),
+      (
+        "duplicate default identifier, only one `?:` is allowed".into(),
+        9
+      )
+    );
+    assert_eq!(
+      parse_err(r#"if_set "A" ? a : b ?: c"#),
+      (
+        "duplicate default identifier, only one `?:` is allowed".into(),
+        19
+      )
+    );
+    assert_eq!(
+      parse_err(r#"join "_" of "A" ?: a ?: b, "B""#),
+      (
+        "duplicate default identifier, only one `?:` is allowed".into(),
+        21
+      )
+    );
+    assert_eq!(
+      parse_err(r#""A" as Ident as type"#),
+      (
+        "unexpected `as`, each clause can appear only once and in the documented order".into(),
+        13
+      )
+    );
+    assert_eq!(
+      parse_err(r#""A" as Ident strip_prefix "a""#),
+      (
+        "unexpected `strip_prefix`, each clause can appear only once and in the documented \
+         order"
+          .into(),
+        13
+      )
+    );
+    assert_eq!(
+      parse_err(r#""A" => m ?: a"#),
+      (
+        "unexpected `?:`, each clause can appear only once and in the documented order".into(),
+        9
+      )
+    );
+    assert_eq!(parse_err(r#""A",,"#), ("unexpected token".into(), 3));
+    assert_eq!(parse_err(r#""A", "B""#), ("unexpected token".into(), 3));
+  }
+
+  #[test]
+  fn test_apply_to_path() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" => crate::m)).unwrap();
+    assert_eq!(tokens.to_string(), "crate :: m ! (env_id) ;");
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" => crate::gen::def_const)).unwrap();
+    assert_eq!(tokens.to_string(), "crate :: gen :: def_const ! (env_id) ;");
+    let tokens = parse_env_id(quote!(["CARGO_PKG_NAME"] => ::other::m)).unwrap();
+    assert_eq!(tokens.to_string(), ":: other :: m ! (env_id) ;");
+    let tokens = parse_cargo_id("CARGO_PKG_NAME", quote!(=> self::gen::m)).unwrap();
+    assert_eq!(tokens.to_string(), "self :: gen :: m ! (env_id) ;");
+  }
+
+  #[test]
+  fn test_self_lower_upper() {
+    let tokens = parse_env_id(quote!("CARGO_PKG_NAME" ?: self_upper)).unwrap();
+    assert_eq!(tokens.to_string(), "env_id");
+    let tokens = parse_env_id(quote!("ENV_ID_SURELY_NOT_SET" ?: self_lower)).unwrap();
+    assert_eq!(tokens.to_string(), "env_id_surely_not_set");
+    let tokens = parse_env_id(quote!("env_id_surely_not_set" ?: self_upper)).unwrap();
+    assert_eq!(tokens.to_string(), "ENV_ID_SURELY_NOT_SET");
+    let err = parse_env_id(quote!("ENV-ID-SURELY-NOT-SET" ?: self_lower)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "`env-id-surely-not-set` derived from `ENV-ID-SURELY-NOT-SET` is not a valid identifier"
+    );
+    assert!(parse_env_id(quote!("FN" ?: self_lower)).is_err());
   }
 }
@@ -0,0 +1,18 @@
+//! Checks the errors reported by `env_id` with `trybuild`.
+
+#[test]
+fn not_set() {
+  std::env::remove_var("ENV_ID_SURELY_NOT_SET");
+  trybuild::TestCases::new().compile_fail("tests/ui/not_set.rs");
+}
+
+#[cfg(unix)]
+#[test]
+fn not_utf8() {
+  use std::ffi::OsStr;
+  use std::os::unix::ffi::OsStrExt;
+
+  // The variable is inherited by `rustc` invoked by `trybuild`.
+  std::env::set_var("ENV_ID_NOT_UTF8", OsStr::from_bytes(b"\xff"));
+  trybuild::TestCases::new().compile_fail("tests/ui/not_utf8.rs");
+}
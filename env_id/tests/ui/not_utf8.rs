@@ -0,0 +1,5 @@
+use env_id::env_id;
+
+fn main() {
+  let env_id!("ENV_ID_NOT_UTF8") = 1;
+}
@@ -18,8 +18,14 @@
 //! foo::<10>(); // Fine.
 //! ```
 //!
-#![cfg_attr(build = "debug", doc = "```should_panic")]
-#![cfg_attr(build = "release", doc = "```compile_fail")]
+#![cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#![cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
 //! # fn foo<const N: usize>() {
 //! #   use build_assert::build_assert;
 //! #   build_assert!(N > 5);
@@ -66,9 +72,12 @@
 //! enable the `no_asm` feature.
 //!
 //! When `no_asm` is enabled, [`build_assert`] raises a link error by referencing
-//! an undefined symbol if the assertion fails. By default, the symbol name is
-//! `__build_error_impl`. To avoid symbol conflicts, you can set the environment
-//! variable `BUILD_ERROR_SYM` to specify a different symbol before building:
+//! an undefined symbol if the assertion fails. Each assertion references a
+//! distinct symbol, which is named after a prefix, the crate name and a
+//! counter (see [`build_uniq_sym`]), e.g. `__build_error_impl_my_crate_0`. By
+//! default, the prefix is `__build_error_impl`. To avoid symbol conflicts, you
+//! can set the environment variable `BUILD_ERROR_SYM` to specify a different
+//! prefix before building:
 //!
 //! ```text
 //! BUILD_ERROR_SYM=hello cargo build --release
@@ -77,6 +86,148 @@
 //! Note that if the project has been previously built, the build cache should be
 //! cleared to ensure this change takes effect.
 //!
+//! To help tools (e.g. editor integrations) tell errors raised by this crate
+//! from other errors, you can set the environment variable `BUILD_ERROR_TAG`
+//! to a tag consisting of ASCII alphanumerics and underscores. Messages raised
+//! by [`build_error`] (and so by [`build_assert`] and friends) are then
+//! prefixed with `[TAG] ` in both debug and release mode, and with `no_asm`
+//! enabled, the tag is appended to the prefix of undefined symbols, e.g.
+//! `__build_error_impl_TAG_my_crate_0`:
+//!
+//! ```text
+//! BUILD_ERROR_TAG=BA cargo build --release
+//! # error: ... [BA] build error at src/main.rs:3:3: N must be greater than 5
+//! ```
+//!
+//! The build cache should be cleared as well when changing the tag. Const
+//! panics raised by the `strict_debug` and `const_panic` features (see below)
+//! are not tagged.
+//!
+//! If you intentionally want a runtime fallback instead of a link error, you
+//! can enable the `error_handler` feature (which implies `no_asm`), and define
+//! a handler with [`define_build_error_handler`]. In release mode, all failed
+//! assertions call the handler with the formatted message, rather than
+//! referencing distinct symbols:
+//!
+//! ```text
+//! build_assert::define_build_error_handler!(|msg| panic!("build error: {msg}"));
+//! ```
+//!
+//! If you want assertions to fail the build in debug mode too, you can enable
+//! the `strict_debug` feature. When enabled, conditions of [`build_assert`]
+//! that are considered const-evaluable (i.e. only consist of integer, `bool`
+//! and `char` literals, constants or const generics in `SCREAMING_SNAKE_CASE`,
+//! `size_of`/`align_of` calls, and operators on them) are evaluated in an
+//! inline const block, so they will raise build-time errors in debug mode.
+//! Since the types of constants are unknown, constants with a single-segment
+//! path are assumed to be const generics, and each comparison or arithmetic
+//! operator must have one of them, a literal, a cast or a `size_of`/`align_of`
+//! call on at least one side. So `N == M` and `Self::SIZE > 0` are evaluated
+//! in an inline const block, but `NAME == "foo"` and `Self::A == Self::B` are
+//! not. Other conditions still
+//! panic at runtime. Since const panics do not support format arguments,
+//! custom messages with format arguments are replaced by the default one.
+//!
+#![cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#![cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+//! # use build_assert::build_assert;
+//! build_assert!(false); // Fails to compile in debug mode with `strict_debug`.
+//! ```
+//!
+//! The same mechanism can be used in release mode by enabling the
+//! experimental `const_panic` feature. When enabled, const-evaluable
+//! conditions raise const panics instead of inline assembly errors, which
+//! requires Rust 1.79 or later for inline const blocks referring to const
+//! generics. Other conditions fall back to inline assembly (or link errors
+//! with `no_asm`). Note that the build error is then reported as a failed
+//! const evaluation, so the message format changes:
+//!
+//! ```text
+//! error[E0080]: evaluation panicked: N must be greater than 5
+//! ```
+//!
+//! The `warn_non_const` feature has no effect when `const_panic` is enabled.
+//!
+//! In release mode, whether a condition that is not const-evaluable fails the
+//! build depends on the optimizer. You can enable the `warn_non_const` feature to emit a
+//! warning for conditions of [`build_assert`] that are not considered
+//! const-evaluable (see `strict_debug` above, calls on const-evaluable
+//! operands like `N.is_power_of_two()` are considered const-evaluable as
+//! well) in release mode:
+//!
+//! ```text
+//! warning: use of deprecated constant `foo::build_assert_non_const`: build_assert condition is not const-evaluable; it may not fail the build
+//! ```
+//!
+//! On size-constrained targets, the formatting machinery pulled in by
+//! messages of [`build_assert_eq`], [`build_assert_eq_display`],
+//! [`build_assert_ne`], [`build_assert_lt`], [`build_assert_le`],
+//! [`build_assert_gt`], [`build_assert_ge`], [`build_assert_distinct`],
+//! [`build_assert_str_eq`], [`build_assert_starts_with`] and
+//! [`build_assert_ends_with`] may be undesirable. You can enable the `no_fmt` feature to replace their
+//! messages with static ones, so operands are no longer displayed and are not
+//! required to implement [`Debug`](core::fmt::Debug) or
+//! [`Display`](core::fmt::Display).
+//!
+//! To let the log aggregator capture failed assertions in debug mode, you can
+//! enable the `log` feature (which requires `std`). When enabled,
+//! [`build_error`] reports the message with `log::error!` immediately before
+//! panicking. It has no effect in release mode.
+//!
+//! On embedded targets using [`defmt`](https://docs.rs/defmt), you can enable
+//! the `defmt` feature. When enabled, [`build_error`] panics with
+//! `defmt::panic!` instead of [`core::panic!`] in debug mode, so the message
+//! is captured by the `defmt` logger. The message is formatted with
+//! [`core::fmt`] and forwarded as a string, so operands are not required to
+//! implement `defmt::Format`. It has no effect in release mode.
+//!
+//! For test harnesses, you can enable the `test_hook` feature (which requires
+//! `std`), and call [`set_build_error_hook`] to install a hook. In debug mode,
+//! [`build_error`] calls the hook with the formatted message right before
+//! panicking, so tests can count and inspect triggered assertions.
+//!
+//! For post-build tooling, you can enable the `emit_metadata` feature. When
+//! enabled, each [`build_assert`] writes a record of the form
+//! `file:line:column: condition`, terminated by a NUL byte, into the
+//! `build_assert` link section (`__DATA,__build_assert` on Apple targets).
+//! The records are marked as `#[used]`, so they are kept even if the
+//! assertion is optimized away, and can be found by scanning the section of
+//! the final binary.
+//!
+//! To collect assertions at build time instead, you can enable the
+//! `collect_sites` feature (which requires Rust 1.89), and set the environment
+//! variable `BUILD_ASSERT_DUMP` to a file path when building. Each
+//! [`build_assert`] then appends a JSON line of the form
+//! `{"file":...,"line":...,"column":...,"condition":...}` to the file during
+//! compilation. Since `rustc` may compile multiple crates in parallel, the
+//! file is locked while appending, so lines from different processes never
+//! interleave. Note that only crates that are actually compiled are
+//! collected, and Cargo does not rebuild crates when `BUILD_ASSERT_DUMP`
+//! changes, so you may want to run `cargo clean` first.
+//!
+//! To test that your own assertions fail as expected, you can enable the
+//! `testing` feature (which requires `std`) in your dev-dependencies, and use
+//! [`assert_build_fails`] in tests. It builds the given snippet with the same
+//! profile and features, and checks that it fails to build in release mode,
+//! or panics in debug mode, so you do not need to duplicate the `cfg_attr`s
+//! of doc tests.
+//!
+//! To compile out all assertions without editing call sites (e.g. when
+//! benchmarking), you can enable the `disabled` feature. When enabled,
+//! [`build_assert`] and its variants still type-check their arguments, but
+//! never raise errors or emit any checks. Note that operands of macros other
+//! than [`build_assert`] may still be evaluated. [`build_static_assert`],
+//! [`build_assert_report`], [`build_assert_impl`], [`build_assert_satisfies`],
+//! [`build_assert_dyn_safe`] and [`build_assert`] with a `const` block
+//! condition are not affected, since they are checked by the compiler and
+//! never emit code.
+//!
 //! # Under the Hood
 //!
 //! The [`build_assert`] macro will be expanded to:
@@ -98,20 +249,33 @@
 //! expand to:
 //!
 //! ```compile_fail
-//! core::arch::asm!("build error at file.rs:line:column");
+//! core::arch::asm!("build error at file.rs:line:column: message", options(raw));
 //! ```
 //!
 //! Since `build` is not a valid instruction on any target, the build will fail.
+//! The message (or the format string, if there are format arguments) passed to
+//! [`build_error`] is embedded in the instruction, so it will be shown in the
+//! error message:
+//!
+//! ```text
+//! error: unknown token in expression
+//!   |
+//! note: instantiated into assembly here
+//!  --> <inline asm>:2:17
+//!   |
+//! 2 |     build error at src/main.rs:3:3: N must be greater than 5
+//!   |                    ^
+//! ```
 //!
 //! On targets that do not support inline assembly, the [`build_error`] macro
 //! will expand to:
 //!
 //! ```compile_fail
 //! extern "Rust" {
-//!   fn __build_error_impl() -> !;
+//!   fn __build_error_impl_my_crate_0() -> !;
 //! }
 //!
-//! unsafe { __build_error_impl() }
+//! unsafe { __build_error_impl_my_crate_0() }
 //! ```
 //!
 //! It raises a link error like this:
@@ -121,7 +285,7 @@
 //!   |
 //!   = note: env -u ...
 //!   = note: /usr/bin/ld: ... .o: in function `rust_out::main::...':
-//!           ... .rs:6: undefined reference to `__build_error_impl'
+//!           ... .rs:6: undefined reference to `__build_error_impl_my_crate_0'
 //!           collect2: error: ld returned 1 exit status
 //!
 //!   = note: ...
@@ -140,24 +304,52 @@
 //! [the Rust reference]: https://doc.rust-lang.org/nightly/reference/inline-assembly.html
 //! [Rust for Linux]: https://rust-for-linux.github.io/docs/kernel/macro.build_assert.html
 
-#[cfg(all(build = "release", feature = "no_asm"))]
-macro_rules! decl_fn {
-  ($id:ident) => {
-    #[doc(hidden)]
-    pub fn $id() -> !;
-  };
-}
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "test_hook")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+#[cfg(any(
+  all(build = "release", feature = "no_asm", not(feature = "disabled")),
+  feature = "error_handler"
+))]
+#[doc(hidden)]
+pub use env_id::env_id as __env_id;
+
+#[cfg(not(feature = "disabled"))]
+#[doc(hidden)]
+pub use build_assert_macros::error_tag as __build_error_tag;
 
-#[cfg(all(build = "release", feature = "no_asm"))]
-extern "Rust" {
-  env_id::env_id!("BUILD_ERROR_SYM" ?: __build_error_impl => decl_fn);
+#[cfg(all(
+  build = "release",
+  feature = "no_asm",
+  not(feature = "error_handler"),
+  not(feature = "disabled")
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_uniq {
+  ($prefix:ident) => {
+    $crate::build_uniq_sym!($prefix => $crate::__build_error_call);
+  };
 }
 
-#[cfg(all(build = "release", feature = "no_asm"))]
+#[cfg(all(
+  build = "release",
+  feature = "no_asm",
+  not(feature = "error_handler"),
+  not(feature = "disabled")
+))]
 #[doc(hidden)]
-#[inline(always)]
-pub fn build_error() {
-  unsafe { env_id::env_id!("BUILD_ERROR_SYM" ?: __build_error_impl)() };
+#[macro_export]
+macro_rules! __build_error_call {
+  ($sym:ident) => {{
+    extern "Rust" {
+      fn $sym() -> !;
+    }
+    unsafe { $sym() }
+  }};
 }
 
 /// Raises a build-time error.
@@ -171,14 +363,148 @@ pub fn build_error() {
 /// # use build_assert::build_error;
 /// build_error!("this is a hard error");
 /// ```
-#[cfg(build = "debug")]
+#[cfg(all(build = "debug", not(feature = "disabled")))]
 #[macro_export]
 macro_rules! build_error {
+  (@code $code:literal; $($args:tt)*) => {
+    $crate::build_error!($($args)*)
+  };
+  () => {
+    $crate::build_error!("explicit panic")
+  };
+  ($msg:expr $(, $($args:tt)*)?) => {
+    $crate::__build_error_panic!(
+      "{}{}",
+      $crate::__build_error_tag!(),
+      core::format_args!($msg $(, $($args)*)?)
+    )
+  };
+}
+
+#[cfg(not(any(feature = "log", feature = "test_hook", feature = "defmt")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_panic {
   ($($args:tt)*) => {
     core::panic!($($args)*)
   };
 }
 
+#[cfg(any(feature = "log", feature = "test_hook", feature = "defmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_panic {
+  () => {
+    $crate::__build_error_panic!("explicit panic")
+  };
+  ($($args:tt)+) => {
+    match core::format_args!($($args)+) {
+      args => {
+        $crate::__build_error_log!(args);
+        $crate::__run_build_error_hook(args);
+        $crate::__build_error_abort!(args)
+      }
+    }
+  };
+}
+
+#[cfg(not(feature = "defmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_abort {
+  ($args:expr) => {
+    core::panic!("{}", $args)
+  };
+}
+
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_abort {
+  ($args:expr) => {
+    $crate::__defmt::panic!("{}", $crate::__defmt::Display2Format(&$args))
+  };
+}
+
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+pub use defmt as __defmt;
+
+#[cfg(not(feature = "log"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_log {
+  ($args:expr) => {};
+}
+
+#[cfg(feature = "log")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_log {
+  ($args:expr) => {
+    $crate::__log::error!("{}", $args)
+  };
+}
+
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use log as __log;
+
+/// Hook called by [`build_error`] in debug mode, stored as a `fn(&str)`.
+#[cfg(feature = "test_hook")]
+static BUILD_ERROR_HOOK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Sets the hook to be called with the formatted message by [`build_error`]
+/// in debug mode, right before panicking. Returns the previous hook.
+///
+/// This function is only available with the `test_hook` feature, and the
+/// hook is never called in release mode.
+///
+/// # Examples
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// use build_assert::{build_assert, set_build_error_hook};
+///
+/// set_build_error_hook(|msg| eprintln!("reached: {msg}"));
+/// # let n = 0;
+/// build_assert!(n > 5); // Prints `reached: assertion failed: n > 5`.
+/// ```
+#[cfg(feature = "test_hook")]
+pub fn set_build_error_hook(hook: fn(&str)) -> Option<fn(&str)> {
+  hook_from_ptr(BUILD_ERROR_HOOK.swap(hook as *mut (), Ordering::AcqRel))
+}
+
+/// Removes the hook set by [`set_build_error_hook`], returns it if any.
+///
+/// This function is only available with the `test_hook` feature.
+#[cfg(feature = "test_hook")]
+pub fn take_build_error_hook() -> Option<fn(&str)> {
+  hook_from_ptr(BUILD_ERROR_HOOK.swap(core::ptr::null_mut(), Ordering::AcqRel))
+}
+
+/// Converts the pointer stored in [`BUILD_ERROR_HOOK`] back to a hook.
+#[cfg(feature = "test_hook")]
+fn hook_from_ptr(ptr: *mut ()) -> Option<fn(&str)> {
+  // SAFETY: non-null pointers are only stored by `set_build_error_hook`,
+  // which are converted from `fn(&str)`.
+  (!ptr.is_null()).then(|| unsafe { core::mem::transmute::<*mut (), fn(&str)>(ptr) })
+}
+
+/// Calls the hook set by [`set_build_error_hook`] with the given message.
+#[cfg(any(feature = "log", feature = "test_hook", feature = "defmt"))]
+#[doc(hidden)]
+#[allow(unused_variables)]
+pub fn __run_build_error_hook(args: core::fmt::Arguments) {
+  #[cfg(feature = "test_hook")]
+  if let Some(hook) = hook_from_ptr(BUILD_ERROR_HOOK.load(Ordering::Acquire)) {
+    match args.as_str() {
+      Some(msg) => hook(msg),
+      None => hook(&std::fmt::format(args)),
+    }
+  }
+}
+
 /// Raises a build-time error.
 ///
 /// This macro will stop the compilation process in release mode, and always
@@ -190,21 +516,48 @@ macro_rules! build_error {
 /// # use build_assert::build_error;
 /// build_error!("this is a hard error");
 /// ```
-#[cfg(all(build = "release", not(feature = "no_asm")))]
+#[cfg(all(build = "release", not(feature = "no_asm"), not(feature = "disabled")))]
 #[macro_export]
 macro_rules! build_error {
-  ($($args:tt)*) => {
+  (@code $code:literal; $($args:tt)*) => {
+    $crate::build_error!($($args)*)
+  };
+  () => {{
+    #[allow(named_asm_labels)]
     unsafe {
-      core::arch::asm!(core::concat!(
-        "build error at ",
-        core::file!(),
-        ":",
-        core::line!(),
-        ":",
-        core::column!()
-      ))
+      core::arch::asm!(
+        core::concat!(
+          $crate::__build_error_tag!(),
+          "build error at ",
+          core::file!(),
+          ":",
+          core::line!(),
+          ":",
+          core::column!()
+        ),
+        options(raw)
+      )
     }
-  };
+  }};
+  ($msg:expr $(, $($args:tt)*)?) => {{
+    #[allow(named_asm_labels)]
+    unsafe {
+      core::arch::asm!(
+        core::concat!(
+          $crate::__build_error_tag!(),
+          "build error at ",
+          core::file!(),
+          ":",
+          core::line!(),
+          ":",
+          core::column!(),
+          ": ",
+          $msg
+        ),
+        options(raw)
+      )
+    }
+  }};
 }
 
 /// Raises a build-time error.
@@ -218,263 +571,5359 @@ macro_rules! build_error {
 /// # use build_assert::build_error;
 /// build_error!("this is a hard error");
 /// ```
-#[cfg(all(build = "release", feature = "no_asm"))]
+#[cfg(all(
+  build = "release",
+  feature = "no_asm",
+  not(feature = "error_handler"),
+  not(feature = "disabled")
+))]
 #[macro_export]
 macro_rules! build_error {
-  ($($args:tt)*) => {
-    $crate::build_error()
-  };
+  (@code $code:literal; $($args:tt)*) => {{
+    macro_rules! __build_error_coded {
+      ($prefix:ident) => {
+        $crate::build_uniq_sym!($prefix $code => $crate::__build_error_call);
+      };
+    }
+    $crate::__env_id!(
+      join "_" of "BUILD_ERROR_SYM" ?: __build_error_impl, "BUILD_ERROR_TAG" ?: _
+        => __build_error_coded
+    );
+  }};
+  ($($args:tt)*) => {{
+    $crate::__env_id!(
+      join "_" of "BUILD_ERROR_SYM" ?: __build_error_impl, "BUILD_ERROR_TAG" ?: _
+        => $crate::__build_error_uniq
+    );
+  }};
 }
 
-/// Asserts that a boolean expression is `true` at build-time.
-///
-/// In release mode, if the expression is evaluated to `false`, or the compiler
-/// or optimizer cannot ensure that the expression is evaluated to `true`, this
-/// macro will stop the compilation process.
+/// Raises a build-time error.
 ///
-/// In debug mode, if the expression is evaluated to `false`, this macro will
-/// panic.
+/// Since the `error_handler` feature is enabled, this macro calls the handler
+/// defined by [`define_build_error_handler`] with the formatted message in
+/// release mode, and always panics in debug mode.
 ///
 /// # Examples
 ///
-/// ```
-/// fn foo<const N: usize>() {
-///   # use build_assert::build_assert;
-///   build_assert!(N.is_power_of_two(), "N is not a power of two");
-/// }
-///
-/// foo::<16>(); // Fine.
-/// ```
-///
-#[cfg_attr(build = "debug", doc = "```should_panic")]
-#[cfg_attr(build = "release", doc = "```compile_fail")]
-/// # fn foo<const N: usize>() {
-/// #   use build_assert::build_assert;
-/// #   build_assert!(N.is_power_of_two());
+/// ```should_panic
+/// # use build_assert::{build_error, define_build_error_handler};
+/// define_build_error_handler!(|msg| panic!("{msg}"));
+/// # fn main() {
+/// build_error!("this is a hard error");
 /// # }
-/// foo::<15>(); // Fails to compile in release mode, panics in debug mode.
 /// ```
+#[cfg(all(
+  build = "release",
+  feature = "error_handler",
+  not(feature = "disabled")
+))]
 #[macro_export]
-macro_rules! build_assert {
-  ($cond:expr $(,)?) => {
-    if !$cond {
-      $crate::build_error!(core::concat!("assertion failed: ", core::stringify!($cond)));
-    }
+macro_rules! build_error {
+  (@code $code:literal; $($args:tt)*) => {
+    $crate::build_error!($($args)*)
   };
-  ($cond:expr, $($arg:tt)+) => {
-    if !$cond {
-      $crate::build_error!($($arg)+);
-    }
+  () => {
+    $crate::build_error!(core::concat!(
+      "build error at ",
+      core::file!(),
+      ":",
+      core::line!(),
+      ":",
+      core::column!()
+    ))
   };
+  ($msg:expr $(, $($args:tt)*)?) => {{
+    macro_rules! __build_error_handled {
+      ($sym:ident) => {{
+        extern "Rust" {
+          fn $sym(msg: core::fmt::Arguments<'_>) -> !;
+        }
+        unsafe {
+          $sym(core::format_args!(
+            "{}{}",
+            $crate::__build_error_tag!(),
+            core::format_args!($msg $(, $($args)*)?)
+          ))
+        }
+      }};
+    }
+    $crate::__env_id!("BUILD_ERROR_SYM" ?: __build_error_impl => __build_error_handled);
+  }};
 }
 
-/// Asserts that two expressions are equal to each other at build-time (using
-/// [PartialEq]).
-///
-/// In release mode, if the expression is evaluated to `false`, or the compiler
-/// or optimizer cannot ensure that the expression is evaluated to `true`, this
-/// macro will stop the compilation process.
+/// Defines the handler called by [`build_error`] in release mode, when the
+/// `error_handler` feature is enabled.
 ///
-/// In debug mode, if the expression is evaluated to `false`, this macro will
-/// panic.
+/// The handler receives the formatted message as [`core::fmt::Arguments`],
+/// and must not return. It is exported as a symbol named after the
+/// environment variable `BUILD_ERROR_SYM` (`__build_error_impl` by default),
+/// so it should be defined exactly once in the final binary.
 ///
 /// # Examples
 ///
-/// ```
-/// fn foo<const A: usize, const B: usize>() {
-///   # use build_assert::build_assert_eq;
-///   build_assert_eq!(A, B, "A and B are not equal");
-/// }
+/// ```should_panic
+/// # use build_assert::{build_assert, define_build_error_handler};
+/// define_build_error_handler!(|msg| panic!("build error: {msg}"));
 ///
-/// foo::<1, 1>(); // Fine.
-/// ```
+/// fn check(n: usize) {
+///   build_assert!(n > 5, "n must be greater than 5, got {}", n);
+/// }
 ///
-#[cfg_attr(build = "debug", doc = "```should_panic")]
-#[cfg_attr(build = "release", doc = "```compile_fail")]
-/// # fn foo<const A: usize, const B: usize>() {
-/// #   use build_assert::build_assert_eq;
-/// #   build_assert_eq!(A, B);
+/// # fn main() {
+/// check(std::env::args().count());
 /// # }
-/// foo::<1, 2>(); // Fails to compile in release mode, panics in debug mode.
 /// ```
+#[cfg(feature = "error_handler")]
 #[macro_export]
-macro_rules! build_assert_eq {
-  ($left:expr, $right:expr $(,)?) => {
-    match (&$left, &$right) {
-      (left_val, right_val) => {
-        if !(*left_val == *right_val) {
-          $crate::build_error!(
-            "assertion `left == right` failed\n  left: {:?}\n right: {:?}",
-            &*left_val,
-            &*right_val,
-          );
+macro_rules! define_build_error_handler {
+  (|$msg:pat_param| $body:expr) => {
+    macro_rules! __build_error_handler {
+      ($sym:ident) => {
+        #[no_mangle]
+        extern "Rust" fn $sym($msg: core::fmt::Arguments<'_>) -> ! {
+          $body
         }
-      }
+      };
     }
+    $crate::__env_id!("BUILD_ERROR_SYM" ?: __build_error_impl => __build_error_handler);
   };
-  ($left:expr, $right:expr, $($arg:tt)+) => {
-    match (&$left, &$right) {
-      (left_val, right_val) => {
-        if !(*left_val == *right_val) {
-          $crate::build_error!(
-            "assertion `left == right` failed: {}\n  left: {:?}\n right: {:?}",
-            core::format_args!($($arg)+),
-            &*left_val,
-            &*right_val,
-          );
-        }
-      }
-    }
+}
+
+/// Raises a build-time error.
+///
+/// Since the `disabled` feature is enabled, this macro does nothing. The
+/// arguments are still type-checked, but no code will be emitted.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_error;
+/// build_error!("this is a hard error");
+/// ```
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! build_error {
+  () => {{}};
+  (@code $code:literal; $($args:tt)*) => {
+    $crate::build_error!($($args)*)
   };
+  ($($args:tt)+) => {{
+    if false {
+      let _ = core::format_args!($($args)+);
+    }
+  }};
 }
 
-/// Asserts that two expressions are not equal to each other at build-time
-/// (using [PartialEq]).
+/// Kind of errors raised by [`build_error`] in the current build, used by
+/// the profile matrix tests.
+#[doc(hidden)]
+pub const __BUILD_ERROR_KIND: &str = if cfg!(feature = "disabled") {
+  "disabled"
+} else if cfg!(build = "debug") {
+  "panic"
+} else if cfg!(feature = "error_handler") {
+  "handler"
+} else if cfg!(feature = "no_asm") {
+  "link"
+} else {
+  "asm"
+};
+
+/// Asserts that a boolean expression is `true` at build-time.
 ///
 /// In release mode, if the expression is evaluated to `false`, or the compiler
 /// or optimizer cannot ensure that the expression is evaluated to `true`, this
 /// macro will stop the compilation process.
 ///
 /// In debug mode, if the expression is evaluated to `false`, this macro will
-/// panic.
+/// panic. The panic location is the location of the macro invocation, or the
+/// location of the caller if the macro is used in a `#[track_caller]` function.
+///
+/// The macro itself never moves values used in the condition or the message,
+/// so they can still be used after the assertion, unless the condition moves
+/// them by itself.
 ///
 /// # Examples
 ///
 /// ```
-/// fn foo<const A: usize, const B: usize>() {
-///   # use build_assert::build_assert_ne;
-///   build_assert_ne!(A, B, "A and B are equal");
+/// fn foo<const N: usize>() {
+///   # use build_assert::build_assert;
+///   build_assert!(N.is_power_of_two(), "N is not a power of two");
 /// }
 ///
-/// foo::<1, 2>(); // Fine.
+/// foo::<16>(); // Fine.
 /// ```
 ///
 #[cfg_attr(build = "debug", doc = "```should_panic")]
 #[cfg_attr(build = "release", doc = "```compile_fail")]
-/// # fn foo<const A: usize, const B: usize>() {
-/// #   use build_assert::build_assert_ne;
-/// #   build_assert_ne!(A, B);
+/// # fn foo<const N: usize>() {
+/// #   use build_assert::build_assert;
+/// #   build_assert!(N.is_power_of_two());
 /// # }
-/// foo::<1, 1>(); // Fails to compile in release mode, panics in debug mode.
+/// foo::<15>(); // Fails to compile in release mode, panics in debug mode.
 /// ```
-#[macro_export]
-macro_rules! build_assert_ne {
-  ($left:expr, $right:expr $(,)?) => {
-    match (&$left, &$right) {
-      (left_val, right_val) => {
-        if *left_val == *right_val {
-          $crate::build_error!(
-            "assertion `left != right` failed\n  left: {:?}\n right: {:?}",
-            &*left_val,
-            &*right_val,
-          );
-        }
-      }
-    }
-  };
-  ($left:expr, $right:expr, $($arg:tt)+) => {
-    match (&$left, &$right) {
-      (left_val, right_val) => {
-        if *left_val == *right_val {
-          $crate::build_error!(
-            "assertion `left != right` failed: {}\n  left: {:?}\n right: {:?}",
-            core::format_args!($($arg)+),
-            &*left_val,
+///
+/// In release mode, the custom message is embedded in the build error:
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const N: usize>() {
+/// #   use build_assert::build_assert;
+///   build_assert!(N.is_power_of_two(), "N is not a power of two, got {}", N);
+/// # }
+/// # foo::<15>();
+/// // error: ... build error at src/lib.rs:4:3: N is not a power of two, got {}
+/// ```
+///
+/// An error code can be attached to the assertion, which is embedded in the
+/// panic message in debug mode, the build error in release mode, and the
+/// undefined symbol with the `no_asm` feature. The code must be a string
+/// literal containing only ASCII alphanumerics and underscores, and the custom
+/// message (if any) must be a string literal too:
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # use build_assert::build_assert;
+/// build_assert!(1 > 2; code = "BA0007", "one is not greater than {}", 2);
+/// // [BA0007] one is not greater than 2
+/// ```
+///
+/// Critical assertions can be marked as `hard`, so they fail to compile in
+/// debug mode too, rather than panicking at runtime. In debug mode, the
+/// condition is evaluated in an inline const block, so it **must be
+/// const-evaluable**, otherwise the build will always fail. The custom message
+/// (if any) must be a string literal without format arguments:
+///
+/// ```
+/// fn foo<const N: usize>() {
+///   # use build_assert::build_assert;
+///   build_assert!(N > 5; hard);
+///   build_assert!(N < 100; hard, "N is too large");
+/// }
+///
+/// foo::<10>(); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// fn foo<const N: usize>() {
+///   # use build_assert::build_assert;
+///   build_assert!(N > 5; hard);
+/// }
+///
+/// foo::<0>(); // Fails to compile in both modes.
+/// ```
+///
+/// If the condition is a `const` block, it is evaluated by the compiler in
+/// an inline const block, so it **fails to compile in both debug and release
+/// mode**, regardless of the optimizer. The custom message of a `const` block
+/// condition can not contain format arguments.
+///
+/// ```
+/// fn foo<const N: usize>() {
+///   # use build_assert::build_assert;
+///   build_assert!(const { N > 5 });
+/// }
+///
+/// foo::<10>(); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// # use build_assert::build_assert;
+/// build_assert!(const { 1 == 2 }); // Fails to compile in both modes.
+/// ```
+#[macro_export]
+macro_rules! build_assert {
+  (const $cond:block $(,)?) => {{
+    const {
+      core::assert!(
+        $cond,
+        "{}",
+        core::concat!("assertion failed: const ", core::stringify!($cond))
+      )
+    }
+  }};
+  (const $cond:block, $($arg:tt)+) => {{
+    const { core::assert!($cond, $($arg)+) }
+  }};
+  ($cond:expr; hard $(,)?) => {{
+    $crate::__build_assert_meta!($cond);
+    $crate::__build_assert_hard!(
+      $cond,
+      core::concat!("assertion failed: ", core::stringify!($cond))
+    );
+  }};
+  ($cond:expr; hard, $msg:literal $(,)?) => {{
+    $crate::__build_assert_meta!($cond);
+    $crate::__build_assert_hard!($cond, $msg);
+  }};
+  ($cond:expr; code = $code:literal $(,)?) => {{
+    const _: &str = $code;
+    $crate::__build_assert_meta!($cond);
+    $crate::__build_assert!(
+      $cond,
+      @code $code;
+      core::concat!("[", $code, "] assertion failed: ", core::stringify!($cond))
+    );
+  }};
+  ($cond:expr; code = $code:literal, $msg:literal $(, $($arg:tt)*)?) => {{
+    const _: &str = $code;
+    $crate::__build_assert_meta!($cond);
+    $crate::__build_assert!(
+      $cond,
+      @code $code;
+      core::concat!("[", $code, "] ", $msg) $(, $($arg)*)?
+    );
+  }};
+  ($cond:expr $(,)?) => {{
+    $crate::__build_assert_meta!($cond);
+    $crate::__build_assert!(
+      $cond,
+      core::concat!("assertion failed: ", core::stringify!($cond))
+    );
+  }};
+  ($cond:expr, $($arg:tt)+) => {{
+    $crate::__build_assert_meta!($cond);
+    $crate::__build_assert!($cond, $($arg)+);
+  }};
+}
+
+#[cfg(not(all(feature = "emit_metadata", not(feature = "disabled"))))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert_meta {
+  ($cond:expr) => {
+    $crate::__build_assert_site!($cond);
+  };
+}
+
+#[cfg(all(feature = "emit_metadata", not(feature = "disabled")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert_meta {
+  ($cond:expr) => {
+    const _: () = {
+      const RECORD: &str = core::concat!(
+        core::file!(),
+        ":",
+        core::line!(),
+        ":",
+        core::column!(),
+        ": ",
+        core::stringify!($cond),
+        "\0"
+      );
+      #[used]
+      #[cfg_attr(target_vendor = "apple", link_section = "__DATA,__build_assert")]
+      #[cfg_attr(not(target_vendor = "apple"), link_section = "build_assert")]
+      static RECORD_BYTES: [u8; RECORD.len()] = $crate::__metadata_record(RECORD);
+    };
+    $crate::__build_assert_site!($cond);
+  };
+}
+
+#[cfg(not(feature = "collect_sites"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert_site {
+  ($cond:expr) => {};
+}
+
+#[cfg(feature = "collect_sites")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert_site {
+  ($cond:expr) => {
+    $crate::__collect_site!($cond);
+  };
+}
+
+#[cfg(feature = "collect_sites")]
+#[doc(hidden)]
+pub use build_assert_macros::collect_site as __collect_site;
+
+/// Converts the given metadata record to a byte array.
+#[cfg(all(feature = "emit_metadata", not(feature = "disabled")))]
+#[doc(hidden)]
+pub const fn __metadata_record<const N: usize>(record: &str) -> [u8; N] {
+  let bytes = record.as_bytes();
+  let mut array = [0; N];
+  let mut i = 0;
+  while i < N {
+    array[i] = bytes[i];
+    i += 1;
+  }
+  array
+}
+
+#[cfg(feature = "disabled")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert {
+  ($cond:expr, $($arg:tt)+) => {
+    if false && !$cond {
+      $crate::build_error!($($arg)+);
+    }
+  };
+}
+
+#[cfg(not(any(
+  all(build = "debug", feature = "strict_debug"),
+  all(build = "release", feature = "warn_non_const"),
+  all(build = "release", feature = "const_panic"),
+  feature = "disabled"
+)))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert {
+  ($cond:expr, $($arg:tt)+) => {
+    if !$cond {
+      $crate::build_error!($($arg)+);
+    }
+  };
+}
+
+#[cfg(all(
+  build = "release",
+  feature = "warn_non_const",
+  not(feature = "const_panic"),
+  not(feature = "disabled")
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert {
+  ($cond:expr, $($arg:tt)+) => {{
+    $crate::__warn_non_const!($cond);
+    if !$cond {
+      $crate::build_error!($($arg)+);
+    }
+  }};
+}
+
+#[cfg(all(
+  build = "release",
+  feature = "warn_non_const",
+  not(feature = "const_panic"),
+  not(feature = "disabled")
+))]
+#[doc(hidden)]
+pub use build_assert_macros::warn_non_const as __warn_non_const;
+
+#[cfg(all(
+  any(
+    all(build = "debug", feature = "strict_debug"),
+    all(build = "release", feature = "const_panic")
+  ),
+  not(feature = "disabled")
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert {
+  ($cond:expr, @code $code:literal; $($arg:tt)+) => {
+    $crate::__strict_assert!($crate, $cond, $($arg)+)
+  };
+  ($cond:expr, $($arg:tt)+) => {
+    $crate::__strict_assert!($crate, $cond, $($arg)+)
+  };
+}
+
+#[cfg(all(
+  any(
+    all(build = "debug", feature = "strict_debug"),
+    all(build = "release", feature = "const_panic")
+  ),
+  not(feature = "disabled")
+))]
+#[doc(hidden)]
+pub use build_assert_macros::strict_assert as __strict_assert;
+
+#[cfg(all(build = "debug", not(feature = "disabled")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert_hard {
+  ($cond:expr, $msg:expr) => {
+    const { core::assert!($cond, "{}", $msg) }
+  };
+}
+
+#[cfg(not(all(build = "debug", not(feature = "disabled"))))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert_hard {
+  ($cond:expr, $msg:expr) => {
+    $crate::__build_assert!($cond, $msg)
+  };
+}
+
+/// Asserts that a boolean expression is `true` at build-time, and evaluates
+/// to `()` as an expression.
+///
+/// This macro is the same as [`build_assert`], except that it is guaranteed
+/// to be an expression, so it can be used where a statement does not fit,
+/// e.g. as a block tail, in a closure body, or in a match guard.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_assert_expr;
+/// fn foo<const N: usize>() -> usize {
+///   let () = build_assert_expr!(N > 5);
+///   match N {
+///     n if { build_assert_expr!(n % 2 == 0); true } => n / 2,
+///     _ => unreachable!(),
+///   }
+/// }
+///
+/// assert_eq!(foo::<10>(), 5); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # use build_assert::build_assert_expr;
+/// fn foo<const N: usize>() {
+///   let check = || build_assert_expr!(N > 5);
+///   check()
+/// }
+///
+/// foo::<0>(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[macro_export]
+macro_rules! build_assert_expr {
+  ($($args:tt)+) => {{
+    $crate::build_assert!($($args)+)
+  }};
+}
+
+/// Asserts that a boolean expression is `true` at build-time, only if the
+/// given configuration predicate matches the target.
+///
+/// The predicate is written as `cfg(...)`, and accepts the same syntax as
+/// the `#[cfg]` attribute. If it does not match, this macro expands to
+/// nothing, so the condition is not even type-checked. Otherwise, this macro
+/// behaves like [`build_assert`].
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_assert_on;
+/// struct Header {
+///   ptr: *const u8,
+///   len: usize,
+/// }
+///
+/// fn check_layout() {
+///   use core::mem::size_of;
+///   build_assert_on!(cfg(target_pointer_width = "64"), size_of::<Header>() == 16);
+///   build_assert_on!(cfg(target_pointer_width = "32"), size_of::<Header>() == 8);
+/// }
+///
+/// check_layout(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # use build_assert::build_assert_on;
+/// fn check_layout() {
+///   build_assert_on!(
+///     cfg(any(target_pointer_width = "32", target_pointer_width = "64")),
+///     core::mem::size_of::<usize>() == 2,
+///     "expected a 16-bit target",
+///   );
+/// }
+///
+/// check_layout(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[macro_export]
+macro_rules! build_assert_on {
+  (cfg($($cfg:tt)+), $($args:tt)+) => {{
+    #[cfg($($cfg)+)]
+    $crate::build_assert!($($args)+);
+  }};
+}
+
+/// Asserts that a boolean expression is `true` at build-time, evaluates the
+/// expression during macro expansion if possible.
+///
+/// If the expression only consists of integer literals, boolean literals and
+/// operators, it will be evaluated during macro expansion, and a compile error
+/// containing the evaluated operands will be raised if it is `false`, in both
+/// debug and release mode. Otherwise, this macro behaves like [`build_assert`].
+///
+/// Integer literals with a suffix (e.g. `1u8`), and operations overflowing
+/// `i32` (the type of unsuffixed integer literals), are not evaluated during
+/// macro expansion.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_assert_eval;
+/// build_assert_eval!(2 + 2 == 4); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// # use build_assert::build_assert_eval;
+/// build_assert_eval!(2 + 2 == 5);
+/// // error: assertion failed: 2 + 2 == 5
+/// //          left: 4
+/// //         right: 5
+/// ```
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! build_assert_eval {
+  ($cond:expr $(,)?) => {
+    $crate::__eval_assert!($crate, $cond)
+  };
+  ($cond:expr, $($arg:tt)+) => {
+    $crate::__eval_assert!($crate, $cond, $($arg)+)
+  };
+}
+
+/// Asserts that a boolean expression is `true` at build-time, evaluates the
+/// expression during macro expansion if possible.
+///
+/// Since the `disabled` feature is enabled, this macro behaves like
+/// [`build_assert`], which does nothing.
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! build_assert_eval {
+  ($($args:tt)+) => {
+    $crate::build_assert!($($args)+)
+  };
+}
+
+#[doc(hidden)]
+pub use build_assert_macros::eval_assert as __eval_assert;
+
+/// Asserts that a boolean expression is `true` at build-time, reports the
+/// failing conjunct if the expression is a conjunction (`a && b && ...`).
+///
+/// Each conjunct is asserted separately. Like [`build_assert_eval`],
+/// conjuncts that only consist of literals and operators are evaluated during
+/// macro expansion, and a compile error containing the evaluated operands
+/// will be raised if one of them is `false`. Other conjuncts are asserted by
+/// [`build_assert`], with a message pointing out which conjunct failed.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize, const C: usize>() {
+///   # use build_assert::build_assert_detailed;
+///   build_assert_detailed!(A > 0 && B < 10 && A + B == C);
+/// }
+///
+/// foo::<1, 2, 3>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const A: usize, const B: usize, const C: usize>() {
+/// #   use build_assert::build_assert_detailed;
+/// #   build_assert_detailed!(A > 0 && B < 10 && A + B == C);
+/// # }
+/// foo::<1, 12, 13>(); // Fails to compile in release mode, panics in debug mode.
+/// // assertion failed: B < 10 (conjunct 2 of 3)
+/// ```
+///
+/// ```compile_fail
+/// # use build_assert::build_assert_detailed;
+/// build_assert_detailed!(1 > 0 && 12 < 10 && 1 + 12 == 13, "bad layout");
+/// // error: bad layout
+/// //        assertion failed: 12 < 10 (conjunct 2 of 3)
+/// //          left: 12
+/// //         right: 10
+/// ```
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! build_assert_detailed {
+  ($cond:expr $(,)?) => {
+    $crate::__detailed_assert!($crate, $cond)
+  };
+  ($cond:expr, $msg:literal $(,)?) => {
+    $crate::__detailed_assert!($crate, $cond, $msg)
+  };
+}
+
+/// Asserts that a boolean expression is `true` at build-time, reports the
+/// failing conjunct if the expression is a conjunction (`a && b && ...`).
+///
+/// Since the `disabled` feature is enabled, this macro behaves like
+/// [`build_assert`], which does nothing.
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! build_assert_detailed {
+  ($($args:tt)+) => {
+    $crate::build_assert!($($args)+)
+  };
+}
+
+#[doc(hidden)]
+pub use build_assert_macros::detailed_assert as __detailed_assert;
+
+#[cfg(not(feature = "no_fmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_fmt {
+  ([$($static:tt)+] $($args:tt)+) => {
+    $crate::build_error!($($args)+)
+  };
+}
+
+#[cfg(feature = "no_fmt")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_error_fmt {
+  ([$msg:expr] $($args:tt)+) => {
+    $crate::build_error!($msg)
+  };
+  ([$msg:expr, $($arg:tt)+] $($args:tt)+) => {{
+    if false {
+      let _ = core::format_args!($($arg)+);
+    }
+    $crate::build_error!($msg)
+  }};
+}
+
+/// Asserts that a boolean const generic parameter is `true` at build-time.
+///
+/// This macro is the same as [`build_assert`], except that the message reads
+/// better for boolean const generic parameters.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const B: bool>() {
+///   # use build_assert::build_assert_true;
+///   build_assert_true!(B);
+/// }
+///
+/// foo::<true>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const B: bool>() {
+/// #   use build_assert::build_assert_true;
+/// #   build_assert_true!(B);
+/// # }
+/// foo::<false>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected const generic `B` to be true
+/// ```
+#[macro_export]
+macro_rules! build_assert_true {
+  ($b:expr $(,)?) => {
+    $crate::build_assert!(
+      $b,
+      core::concat!(
+        "expected const generic `",
+        core::stringify!($b),
+        "` to be true"
+      )
+    )
+  };
+}
+
+/// Asserts that a boolean const generic parameter is `false` at build-time.
+///
+/// This macro is the same as [`build_assert`] with a negated condition,
+/// except that the message reads better for boolean const generic parameters.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const B: bool>() {
+///   # use build_assert::build_assert_false;
+///   build_assert_false!(B);
+/// }
+///
+/// foo::<false>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const B: bool>() {
+/// #   use build_assert::build_assert_false;
+/// #   build_assert_false!(B);
+/// # }
+/// foo::<true>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected const generic `B` to be false
+/// ```
+#[macro_export]
+macro_rules! build_assert_false {
+  ($b:expr $(,)?) => {
+    $crate::build_assert!(
+      !$b,
+      core::concat!(
+        "expected const generic `",
+        core::stringify!($b),
+        "` to be false"
+      )
+    )
+  };
+}
+
+/// Asserts that a boolean expression is `false` at build-time, for code paths
+/// that are unreachable for valid configurations.
+///
+/// This macro is the logical complement of [`build_assert`]: it raises a
+/// build-time error if `cond` is `true` (meaning an impossible case
+/// occurred) in release mode, and panics in debug mode.
+///
+/// # Examples
+///
+/// ```
+/// fn name<const N: usize>() -> &'static str {
+///   # use build_assert::build_unreachable_if;
+///   match N {
+///     0 => "zero",
+///     1 => "one",
+///     _ => {
+///       build_unreachable_if!(N > 1);
+///       "many"
+///     }
+///   }
+/// }
+///
+/// assert_eq!(name::<1>(), "one"); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn name<const N: usize>() -> &'static str {
+/// #   use build_assert::build_unreachable_if;
+/// #   match N {
+/// #     0 => "zero",
+/// #     1 => "one",
+/// #     _ => {
+/// #       build_unreachable_if!(N > 1);
+/// #       "many"
+/// #     }
+/// #   }
+/// # }
+/// name::<2>(); // Fails to compile in release mode, panics in debug mode.
+/// // unreachable for this configuration
+/// ```
+#[macro_export]
+macro_rules! build_unreachable_if {
+  ($cond:expr $(,)?) => {
+    $crate::build_assert!(!($cond), "unreachable for this configuration")
+  };
+  ($cond:expr, $($arg:tt)+) => {
+    $crate::build_assert!(!($cond), $($arg)+)
+  };
+}
+
+/// Asserts that the size of a type is at least the given number of bytes at
+/// build-time.
+///
+/// This macro is the same as [`build_assert`] with condition
+/// `core::mem::size_of::<T>() >= bytes`, except that the message names the
+/// type and shows both values.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<T>() {
+///   # use build_assert::build_assert_size_ge;
+///   build_assert_size_ge!(T, 4);
+/// }
+///
+/// foo::<u32>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<T>() {
+/// #   use build_assert::build_assert_size_ge;
+/// #   build_assert_size_ge!(T, 4);
+/// # }
+/// foo::<u16>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected size of `T` to be at least 4 bytes, got 2
+/// ```
+#[macro_export]
+macro_rules! build_assert_size_ge {
+  ($ty:ty, $bytes:expr $(,)?) => {
+    $crate::build_assert!(
+      core::mem::size_of::<$ty>() >= $bytes,
+      core::concat!(
+        "expected size of `",
+        core::stringify!($ty),
+        "` to be at least {} bytes, got {}"
+      ),
+      $bytes,
+      core::mem::size_of::<$ty>(),
+    )
+  };
+}
+
+/// Asserts that the alignment of a type is at least the given value at
+/// build-time.
+///
+/// This macro is the same as [`build_assert`] with condition
+/// `core::mem::align_of::<T>() >= align`, except that the message names the
+/// type and shows both values.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<T>() {
+///   # use build_assert::build_assert_align_ge;
+///   build_assert_align_ge!(T, 8);
+/// }
+///
+/// foo::<u64>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<T>() {
+/// #   use build_assert::build_assert_align_ge;
+/// #   build_assert_align_ge!(T, 8);
+/// # }
+/// foo::<u8>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected alignment of `T` to be at least 8, got 1
+/// ```
+#[macro_export]
+macro_rules! build_assert_align_ge {
+  ($ty:ty, $align:expr $(,)?) => {
+    $crate::build_assert!(
+      core::mem::align_of::<$ty>() >= $align,
+      core::concat!(
+        "expected alignment of `",
+        core::stringify!($ty),
+        "` to be at least {}, got {}"
+      ),
+      $align,
+      core::mem::align_of::<$ty>(),
+    )
+  };
+}
+
+/// Asserts that two array lengths are equal at build-time.
+///
+/// This macro is the same as [`build_assert`] with condition `n == m`, except
+/// that the message reads better for const generic array lengths.
+///
+/// # Examples
+///
+/// ```
+/// fn zip<const N: usize, const M: usize>(a: [u8; N], b: [u8; M]) -> [(u8, u8); N] {
+///   # use build_assert::build_assert_len_eq;
+///   build_assert_len_eq!(N, M);
+///   core::array::from_fn(|i| (a[i], b[i]))
+/// }
+///
+/// zip([1, 2], [3, 4]); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn zip<const N: usize, const M: usize>(a: [u8; N], b: [u8; M]) {
+/// #   use build_assert::build_assert_len_eq;
+/// #   build_assert_len_eq!(N, M);
+/// # }
+/// zip([1, 2], [3]); // Fails to compile in release mode, panics in debug mode.
+/// // array length mismatch: 2 vs 1
+/// ```
+#[macro_export]
+macro_rules! build_assert_len_eq {
+  ($n:expr, $m:expr $(,)?) => {
+    $crate::build_assert!($n == $m, "array length mismatch: {} vs {}", $n, $m)
+  };
+}
+
+/// Asserts that an integer is divisible by another at build-time.
+///
+/// This macro is useful for checking that const generic lengths are multiples
+/// of a lane or tile width. A zero divisor is reported with a distinct
+/// message.
+///
+/// # Examples
+///
+/// ```
+/// fn sum_lanes<const N: usize, const LANES: usize>(a: [u32; N]) -> [u32; LANES] {
+///   # use build_assert::build_assert_divisible;
+///   build_assert_divisible!(N, LANES);
+///   let mut sum = [0; LANES];
+///   for chunk in a.chunks_exact(LANES) {
+///     for (s, v) in sum.iter_mut().zip(chunk) {
+///       *s += v;
+///     }
+///   }
+///   sum
+/// }
+///
+/// sum_lanes::<8, 4>([1; 8]); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn sum_lanes<const N: usize, const LANES: usize>(a: [u32; N]) {
+/// #   use build_assert::build_assert_divisible;
+/// #   build_assert_divisible!(N, LANES);
+/// # }
+/// sum_lanes::<6, 4>([1; 6]); // Fails to compile in release mode, panics in debug mode.
+/// // expected 6 to be divisible by 4
+/// ```
+///
+/// A zero divisor is reported as well:
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn sum_lanes<const N: usize, const LANES: usize>(a: [u32; N]) {
+/// #   use build_assert::build_assert_divisible;
+/// #   build_assert_divisible!(N, LANES);
+/// # }
+/// sum_lanes::<8, 0>([1; 8]); // divisor is zero
+/// ```
+#[macro_export]
+macro_rules! build_assert_divisible {
+  ($n:expr, $by:expr $(,)?) => {{
+    $crate::build_assert!($by != 0, "divisor is zero");
+    $crate::build_assert!(
+      $by == 0 || $n % $by == 0,
+      "expected {} to be divisible by {}",
+      $n,
+      $by
+    );
+  }};
+}
+
+/// Asserts that an integer is congruent to a residue modulo another at
+/// build-time, i.e. `n % modulus == residue`.
+///
+/// This macro is useful for checking constraints in the form of
+/// `N == k * CHUNK + r`, e.g. for sizing ring buffers. A zero modulus is
+/// reported with a distinct message, and the actual residue is shown on
+/// failure.
+///
+/// # Examples
+///
+/// ```
+/// fn ring<const N: usize, const CHUNK: usize>() {
+///   # use build_assert::build_assert_congruent;
+///   build_assert_congruent!(N, CHUNK, 1);
+/// }
+///
+/// ring::<33, 8>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn ring<const N: usize, const CHUNK: usize>() {
+/// #   use build_assert::build_assert_congruent;
+/// #   build_assert_congruent!(N, CHUNK, 1);
+/// # }
+/// ring::<34, 8>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected 34 % 8 to be 1, got 2
+/// ```
+///
+/// A zero modulus is reported as well:
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn ring<const N: usize, const CHUNK: usize>() {
+/// #   use build_assert::build_assert_congruent;
+/// #   build_assert_congruent!(N, CHUNK, 1);
+/// # }
+/// ring::<33, 0>(); // modulus is zero
+/// ```
+#[macro_export]
+macro_rules! build_assert_congruent {
+  ($n:expr, $modulus:expr, $residue:expr $(,)?) => {{
+    $crate::build_assert!($modulus != 0, "modulus is zero");
+    $crate::build_assert!(
+      $modulus == 0 || $n % $modulus == $residue,
+      "expected {} % {} to be {}, got {}",
+      $n,
+      $modulus,
+      $residue,
+      $n % $modulus
+    );
+  }};
+}
+
+/// Asserts that an integer is even at build-time.
+///
+/// Signed integers are supported as well, and zero is considered even.
+///
+/// # Examples
+///
+/// ```
+/// fn pairs<const N: usize>() {
+///   # use build_assert::build_assert_even;
+///   build_assert_even!(N);
+/// }
+///
+/// pairs::<4>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn pairs<const N: usize>() {
+/// #   use build_assert::build_assert_even;
+/// #   build_assert_even!(N);
+/// # }
+/// pairs::<3>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected an even value, got 3
+/// ```
+#[macro_export]
+macro_rules! build_assert_even {
+  ($n:expr $(,)?) => {
+    $crate::build_assert!($n % 2 == 0, "expected an even value, got {}", $n)
+  };
+}
+
+/// Asserts that an integer is odd at build-time.
+///
+/// Signed integers are supported as well.
+///
+/// # Examples
+///
+/// ```
+/// fn pairs<const N: usize>() {
+///   # use build_assert::build_assert_odd;
+///   build_assert_odd!(N);
+/// }
+///
+/// pairs::<3>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn pairs<const N: usize>() {
+/// #   use build_assert::build_assert_odd;
+/// #   build_assert_odd!(N);
+/// # }
+/// pairs::<4>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected an odd value, got 4
+/// ```
+#[macro_export]
+macro_rules! build_assert_odd {
+  ($n:expr $(,)?) => {
+    $crate::build_assert!($n % 2 != 0, "expected an odd value, got {}", $n)
+  };
+}
+
+/// Asserts that two integers are coprime (their greatest common divisor is 1)
+/// at build-time.
+///
+/// The operands must be of unsigned integer types, signed operands are
+/// rejected at compile time. The computed GCD is reported on failure.
+///
+/// # Examples
+///
+/// ```
+/// fn stride<const N: usize, const STEP: usize>() {
+///   # use build_assert::build_assert_coprime;
+///   build_assert_coprime!(N, STEP);
+/// }
+///
+/// stride::<16, 3>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn stride<const N: usize, const STEP: usize>() {
+/// #   use build_assert::build_assert_coprime;
+/// #   build_assert_coprime!(N, STEP);
+/// # }
+/// stride::<16, 6>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected 16 and 6 to be coprime, got gcd 2
+/// ```
+///
+/// Signed operands are rejected:
+///
+/// ```compile_fail
+/// # use build_assert::build_assert_coprime;
+/// build_assert_coprime!(16i32, -3i32);
+/// ```
+#[macro_export]
+macro_rules! build_assert_coprime {
+  ($a:expr, $b:expr $(,)?) => {{
+    let gcd = $crate::const_cmp::gcd(
+      $crate::UnsignedInt::to_u128($a),
+      $crate::UnsignedInt::to_u128($b),
+    );
+    $crate::build_assert!(
+      gcd == 1,
+      "expected {} and {} to be coprime, got gcd {}",
+      $a,
+      $b,
+      gcd
+    );
+  }};
+}
+
+/// Asserts that the greatest common divisor of two integers equals the
+/// expected value at build-time.
+///
+/// Like [`build_assert_coprime`], the operands must be of unsigned integer
+/// types. The GCD of `0` and `0` is `0`.
+///
+/// # Examples
+///
+/// ```
+/// fn tile<const W: usize, const H: usize>() {
+///   # use build_assert::build_assert_gcd_eq;
+///   build_assert_gcd_eq!(W, H, 4);
+/// }
+///
+/// tile::<12, 8>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn tile<const W: usize, const H: usize>() {
+/// #   use build_assert::build_assert_gcd_eq;
+/// #   build_assert_gcd_eq!(W, H, 4);
+/// # }
+/// tile::<12, 9>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected gcd(12, 9) to be 4, got 3
+/// ```
+#[macro_export]
+macro_rules! build_assert_gcd_eq {
+  ($a:expr, $b:expr, $expected:expr $(,)?) => {{
+    let gcd = $crate::const_cmp::gcd(
+      $crate::UnsignedInt::to_u128($a),
+      $crate::UnsignedInt::to_u128($b),
+    );
+    $crate::build_assert!(
+      gcd == $expected as u128,
+      "expected gcd({}, {}) to be {}, got {}",
+      $a,
+      $b,
+      $expected,
+      gcd
+    );
+  }};
+}
+
+/// Unsigned integer types accepted by [`build_assert_coprime`] and
+/// [`build_assert_gcd_eq`].
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` is not an unsigned integer type",
+  label = "expected an unsigned integer",
+  note = "the GCD can only be computed for unsigned integers"
+)]
+pub trait UnsignedInt {
+  fn to_u128(self) -> u128;
+}
+
+macro_rules! impl_unsigned_int {
+  ($($ty:ty),+ $(,)?) => {
+    $(
+      impl UnsignedInt for $ty {
+        #[inline(always)]
+        fn to_u128(self) -> u128 {
+          self as u128
+        }
+      }
+    )+
+  };
+}
+
+impl_unsigned_int!(u8, u16, u32, u64, u128, usize);
+
+/// Asserts that an integer is non-zero at build-time, and evaluates to the
+/// integer.
+///
+/// This macro is useful for constructing non-zero integers from const
+/// generics. Note that the expression is evaluated twice, so it should not
+/// have side effects.
+///
+/// # Examples
+///
+/// ```
+/// use core::num::NonZeroUsize;
+///
+/// fn foo<const N: usize>() -> NonZeroUsize {
+///   # use build_assert::build_assert_nonzero;
+///   NonZeroUsize::new(build_assert_nonzero!(N)).unwrap()
+/// }
+///
+/// foo::<4>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const N: usize>() -> usize {
+/// #   use build_assert::build_assert_nonzero;
+/// #   build_assert_nonzero!(N)
+/// # }
+/// foo::<0>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected a non-zero value
+/// ```
+#[macro_export]
+macro_rules! build_assert_nonzero {
+  ($value:expr $(,)?) => {{
+    $crate::build_assert!($value != 0, "expected a non-zero value");
+    $value
+  }};
+}
+
+/// Asserts that all elements of an integer array (or slice) are non-zero at
+/// build-time.
+///
+/// The elements are checked in a `while` loop, so the array should be known
+/// at build-time (e.g. a constant or an associated constant of a const
+/// generic type). On failure, the index of the first zero element is
+/// reported. Empty arrays always pass.
+///
+/// # Examples
+///
+/// ```
+/// struct Shape<const W: usize, const H: usize>;
+///
+/// impl<const W: usize, const H: usize> Shape<W, H> {
+///   const STRIDES: [usize; 2] = [H, 1];
+///
+///   fn new() -> Self {
+///     # use build_assert::build_assert_all_nonzero;
+///     build_assert_all_nonzero!(Self::STRIDES);
+///     Self
+///   }
+/// }
+///
+/// Shape::<4, 8>::new(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # use build_assert::build_assert_all_nonzero;
+/// const DIMS: [u32; 3] = [4, 0, 8];
+///
+/// build_assert_all_nonzero!(DIMS); // Fails to compile in release mode, panics in debug mode.
+/// // element at index 1 is zero
+/// ```
+#[macro_export]
+macro_rules! build_assert_all_nonzero {
+  ($array:expr $(,)?) => {{
+    let array = &$array;
+    let mut index = 0;
+    while index < array.len() && array[index] != 0 {
+      index += 1;
+    }
+    $crate::build_assert!(index == array.len(), "element at index {} is zero", index);
+  }};
+}
+
+/// Asserts that the given values are non-decreasing at build-time, i.e.
+/// each value is less than or equal to the next one (using [PartialOrd]).
+///
+/// This macro is useful for checking the order of const generic parameters.
+/// The values are checked in a `while` loop, and the first inversion is
+/// reported with indices and values of both elements.
+///
+/// # Examples
+///
+/// ```
+/// fn levels<const LOW: usize, const MID: usize, const HIGH: usize>() {
+///   # use build_assert::build_assert_monotonic;
+///   build_assert_monotonic!([LOW, MID, HIGH]);
+/// }
+///
+/// levels::<1, 2, 3>(); // Fine.
+/// levels::<1, 1, 3>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn levels<const LOW: usize, const MID: usize, const HIGH: usize>() {
+/// #   use build_assert::build_assert_monotonic;
+/// #   build_assert_monotonic!([LOW, MID, HIGH]);
+/// # }
+/// levels::<1, 4, 3>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected non-decreasing values, but element at index 2 (3) is less than element at index 1 (4)
+/// ```
+#[macro_export]
+macro_rules! build_assert_monotonic {
+  ([$($value:expr),+ $(,)?] $(,)?) => {{
+    let values = [$($value),+];
+    let mut index = 1;
+    while index < values.len() && values[index - 1] <= values[index] {
+      index += 1;
+    }
+    $crate::build_assert!(
+      index >= values.len(),
+      "expected non-decreasing values, but element at index {} ({:?}) is less than element at index {} ({:?})",
+      index,
+      values[index],
+      index - 1,
+      values[index - 1]
+    );
+  }};
+}
+
+/// Asserts that an integer is within the given inclusive bounds at
+/// build-time.
+///
+/// This macro checks `min <= value` and `value <= max` separately, so the
+/// message tells which bound is violated.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const N: usize>() {
+///   # use build_assert::build_assert_bounds;
+///   build_assert_bounds!(N, 1, 64);
+/// }
+///
+/// foo::<1>(); // Fine.
+/// foo::<64>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const N: usize>() {
+/// #   use build_assert::build_assert_bounds;
+/// #   build_assert_bounds!(N, 1, 64);
+/// # }
+/// foo::<65>(); // Fails to compile in release mode, panics in debug mode.
+/// // value 65 is above the maximum 64
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const N: usize>() {
+/// #   use build_assert::build_assert_bounds;
+/// #   build_assert_bounds!(N, 1, 64);
+/// # }
+/// foo::<0>(); // value 0 is below the minimum 1
+/// ```
+#[macro_export]
+macro_rules! build_assert_bounds {
+  ($value:expr, $min:expr, $max:expr $(,)?) => {{
+    $crate::build_assert!(
+      $min <= $value,
+      "value {} is below the minimum {}",
+      $value,
+      $min
+    );
+    $crate::build_assert!(
+      $value <= $max,
+      "value {} is above the maximum {}",
+      $value,
+      $max
+    );
+  }};
+}
+
+/// Asserts that a character is in the given range at build-time.
+///
+/// The range is either inclusive (`'a'..='z'`) or exclusive (`'\0'..'\u{80}'`),
+/// and its bounds must be character literals. Characters are compared by
+/// their codepoints, so the condition is const-evaluable for const generic
+/// `char`s. On failure, the message shows the character, its codepoint and
+/// the allowed range.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const C: char>() {
+///   # use build_assert::build_assert_char_range;
+///   build_assert_char_range!(C, 'a'..='z');
+/// }
+///
+/// foo::<'a'>(); // Fine.
+/// foo::<'z'>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const C: char>() {
+/// #   use build_assert::build_assert_char_range;
+/// #   build_assert_char_range!(C, 'a'..='z');
+/// # }
+/// foo::<'A'>(); // Fails to compile in release mode, panics in debug mode.
+/// // character 'A' (U+0041) is not in the range 'a'..='z'
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # use build_assert::build_assert_char_range;
+/// fn ascii<const C: char>() {
+///   build_assert_char_range!(C, '\0'..'\u{80}');
+/// }
+///
+/// ascii::<'é'>(); // character 'é' (U+00E9) is not in the range '\0'..'\u{80}'
+/// ```
+#[macro_export]
+macro_rules! build_assert_char_range {
+  ($c:expr, $start:literal ..= $end:literal $(,)?) => {
+    $crate::build_assert!(
+      $start as u32 <= $c as u32 && $c as u32 <= $end as u32,
+      "character {:?} (U+{:04X}) is not in the range {:?}..={:?}",
+      $c,
+      $c as u32,
+      $start,
+      $end
+    )
+  };
+  ($c:expr, $start:literal .. $end:literal $(,)?) => {
+    $crate::build_assert!(
+      $start as u32 <= $c as u32 && ($c as u32) < $end as u32,
+      "character {:?} (U+{:04X}) is not in the range {:?}..{:?}",
+      $c,
+      $c as u32,
+      $start,
+      $end
+    )
+  };
+}
+
+/// Asserts that two half-open ranges `[a_start, a_end)` and
+/// `[b_start, b_end)` do not overlap at build-time.
+///
+/// This macro is useful for validating memory maps. Both ranges are checked
+/// to be well-formed (`start <= end`) first, with a distinct message. Adjacent
+/// ranges and empty ranges never overlap. On failure, the extent of the
+/// overlap is reported.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A_START: usize, const A_END: usize, const B_START: usize, const B_END: usize>() {
+///   # use build_assert::build_assert_no_overlap;
+///   build_assert_no_overlap!(A_START, A_END, B_START, B_END);
+/// }
+///
+/// foo::<0x0000, 0x1000, 0x1000, 0x2000>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const A_START: usize, const A_END: usize, const B_START: usize, const B_END: usize>() {
+/// #   use build_assert::build_assert_no_overlap;
+/// #   build_assert_no_overlap!(A_START, A_END, B_START, B_END);
+/// # }
+/// foo::<0, 16, 8, 32>(); // Fails to compile in release mode, panics in debug mode.
+/// // ranges [0, 16) and [8, 32) overlap in [8, 16)
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const A_START: usize, const A_END: usize, const B_START: usize, const B_END: usize>() {
+/// #   use build_assert::build_assert_no_overlap;
+/// #   build_assert_no_overlap!(A_START, A_END, B_START, B_END);
+/// # }
+/// foo::<16, 8, 32, 64>(); // first range [16, 8) is malformed
+/// ```
+#[macro_export]
+macro_rules! build_assert_no_overlap {
+  ($a_start:expr, $a_end:expr, $b_start:expr, $b_end:expr $(,)?) => {{
+    $crate::build_assert!(
+      $a_start <= $a_end,
+      "first range [{}, {}) is malformed",
+      $a_start,
+      $a_end
+    );
+    $crate::build_assert!(
+      $b_start <= $b_end,
+      "second range [{}, {}) is malformed",
+      $b_start,
+      $b_end
+    );
+    $crate::build_assert!(
+      $a_end <= $b_start || $b_end <= $a_start || $a_start == $a_end || $b_start == $b_end,
+      "ranges [{}, {}) and [{}, {}) overlap in [{}, {})",
+      $a_start,
+      $a_end,
+      $b_start,
+      $b_end,
+      if $a_start > $b_start {
+        $a_start
+      } else {
+        $b_start
+      },
+      if $a_end < $b_end { $a_end } else { $b_end },
+    );
+  }};
+}
+
+/// Asserts that `left op right` holds at build-time, where `op` is a
+/// comparison operator, e.g. `==` or `<`.
+///
+/// All comparison assertions expand to this macro with their row of
+/// `[op, format]`, where `format` is the format spec of the operands:
+///
+/// | Macro                       | `op` | `format` |
+/// | --------------------------- | ---- | -------- |
+/// | [`build_assert_eq`]         | `==` | `{:?}`   |
+/// | [`build_assert_ne`]         | `!=` | `{:?}`   |
+/// | [`build_assert_eq_display`] | `==` | `{}`     |
+/// | [`build_assert_lt`]         | `<`  | `{:?}`   |
+/// | [`build_assert_le`]         | `<=` | `{:?}`   |
+/// | [`build_assert_gt`]         | `>`  | `{:?}`   |
+/// | [`build_assert_ge`]         | `>=` | `{:?}`   |
+///
+/// So they share the same message format: ``assertion `left op right`
+/// failed[: message]``, followed by both operands, and optionally the
+/// difference of them with `show_diff`.
+///
+/// The public macros are written out instead of being generated from the
+/// table, because macro-expanded `#[macro_export]` macros can not be referred
+/// to by absolute paths (e.g. in [`prelude`]) in this crate.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert_cmp {
+  ([$op:tt, $fmt:literal] $left:expr, $right:expr $(,)?) => {
+    match (&$left, &$right) {
+      (left_val, right_val) => {
+        if !(*left_val $op *right_val) {
+          $crate::__build_error_fmt!(
+            [core::concat!("assertion `left ", core::stringify!($op), " right` failed")]
+            core::concat!(
+              "assertion `left ",
+              core::stringify!($op),
+              " right` failed\n  left: ",
+              $fmt,
+              "\n right: ",
+              $fmt
+            ),
+            &*left_val,
+            &*right_val,
+          );
+        }
+      }
+    }
+  };
+  ([$op:tt, $fmt:literal] $left:expr, $right:expr; show_diff $(,)?) => {
+    match (&$left, &$right) {
+      (left_val, right_val) => {
+        if !(*left_val $op *right_val) {
+          $crate::__build_error_fmt!(
+            [core::concat!("assertion `left ", core::stringify!($op), " right` failed")]
+            core::concat!(
+              "assertion `left ",
+              core::stringify!($op),
+              " right` failed\n  left: ",
+              $fmt,
+              "\n right: ",
+              $fmt,
+              "\n  diff: ",
+              $fmt
+            ),
+            &*left_val,
+            &*right_val,
+            $crate::WrappingDiff::wrapping_diff(left_val, right_val),
+          );
+        }
+      }
+    }
+  };
+  ([$op:tt, $fmt:literal] $left:expr, $right:expr; show_diff, $($arg:tt)+) => {
+    match (&$left, &$right) {
+      (left_val, right_val) => {
+        if !(*left_val $op *right_val) {
+          $crate::__build_error_fmt!(
+            [core::concat!("assertion `left ", core::stringify!($op), " right` failed"), $($arg)+]
+            core::concat!(
+              "assertion `left ",
+              core::stringify!($op),
+              " right` failed: {}\n  left: ",
+              $fmt,
+              "\n right: ",
+              $fmt,
+              "\n  diff: ",
+              $fmt
+            ),
+            core::format_args!($($arg)+),
+            &*left_val,
+            &*right_val,
+            $crate::WrappingDiff::wrapping_diff(left_val, right_val),
+          );
+        }
+      }
+    }
+  };
+  ([$op:tt, $fmt:literal] $left:expr, $right:expr, $($arg:tt)+) => {
+    match (&$left, &$right) {
+      (left_val, right_val) => {
+        if !(*left_val $op *right_val) {
+          $crate::__build_error_fmt!(
+            [core::concat!("assertion `left ", core::stringify!($op), " right` failed"), $($arg)+]
+            core::concat!(
+              "assertion `left ",
+              core::stringify!($op),
+              " right` failed: {}\n  left: ",
+              $fmt,
+              "\n right: ",
+              $fmt
+            ),
+            core::format_args!($($arg)+),
+            &*left_val,
             &*right_val,
           );
         }
       }
     }
-  };
-}
+  };
+}
+
+/// Asserts that two expressions are equal to each other at build-time (using
+/// [PartialEq]).
+///
+/// In release mode, if the expression is evaluated to `false`, or the compiler
+/// or optimizer cannot ensure that the expression is evaluated to `true`, this
+/// macro will stop the compilation process.
+///
+/// In debug mode, if the expression is evaluated to `false`, this macro will
+/// panic.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize>() {
+///   # use build_assert::build_assert_eq;
+///   build_assert_eq!(A, B, "A and B are not equal");
+/// }
+///
+/// foo::<1, 1>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const A: usize, const B: usize>() {
+/// #   use build_assert::build_assert_eq;
+/// #   build_assert_eq!(A, B);
+/// # }
+/// foo::<1, 2>(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+///
+/// For integer operands, you can add `; show_diff` to show the difference
+/// (`left.wrapping_sub(right)`) of the two values in the panic message:
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # use build_assert::build_assert_eq;
+/// build_assert_eq!(100u32, 42; show_diff);
+/// // assertion `left == right` failed
+/// //   left: 100
+/// //  right: 42
+/// //   diff: 58
+/// ```
+///
+/// `show_diff` can not be used with non-integer operands:
+///
+/// ```compile_fail
+/// # use build_assert::build_assert_eq;
+/// build_assert_eq!("a", "b"; show_diff);
+/// ```
+#[macro_export]
+macro_rules! build_assert_eq {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__build_assert_cmp!([==, "{:?}"] $left, $right)
+  };
+  ($left:expr, $right:expr, $($arg:tt)+) => {
+    $crate::__build_assert_cmp!([==, "{:?}"] $left, $right, $($arg)+)
+  };
+  ($left:expr, $right:expr; show_diff $(,)?) => {
+    $crate::__build_assert_cmp!([==, "{:?}"] $left, $right; show_diff)
+  };
+  ($left:expr, $right:expr; show_diff, $($arg:tt)+) => {
+    $crate::__build_assert_cmp!([==, "{:?}"] $left, $right; show_diff, $($arg)+)
+  };
+}
+
+/// Integer types that support `show_diff` in [`build_assert_eq`].
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+  message = "`show_diff` can not be used with `{Self}`",
+  label = "expected an integer type",
+  note = "`show_diff` only supports integer types"
+)]
+pub trait WrappingDiff {
+  fn wrapping_diff(&self, rhs: &Self) -> Self;
+}
+
+macro_rules! impl_wrapping_diff {
+  ($($ty:ty),+ $(,)?) => {
+    $(
+      impl WrappingDiff for $ty {
+        #[inline(always)]
+        fn wrapping_diff(&self, rhs: &Self) -> Self {
+          self.wrapping_sub(*rhs)
+        }
+      }
+    )+
+  };
+}
+
+impl_wrapping_diff!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Asserts that two expressions are not equal to each other at build-time
+/// (using [PartialEq]).
+///
+/// In release mode, if the expression is evaluated to `false`, or the compiler
+/// or optimizer cannot ensure that the expression is evaluated to `true`, this
+/// macro will stop the compilation process.
+///
+/// In debug mode, if the expression is evaluated to `false`, this macro will
+/// panic.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize>() {
+///   # use build_assert::build_assert_ne;
+///   build_assert_ne!(A, B, "A and B are equal");
+/// }
+///
+/// foo::<1, 2>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const A: usize, const B: usize>() {
+/// #   use build_assert::build_assert_ne;
+/// #   build_assert_ne!(A, B);
+/// # }
+/// foo::<1, 1>(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[macro_export]
+macro_rules! build_assert_ne {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__build_assert_cmp!([!=, "{:?}"] $left, $right)
+  };
+  ($left:expr, $right:expr, $($arg:tt)+) => {
+    $crate::__build_assert_cmp!([!=, "{:?}"] $left, $right, $($arg)+)
+  };
+}
+
+/// Asserts that two expressions are equal to each other at build-time (using
+/// [PartialEq]), displaying the operands with [`Display`](core::fmt::Display).
+///
+/// This macro is the same as [`build_assert_eq`], except that the operands are
+/// formatted with `{}` instead of `{:?}`, so they are required to implement
+/// [`Display`](core::fmt::Display) rather than [`Debug`](core::fmt::Debug).
+/// This gives nicer messages for user-facing types.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize>() {
+///   # use build_assert::build_assert_eq_display;
+///   build_assert_eq_display!(A, B, "A and B are not equal");
+/// }
+///
+/// foo::<1, 1>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # use build_assert::build_assert_eq_display;
+/// build_assert_eq_display!("foo", "bar");
+/// // assertion `left == right` failed
+/// //   left: foo
+/// //  right: bar
+/// ```
+#[macro_export]
+macro_rules! build_assert_eq_display {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__build_assert_cmp!([==, "{}"] $left, $right)
+  };
+  ($left:expr, $right:expr, $($arg:tt)+) => {
+    $crate::__build_assert_cmp!([==, "{}"] $left, $right, $($arg)+)
+  };
+}
+
+/// Asserts that the first expression is less than the second one at
+/// build-time (using [PartialOrd]).
+///
+/// In release mode, if the expression is evaluated to `false`, or the compiler
+/// or optimizer cannot ensure that the expression is evaluated to `true`, this
+/// macro will stop the compilation process.
+///
+/// In debug mode, if the expression is evaluated to `false`, this macro will
+/// panic.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize>() {
+///   # use build_assert::build_assert_lt;
+///   build_assert_lt!(A, B, "A must be less than B");
+/// }
+///
+/// foo::<1, 2>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const A: usize, const B: usize>() {
+/// #   use build_assert::build_assert_lt;
+/// #   build_assert_lt!(A, B);
+/// # }
+/// foo::<2, 2>(); // Fails to compile in release mode, panics in debug mode.
+/// // assertion `left < right` failed
+/// //   left: 2
+/// //  right: 2
+/// ```
+#[macro_export]
+macro_rules! build_assert_lt {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__build_assert_cmp!([<, "{:?}"] $left, $right)
+  };
+  ($left:expr, $right:expr, $($arg:tt)+) => {
+    $crate::__build_assert_cmp!([<, "{:?}"] $left, $right, $($arg)+)
+  };
+}
+
+/// Asserts that the first expression is less than or equal to the second one at
+/// build-time (using [PartialOrd]).
+///
+/// In release mode, if the expression is evaluated to `false`, or the compiler
+/// or optimizer cannot ensure that the expression is evaluated to `true`, this
+/// macro will stop the compilation process.
+///
+/// In debug mode, if the expression is evaluated to `false`, this macro will
+/// panic.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize>() {
+///   # use build_assert::build_assert_le;
+///   build_assert_le!(A, B, "A must be less than or equal to B");
+/// }
+///
+/// foo::<2, 2>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const A: usize, const B: usize>() {
+/// #   use build_assert::build_assert_le;
+/// #   build_assert_le!(A, B);
+/// # }
+/// foo::<3, 2>(); // Fails to compile in release mode, panics in debug mode.
+/// // assertion `left <= right` failed
+/// //   left: 3
+/// //  right: 2
+/// ```
+#[macro_export]
+macro_rules! build_assert_le {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__build_assert_cmp!([<=, "{:?}"] $left, $right)
+  };
+  ($left:expr, $right:expr, $($arg:tt)+) => {
+    $crate::__build_assert_cmp!([<=, "{:?}"] $left, $right, $($arg)+)
+  };
+}
+
+/// Asserts that the first expression is greater than the second one at
+/// build-time (using [PartialOrd]).
+///
+/// In release mode, if the expression is evaluated to `false`, or the compiler
+/// or optimizer cannot ensure that the expression is evaluated to `true`, this
+/// macro will stop the compilation process.
+///
+/// In debug mode, if the expression is evaluated to `false`, this macro will
+/// panic.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize>() {
+///   # use build_assert::build_assert_gt;
+///   build_assert_gt!(A, B, "A must be greater than B");
+/// }
+///
+/// foo::<2, 1>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const A: usize, const B: usize>() {
+/// #   use build_assert::build_assert_gt;
+/// #   build_assert_gt!(A, B);
+/// # }
+/// foo::<2, 2>(); // Fails to compile in release mode, panics in debug mode.
+/// // assertion `left > right` failed
+/// //   left: 2
+/// //  right: 2
+/// ```
+#[macro_export]
+macro_rules! build_assert_gt {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__build_assert_cmp!([>, "{:?}"] $left, $right)
+  };
+  ($left:expr, $right:expr, $($arg:tt)+) => {
+    $crate::__build_assert_cmp!([>, "{:?}"] $left, $right, $($arg)+)
+  };
+}
+
+/// Asserts that the first expression is greater than or equal to the second one at
+/// build-time (using [PartialOrd]).
+///
+/// In release mode, if the expression is evaluated to `false`, or the compiler
+/// or optimizer cannot ensure that the expression is evaluated to `true`, this
+/// macro will stop the compilation process.
+///
+/// In debug mode, if the expression is evaluated to `false`, this macro will
+/// panic.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize>() {
+///   # use build_assert::build_assert_ge;
+///   build_assert_ge!(A, B, "A must be greater than or equal to B");
+/// }
+///
+/// foo::<2, 2>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const A: usize, const B: usize>() {
+/// #   use build_assert::build_assert_ge;
+/// #   build_assert_ge!(A, B);
+/// # }
+/// foo::<2, 3>(); // Fails to compile in release mode, panics in debug mode.
+/// // assertion `left >= right` failed
+/// //   left: 2
+/// //  right: 3
+/// ```
+#[macro_export]
+macro_rules! build_assert_ge {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__build_assert_cmp!([>=, "{:?}"] $left, $right)
+  };
+  ($left:expr, $right:expr, $($arg:tt)+) => {
+    $crate::__build_assert_cmp!([>=, "{:?}"] $left, $right, $($arg)+)
+  };
+}
+
+/// Asserts that two expressions are distinct values at build-time (using
+/// [PartialEq]).
+///
+/// This macro is the same as [`build_assert_ne`], except that the message
+/// reports the shared value rather than both operands, which reads better
+/// when checking that indices or IDs are distinct.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: usize, const B: usize>() {
+///   # use build_assert::build_assert_distinct;
+///   build_assert_distinct!(A, B);
+/// }
+///
+/// foo::<1, 2>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const A: usize, const B: usize>() {
+/// #   use build_assert::build_assert_distinct;
+/// #   build_assert_distinct!(A, B);
+/// # }
+/// foo::<1, 1>(); // Fails to compile in release mode, panics in debug mode.
+/// // expected distinct values but both were 1
+/// ```
+#[macro_export]
+macro_rules! build_assert_distinct {
+  ($left:expr, $right:expr $(,)?) => {
+    match (&$left, &$right) {
+      (left_val, right_val) => {
+        if *left_val == *right_val {
+          $crate::__build_error_fmt!(
+            ["expected distinct values"]
+            "expected distinct values but both were {:?}",
+            &*left_val,
+          );
+        }
+      }
+    }
+  };
+  ($left:expr, $right:expr, $($arg:tt)+) => {
+    match (&$left, &$right) {
+      (left_val, right_val) => {
+        if *left_val == *right_val {
+          $crate::__build_error_fmt!(
+            ["expected distinct values", $($arg)+]
+            "expected distinct values but both were {:?}: {}",
+            &*left_val,
+            core::format_args!($($arg)+),
+          );
+        }
+      }
+    }
+  };
+}
+
+/// Asserts that two string slices are equal to each other at build-time.
+///
+/// The strings are compared byte by byte in a `const fn`, so this macro does
+/// not rely on [PartialEq] of [`str`]. The message shows both strings and the
+/// index of the first differing byte.
+///
+/// In release mode, if the strings are not equal, or the compiler or optimizer
+/// cannot ensure that they are equal, this macro will stop the compilation
+/// process.
+///
+/// In debug mode, if the strings are not equal, this macro will panic.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_assert_str_eq;
+/// const NAME: &str = "build_assert";
+///
+/// build_assert_str_eq!(NAME, "build_assert"); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # use build_assert::build_assert_str_eq;
+/// const NAME: &str = "build_assert";
+///
+/// build_assert_str_eq!(NAME, "build_error");
+/// // strings differ at byte 6
+/// //   left: "build_assert"
+/// //  right: "build_error"
+/// ```
+#[macro_export]
+macro_rules! build_assert_str_eq {
+  ($left:expr, $right:expr $(,)?) => {
+    match ($left, $right) {
+      // The index is not displayed with `no_fmt`.
+      #[allow(unused_variables)]
+      (left_val, right_val) => {
+        if let Some(i) = $crate::const_cmp::first_diff_index(left_val.as_bytes(), right_val.as_bytes()) {
+          $crate::__build_error_fmt!(
+            ["assertion `left == right` failed"]
+            "assertion `left == right` failed: strings differ at byte {}\n  left: {:?}\n right: {:?}",
+            i,
+            left_val,
+            right_val,
+          );
+        }
+      }
+    }
+  };
+}
+
+/// Asserts that a string slice starts with the given prefix at build-time.
+///
+/// The strings are compared byte by byte in a `const fn`, so this macro does
+/// not rely on [`str::starts_with`]. The message shows both the string and
+/// the expected prefix.
+///
+/// In release mode, if the string does not start with the prefix, or the
+/// compiler or optimizer cannot ensure that it does, this macro will stop the
+/// compilation process.
+///
+/// In debug mode, if the string does not start with the prefix, this macro
+/// will panic.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_assert_starts_with;
+/// const VERSION: &str = "v1.2.0";
+///
+/// build_assert_starts_with!(VERSION, "v1."); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # use build_assert::build_assert_starts_with;
+/// const VERSION: &str = "v1.2.0";
+///
+/// build_assert_starts_with!(VERSION, "v2.");
+/// // string "v1.2.0" does not start with "v2."
+/// ```
+#[macro_export]
+macro_rules! build_assert_starts_with {
+  ($s:expr, $prefix:expr $(,)?) => {
+    match ($s, $prefix) {
+      // The values are not displayed with `no_fmt`.
+      #[allow(unused_variables)]
+      (s, prefix) => {
+        if !$crate::const_cmp::starts_with(s.as_bytes(), prefix.as_bytes()) {
+          $crate::__build_error_fmt!(
+            ["string does not start with the expected prefix"]
+            "string {:?} does not start with {:?}",
+            s,
+            prefix,
+          );
+        }
+      }
+    }
+  };
+}
+
+/// Asserts that a string slice ends with the given suffix at build-time.
+///
+/// The strings are compared byte by byte in a `const fn`, so this macro does
+/// not rely on [`str::ends_with`]. The message shows both the string and the
+/// expected suffix.
+///
+/// In release mode, if the string does not end with the suffix, or the
+/// compiler or optimizer cannot ensure that it does, this macro will stop the
+/// compilation process.
+///
+/// In debug mode, if the string does not end with the suffix, this macro will
+/// panic.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_assert_ends_with;
+/// const VERSION: &str = "v1.2.0-beta";
+///
+/// build_assert_ends_with!(VERSION, "-beta"); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # use build_assert::build_assert_ends_with;
+/// const VERSION: &str = "v1.2.0-beta";
+///
+/// build_assert_ends_with!(VERSION, "-rc");
+/// // string "v1.2.0-beta" does not end with "-rc"
+/// ```
+#[macro_export]
+macro_rules! build_assert_ends_with {
+  ($s:expr, $suffix:expr $(,)?) => {
+    match ($s, $suffix) {
+      // The values are not displayed with `no_fmt`.
+      #[allow(unused_variables)]
+      (s, suffix) => {
+        if !$crate::const_cmp::ends_with(s.as_bytes(), suffix.as_bytes()) {
+          $crate::__build_error_fmt!(
+            ["string does not end with the expected suffix"]
+            "string {:?} does not end with {:?}",
+            s,
+            suffix,
+          );
+        }
+      }
+    }
+  };
+}
+
+/// Asserts that an entry of a lookup table is equal to the expected value at
+/// build-time (using [PartialEq]).
+///
+/// The index is checked against the length of the table first, so an
+/// out-of-bounds index is reported with a distinct message.
+///
+/// # Examples
+///
+/// ```
+/// const LANES: [usize; 4] = [1, 2, 4, 8];
+///
+/// fn foo<const I: usize, const N: usize>() {
+///   # use build_assert::build_assert_lut_eq;
+///   build_assert_lut_eq!(LANES, I, N);
+/// }
+///
+/// foo::<2, 4>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # const LANES: [usize; 4] = [1, 2, 4, 8];
+/// # fn foo<const I: usize, const N: usize>() {
+/// #   use build_assert::build_assert_lut_eq;
+/// #   build_assert_lut_eq!(LANES, I, N);
+/// # }
+/// foo::<2, 8>(); // Fails to compile in release mode, panics in debug mode.
+/// // table entry at index 2 is 4, expected 8
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # const LANES: [usize; 4] = [1, 2, 4, 8];
+/// # fn foo<const I: usize, const N: usize>() {
+/// #   use build_assert::build_assert_lut_eq;
+/// #   build_assert_lut_eq!(LANES, I, N);
+/// # }
+/// foo::<4, 16>(); // index 4 out of bounds for table of length 4
+/// ```
+#[macro_export]
+macro_rules! build_assert_lut_eq {
+  ($table:expr, $idx:expr, $expected:expr $(,)?) => {{
+    $crate::build_assert!(
+      $idx < $table.len(),
+      "index {} out of bounds for table of length {}",
+      $idx,
+      $table.len()
+    );
+    match (&$table, $idx, &$expected) {
+      // The entries are not displayed with `no_fmt`.
+      #[allow(unused_variables)]
+      (table, idx, expected_val) => {
+        if idx < table.len() && !(table[idx] == *expected_val) {
+          $crate::__build_error_fmt!(
+            ["table entry mismatch"]
+            "table entry at index {} is {:?}, expected {:?}",
+            idx,
+            table[idx],
+            *expected_val,
+          );
+        }
+      }
+    }
+  }};
+}
+
+/// Asserts that a value is equal to one of the given values at build-time
+/// (using [PartialEq]).
+///
+/// This macro is useful for checking that a const generic is a valid
+/// discriminant of an enum. On failure, both the value and the allowed
+/// values are displayed using [Debug].
+///
+/// # Examples
+///
+/// ```
+/// #[repr(u8)]
+/// enum Mode {
+///   Read = 1,
+///   Write = 2,
+///   Exec = 4,
+/// }
+///
+/// fn foo<const D: u8>() {
+///   # use build_assert::build_assert_one_of;
+///   build_assert_one_of!(D, [Mode::Read as u8, Mode::Write as u8, Mode::Exec as u8]);
+/// }
+///
+/// foo::<2>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn foo<const D: u8>() {
+/// #   use build_assert::build_assert_one_of;
+/// #   build_assert_one_of!(D, [1, 2, 4]);
+/// # }
+/// foo::<3>(); // Fails to compile in release mode, panics in debug mode.
+/// // value 3 is not one of [1, 2, 4]
+/// ```
+#[macro_export]
+macro_rules! build_assert_one_of {
+  ($value:expr, [$($allowed:expr),+ $(,)?] $(,)?) => {
+    $crate::build_assert!(
+      $($value == $allowed)||+,
+      "value {:?} is not one of {:?}",
+      $value,
+      [$($allowed),+],
+    )
+  };
+}
+
+/// Asserts that a boolean expression is `true` at build-time, after a
+/// deprecation window.
+///
+/// This macro is useful for phasing out a configuration. The version of the
+/// crate being built (`CARGO_PKG_VERSION`) is compared with the given
+/// version `since`:
+///
+/// * If the current version is lower, this macro emits a deprecation
+///   warning containing the message if the condition is `false`.
+/// * Otherwise, this macro behaves like [`build_assert`], which raises a
+///   build-time error if the condition is `false`.
+///
+/// Versions are in the form of `MAJOR[.MINOR[.PATCH]][-PRE][+BUILD]`,
+/// pre-releases are lower than the corresponding release.
+///
+/// Since the warning is selected in a const context, the condition must be
+/// a constant expression that does not refer to generic parameters of the
+/// outer item, e.g. `cfg!(...)` or values of constants.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_assert_deprecated;
+/// const LEGACY_LAYOUT: bool = true;
+///
+/// // The version of this crate is lower than `99.0.0`, so this only warns:
+/// // warning: use of deprecated associated function `...`: legacy layout will be removed
+/// build_assert_deprecated!(!LEGACY_LAYOUT, since = "99.0.0", "legacy layout will be removed");
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # use build_assert::build_assert_deprecated;
+/// const LEGACY_LAYOUT: bool = true;
+///
+/// build_assert_deprecated!(!LEGACY_LAYOUT, since = "0.0.1", "legacy layout is removed");
+/// // legacy layout is removed (an error since version 0.0.1)
+/// ```
+#[macro_export]
+macro_rules! build_assert_deprecated {
+  ($cond:expr, since = $since:literal, $msg:literal $(,)?) => {{
+    const COND: bool = $cond;
+    const REACHED: bool = $crate::const_cmp::version_ge(::core::env!("CARGO_PKG_VERSION"), $since);
+    struct Check<const OK: bool>;
+    #[allow(dead_code)]
+    impl Check<false> {
+      #[deprecated(note = $msg)]
+      fn build_assert_deprecated() {}
+    }
+    #[allow(dead_code)]
+    impl Check<true> {
+      fn build_assert_deprecated() {}
+    }
+    // Warns if the condition does not hold before the version is reached.
+    Check::<{ COND || REACHED }>::build_assert_deprecated();
+    $crate::build_assert!(
+      COND || !REACHED,
+      ::core::concat!($msg, " (an error since version ", $since, ")")
+    );
+  }};
+}
+
+/// Asserts that a value is equal to the only currently supported value at
+/// build-time (using [PartialEq]).
+///
+/// This macro behaves like [`build_assert_eq`], but the message is written
+/// for users of an API that only supports one configuration for now (e.g.
+/// during a staged feature rollout). On failure, both values are displayed
+/// using [Debug].
+///
+/// # Examples
+///
+/// ```
+/// fn simd_width<const LANES: usize>() -> usize {
+///   # use build_assert::build_assert_exactly;
+///   build_assert_exactly!(LANES, 4);
+///   LANES * 32
+/// }
+///
+/// assert_eq!(simd_width::<4>(), 128); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn simd_width<const LANES: usize>() -> usize {
+/// #   use build_assert::build_assert_exactly;
+/// #   build_assert_exactly!(LANES, 4);
+/// #   LANES * 32
+/// # }
+/// simd_width::<8>(); // Fails to compile in release mode, panics in debug mode.
+/// // this API currently requires 4, got 8
+/// ```
+#[macro_export]
+macro_rules! build_assert_exactly {
+  ($value:expr, $expected:expr $(,)?) => {
+    $crate::build_assert!(
+      $value == $expected,
+      "this API currently requires {:?}, got {:?}",
+      $expected,
+      $value,
+    )
+  };
+}
+
+/// Const comparison helpers for building assertions.
+///
+/// All helpers are `const fn`, so they can be used in conditions evaluated in
+/// const contexts, e.g. `build_assert!(const { ... })`.
+#[doc(hidden)]
+pub mod const_cmp {
+  /// Returns the index of the first byte that differs between two slices, or
+  /// `None` if they are equal.
+  ///
+  /// If one slice is a prefix of the other, returns the length of the shorter
+  /// one.
+  #[inline(always)]
+  pub const fn first_diff_index(left: &[u8], right: &[u8]) -> Option<usize> {
+    let mut i = 0;
+    while i < left.len() && i < right.len() {
+      if left[i] != right[i] {
+        return Some(i);
+      }
+      i += 1;
+    }
+    if left.len() == right.len() {
+      None
+    } else {
+      Some(i)
+    }
+  }
+
+  /// Returns `true` if the byte slice starts with the given prefix.
+  #[inline(always)]
+  pub const fn starts_with(s: &[u8], prefix: &[u8]) -> bool {
+    if prefix.len() > s.len() {
+      return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+      if s[i] != prefix[i] {
+        return false;
+      }
+      i += 1;
+    }
+    true
+  }
+
+  /// Returns `true` if the byte slice ends with the given suffix.
+  #[inline(always)]
+  pub const fn ends_with(s: &[u8], suffix: &[u8]) -> bool {
+    if suffix.len() > s.len() {
+      return false;
+    }
+    let offset = s.len() - suffix.len();
+    let mut i = 0;
+    while i < suffix.len() {
+      if s[offset + i] != suffix[i] {
+        return false;
+      }
+      i += 1;
+    }
+    true
+  }
+
+  /// Returns `true` if the version `current` is greater than or equal to the
+  /// version `since`.
+  ///
+  /// Versions are in the form of `MAJOR[.MINOR[.PATCH]][-PRE][+BUILD]`.
+  /// Missing components are treated as zeros, build metadata is ignored, and
+  /// pre-releases are lower than the corresponding release (pre-release
+  /// identifiers themselves are not compared).
+  ///
+  /// # Panics
+  ///
+  /// Panics if either version is malformed.
+  #[inline(always)]
+  pub const fn version_ge(current: &str, since: &str) -> bool {
+    let current = parse_version(current.as_bytes());
+    let since = parse_version(since.as_bytes());
+    let mut i = 0;
+    while i < current.len() {
+      if current[i] != since[i] {
+        return current[i] > since[i];
+      }
+      i += 1;
+    }
+    true
+  }
+
+  /// Parses the given version into `[major, minor, patch, is_release]`.
+  const fn parse_version(version: &[u8]) -> [u64; 4] {
+    let mut parts = [0, 0, 0, 1];
+    let (mut part, mut digits) = (0, 0);
+    let mut i = 0;
+    while i < version.len() {
+      match version[i] {
+        c @ b'0'..=b'9' => {
+          parts[part] = parts[part] * 10 + (c - b'0') as u64;
+          digits += 1;
+        }
+        b'.' if digits > 0 && part < 2 => {
+          part += 1;
+          digits = 0;
+        }
+        b'-' if digits > 0 => {
+          parts[3] = 0;
+          break;
+        }
+        b'+' if digits > 0 => break,
+        _ => panic!("malformed version"),
+      }
+      i += 1;
+    }
+    if digits == 0 {
+      panic!("malformed version");
+    }
+    parts
+  }
+
+  /// Returns the greatest common divisor of two integers, the GCD of `0` and
+  /// `0` is `0`.
+  ///
+  /// The optimizer does not fold loops of the Euclidean algorithm, so its
+  /// steps are unrolled, which lets the result be folded for constant
+  /// operands. By Lamé's theorem, at most 185 steps are needed for `u128`.
+  #[inline(always)]
+  #[allow(unused_assignments)]
+  pub const fn gcd(mut a: u128, mut b: u128) -> u128 {
+    macro_rules! step {
+      () => {
+        if b != 0 {
+          let r = a % b;
+          a = b;
+          b = r;
+        }
+      };
+    }
+    macro_rules! repeat_6 {
+      ($($t:tt)*) => {
+        $($t)* $($t)* $($t)* $($t)* $($t)* $($t)*
+      };
+    }
+    // 216 steps.
+    repeat_6! {
+      repeat_6! {
+        repeat_6! {
+          step!();
+        }
+      }
+    }
+    a
+  }
+}
+
+/// Asserts that the given snippet fails as expected in the current profile.
+///
+/// The snippet is built as the `main.rs` of a separate crate, using the same
+/// profile and features of `build_assert` as the current build. If the
+/// snippet does not contain a `main` function, it is wrapped in one. Then:
+///
+/// * With inline assembly or link errors (release mode), the build must fail.
+/// * With panics (debug mode) or the `error_handler` feature, the snippet
+///   must build and fail at runtime. With `strict_debug`, a build failure is
+///   accepted too.
+///
+/// If a message is given, it must appear in the error output, unless a link
+/// error is raised, since the message is not part of it.
+///
+/// This macro is only available with the `testing` feature, and requires
+/// `cargo` to be available when running tests. See [`testing`] for more
+/// information.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use build_assert::assert_build_fails;
+/// assert_build_fails!(
+///   r#"
+///   fn foo<const N: usize>() {
+///     build_assert::build_assert!(N > 5, "N must be greater than 5");
+///   }
+///
+///   fn main() {
+///     foo::<0>();
+///   }
+///   "#,
+///   "N must be greater than 5",
+/// );
+/// ```
+#[cfg(feature = "testing")]
+#[macro_export]
+macro_rules! assert_build_fails {
+  ($snippet:expr $(,)?) => {
+    $crate::testing::assert_build_fails($snippet, None)
+  };
+  ($snippet:expr, $msg:expr $(,)?) => {
+    $crate::testing::assert_build_fails($snippet, Some($msg))
+  };
+}
+
+/// Test support for crates using `build_assert`.
+///
+/// This module is only available with the `testing` feature, which requires
+/// `std`. The snippets are built in `build_assert_testing` of the temporary
+/// directory, so repeated builds can reuse the compiled dependencies.
+#[cfg(feature = "testing")]
+pub mod testing {
+  use std::collections::hash_map::DefaultHasher;
+  use std::hash::{Hash, Hasher};
+  use std::path::{Path, PathBuf};
+  use std::process::{Command, Output};
+  use std::string::String;
+  use std::vec::Vec;
+  use std::{env, format, fs, panic};
+
+  /// Features of `build_assert` that are forwarded to the snippet crate.
+  const FEATURES: &[(&str, bool)] = &[
+    ("no_asm", cfg!(feature = "no_asm")),
+    ("error_handler", cfg!(feature = "error_handler")),
+    ("strict_debug", cfg!(feature = "strict_debug")),
+    ("warn_non_const", cfg!(feature = "warn_non_const")),
+    ("const_panic", cfg!(feature = "const_panic")),
+    ("disabled", cfg!(feature = "disabled")),
+    ("no_fmt", cfg!(feature = "no_fmt")),
+    ("emit_metadata", cfg!(feature = "emit_metadata")),
+  ];
+
+  /// Asserts that the given snippet fails as expected in the current
+  /// profile.
+  ///
+  /// See [`assert_build_fails`](crate::assert_build_fails) for details.
+  #[track_caller]
+  pub fn assert_build_fails(snippet: &str, msg: Option<&str>) {
+    let kind = crate::__BUILD_ERROR_KIND;
+    if kind == "disabled" {
+      panic!("assertions are compiled out by feature `disabled`");
+    }
+    let dir = create(snippet);
+    let output = cargo(&dir, "build");
+    let built = output.status.success();
+    let stderr = match (kind, built) {
+      ("asm" | "link", true) => panic!("expected the snippet to fail to build, but it built"),
+      ("asm" | "link", false) => stderr(&output),
+      (_, false) if cfg!(feature = "strict_debug") => stderr(&output),
+      (_, false) => panic!("failed to build the snippet:\n{}", stderr(&output)),
+      (_, true) => {
+        let output = cargo(&dir, "run");
+        let stderr = stderr(&output);
+        assert!(
+          !output.status.success(),
+          "expected the snippet to fail at runtime, but it succeeded:\n{stderr}"
+        );
+        stderr
+      }
+    };
+    if let (Some(msg), false) = (msg, kind == "link") {
+      assert!(
+        stderr.contains(msg),
+        "expected `{msg}` in the error output:\n{stderr}"
+      );
+    }
+  }
+
+  /// Creates a crate containing the given snippet, returns its directory.
+  fn create(snippet: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    snippet.hash(&mut hasher);
+    let dir = root().join(format!("snippet_{:016x}", hasher.finish()));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    let features = FEATURES
+      .iter()
+      .filter(|(_, enabled)| *enabled)
+      .map(|(f, _)| format!("{f:?}"))
+      .collect::<Vec<_>>()
+      .join(", ");
+    let manifest = format!(
+      r#"[package]
+name = "snippet"
+version = "0.0.0"
+edition = "2021"
+
+[dependencies]
+build_assert = {{ path = {:?}, features = [{features}] }}
+
+[workspace]
+"#,
+      env!("CARGO_MANIFEST_DIR"),
+    );
+    fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    let main = if snippet.contains("fn main") {
+      snippet.into()
+    } else {
+      format!("fn main() {{\n{snippet}\n}}\n")
+    };
+    fs::write(dir.join("src/main.rs"), main).unwrap();
+    // Reuse the lock file if possible, to avoid resolving dependencies again.
+    let lock = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.lock");
+    if lock.exists() {
+      fs::copy(lock, dir.join("Cargo.lock")).unwrap();
+    }
+    dir
+  }
+
+  /// Runs the given `cargo` command in the given directory.
+  fn cargo(dir: &Path, command: &str) -> Output {
+    let mut cmd = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cmd
+      .arg(command)
+      .arg("--quiet")
+      .current_dir(dir)
+      .env("CARGO_TARGET_DIR", root().join("target"));
+    if cfg!(build = "release") {
+      cmd.arg("--release");
+    }
+    cmd.output().unwrap()
+  }
+
+  /// Returns the root directory of the snippet crates.
+  fn root() -> PathBuf {
+    env::temp_dir().join("build_assert_testing")
+  }
+
+  /// Returns the standard error output as a string.
+  fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+  }
+}
+
+/// Asserts a list of conditions at build-time.
+///
+/// Conditions are separated by `;`, and each of them can be followed by an
+/// optional custom message in the form of `=> "message"`. Conditions of the
+/// form `a == b` and `a != b` are asserted by [`build_assert_eq`] and
+/// [`build_assert_ne`] respectively, others are asserted by [`build_assert`].
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const N: usize, const M: usize>() {
+///   # use build_assert::build_assert_block;
+///   build_assert_block! {
+///     N > 0;
+///     M.is_power_of_two() => "M must be a power of two";
+///     N != M => "N and M must be different, got {}", N;
+///   }
+/// }
+///
+/// foo::<1, 4>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const N: usize, const M: usize>() {
+/// #   use build_assert::build_assert_block;
+/// #   build_assert_block! {
+/// #     N > 0;
+/// #     M.is_power_of_two() => "M must be a power of two";
+/// #     N != M => "N and M must be different, got {}", N;
+/// #   }
+/// # }
+/// foo::<1, 3>(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[macro_export]
+macro_rules! build_assert_block {
+  ($($t:tt)*) => {
+    $crate::__build_assert_block!(@munch [] $($t)*)
+  };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_assert_block {
+  // Splits the block into entries.
+  (@munch []) => {};
+  (@munch [$($cond:tt)+]) => {
+    $crate::__build_assert_block!(@check [$($cond)+] [] $($cond)+);
+  };
+  (@munch [] ; $($rest:tt)*) => {
+    $crate::__build_assert_block!(@munch [] $($rest)*);
+  };
+  (@munch [$($cond:tt)+] ; $($rest:tt)*) => {
+    $crate::__build_assert_block!(@check [$($cond)+] [] $($cond)+);
+    $crate::__build_assert_block!(@munch [] $($rest)*);
+  };
+  (@munch [$($cond:tt)+] => $msg:literal $(, $arg:expr)* $(; $($rest:tt)*)?) => {
+    $crate::__build_assert_block!(@check [$($cond)+] [, $msg $(, $arg)*] $($cond)+);
+    $crate::__build_assert_block!(@munch [] $($($rest)*)?);
+  };
+  (@munch [$($cond:tt)*] $t:tt $($rest:tt)*) => {
+    $crate::__build_assert_block!(@munch [$($cond)* $t] $($rest)*);
+  };
+  // Operators `&&` and `||` have lower precedence than `==` and `!=`.
+  (@check [$($cond:tt)+] [$($msg:tt)*] && $($rest:tt)*) => {
+    $crate::build_assert!($($cond)+ $($msg)*);
+  };
+  (@check [$($cond:tt)+] [$($msg:tt)*] || $($rest:tt)*) => {
+    $crate::build_assert!($($cond)+ $($msg)*);
+  };
+  (@check [$($cond:tt)+] [$($msg:tt)*]) => {
+    $crate::__build_assert_block!(@split [$($cond)+] [$($msg)*] [] $($cond)+);
+  };
+  (@check [$($cond:tt)+] [$($msg:tt)*] $t:tt $($rest:tt)*) => {
+    $crate::__build_assert_block!(@check [$($cond)+] [$($msg)*] $($rest)*);
+  };
+  // Finds top-level `==` or `!=` in the condition.
+  (@split [$($cond:tt)+] [$($msg:tt)*] [$($l:tt)+] == $($r:tt)+) => {
+    $crate::build_assert_eq!($($l)+, $($r)+ $($msg)*);
+  };
+  (@split [$($cond:tt)+] [$($msg:tt)*] [$($l:tt)+] != $($r:tt)+) => {
+    $crate::build_assert_ne!($($l)+, $($r)+ $($msg)*);
+  };
+  (@split [$($cond:tt)+] [$($msg:tt)*] [$($l:tt)*]) => {
+    $crate::build_assert!($($cond)+ $($msg)*);
+  };
+  (@split [$($cond:tt)+] [$($msg:tt)*] [$($l:tt)*] $t:tt $($rest:tt)*) => {
+    $crate::__build_assert_block!(@split [$($cond)+] [$($msg)*] [$($l)* $t] $($rest)*);
+  };
+}
+
+/// Asserts that a constant boolean expression is `true` at build-time.
+///
+/// Unlike [`build_assert`], this macro can be used at module scope, and the
+/// expression is evaluated by the compiler rather than the optimizer, so it
+/// **fails to compile in both debug and release mode**. As a consequence, the
+/// expression can not refer to generic parameters of the outer item, and the
+/// custom message must be a string literal.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_static_assert;
+/// struct Foo(u32, u32);
+///
+/// build_static_assert!(core::mem::size_of::<Foo>() == 8); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// # use build_assert::build_static_assert;
+/// struct Foo(u32, u32);
+///
+/// build_static_assert!(core::mem::size_of::<Foo>() == 4, "Foo is too large");
+/// ```
+#[macro_export]
+macro_rules! build_static_assert {
+  ($cond:expr $(,)?) => {
+    const _: () = core::assert!(
+      $cond,
+      core::concat!("assertion failed: ", core::stringify!($cond))
+    );
+  };
+  ($cond:expr, $($arg:tt)+) => {
+    const _: () = core::assert!($cond, $($arg)+);
+  };
+}
+
+/// Asserts a list of constant boolean expressions at build-time, and reports
+/// all failed expressions in a single error.
+///
+/// Like [`build_static_assert`], this macro can be used at module scope, and
+/// the expressions are evaluated by the compiler, so it **fails to compile in
+/// both debug and release mode**. Unlike [`build_static_assert`], it does not
+/// stop at the first failed expression, the error message lists all of them.
+///
+/// # Examples
+///
+/// ```
+/// # use build_assert::build_assert_report;
+/// const N: usize = 8;
+///
+/// build_assert_report! {
+///   N > 5;
+///   N.is_power_of_two();
+///   core::mem::size_of::<u64>() == N;
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use build_assert::build_assert_report;
+/// const N: usize = 3;
+///
+/// build_assert_report! {
+///   N > 5;
+///   N.is_power_of_two();
+///   N != 0;
+/// }
+/// // error[E0080]: evaluation panicked: build assertions failed:
+/// //                 - N > 5
+/// //                 - N.is_power_of_two()
+/// ```
+#[macro_export]
+macro_rules! build_assert_report {
+  ($($cond:expr);+ $(;)?) => {
+    const _: () = $crate::Report::new()
+      $(.check($cond, core::stringify!($cond)))+
+      .finish();
+  };
+}
+
+/// Failures collected by [`build_assert_report`].
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Report {
+  buf: [u8; Report::CAPACITY],
+  len: usize,
+  failed: bool,
+}
+
+impl Report {
+  /// Maximum length of the report message in bytes, the rest are truncated.
+  const CAPACITY: usize = 1024;
+
+  /// Header of the report message.
+  const HEADER: &'static str = "build assertions failed:";
+
+  /// Creates a new empty report.
+  pub const fn new() -> Self {
+    Self {
+      buf: [0; Self::CAPACITY],
+      len: 0,
+      failed: false,
+    }
+  }
+
+  /// Records the given expression if the condition is `false`.
+  pub const fn check(self, cond: bool, expr: &str) -> Self {
+    if cond {
+      return self;
+    }
+    let report = if self.failed {
+      self
+    } else {
+      self.push(Self::HEADER)
+    };
+    let mut report = report.push("\n  - ").push(expr);
+    report.failed = true;
+    report
+  }
+
+  /// Returns the report message.
+  pub const fn message(&self) -> &str {
+    match core::str::from_utf8(self.buf.split_at(self.len).0) {
+      Ok(s) => s,
+      Err(_) => Self::HEADER,
+    }
+  }
+
+  /// Raises a compile error if any condition failed.
+  pub const fn finish(self) {
+    if self.failed {
+      core::panic!("{}", self.message());
+    }
+  }
+
+  /// Appends the given string to the message, truncates at a character
+  /// boundary if there is not enough space.
+  const fn push(mut self, s: &str) -> Self {
+    let bytes = s.as_bytes();
+    let mut n = Self::CAPACITY - self.len;
+    if n >= bytes.len() {
+      n = bytes.len();
+    } else {
+      while n > 0 && bytes[n] & 0xc0 == 0x80 {
+        n -= 1;
+      }
+    }
+    let mut i = 0;
+    while i < n {
+      self.buf[self.len] = bytes[i];
+      self.len += 1;
+      i += 1;
+    }
+    self
+  }
+}
+
+impl Default for Report {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Asserts that an index is in bounds of the given length at build-time.
+///
+/// In release mode, if the index is not less than the length, or the compiler
+/// or optimizer cannot ensure that, this macro will stop the compilation
+/// process.
+///
+/// In debug mode, if the index is not less than the length, this macro will
+/// panic with a message like the one of an out-of-bounds indexing.
+///
+/// # Examples
+///
+/// ```
+/// fn get<const I: usize, const N: usize>(arr: [u8; N]) -> u8 {
+///   # use build_assert::build_assert_index;
+///   build_assert_index!(I, N);
+///   arr[I]
+/// }
+///
+/// get::<1, 4>([0; 4]); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn get<const I: usize, const N: usize>(arr: [u8; N]) -> u8 {
+/// #   use build_assert::build_assert_index;
+/// #   build_assert_index!(I, N);
+/// #   arr[I]
+/// # }
+/// get::<4, 4>([0; 4]); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[macro_export]
+macro_rules! build_assert_index {
+  ($idx:expr, $len:expr $(,)?) => {
+    match (&$idx, &$len) {
+      (idx_val, len_val) => {
+        if !(*idx_val < *len_val) {
+          $crate::build_error!("index {} out of bounds for length {}", &*idx_val, &*len_val);
+        }
+      }
+    }
+  };
+}
+
+/// Asserts that the size of a type is equal to the given number of bytes at
+/// build-time.
+///
+/// Since [`core::mem::size_of`] is a `const fn`, in release mode, this macro
+/// will stop the compilation process if the size does not match.
+///
+/// In debug mode, if the size does not match, this macro will panic.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const N: usize>() {
+///   # use build_assert::build_assert_size;
+///   build_assert_size!([u32; N], 16);
+/// }
+///
+/// foo::<4>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const N: usize>() {
+/// #   use build_assert::build_assert_size;
+/// #   build_assert_size!([u32; N], 16);
+/// # }
+/// foo::<3>(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[macro_export]
+macro_rules! build_assert_size {
+  ($ty:ty, $bytes:expr $(,)?) => {
+    match (&core::mem::size_of::<$ty>(), &$bytes) {
+      (size_val, bytes_val) => {
+        if *size_val != *bytes_val {
+          $crate::build_error!(
+            "size of `{}` is {} bytes, expected {}",
+            core::stringify!($ty),
+            &*size_val,
+            &*bytes_val,
+          );
+        }
+      }
+    }
+  };
+}
+
+/// Asserts that an integer is a valid shift amount for the given integer type
+/// at build-time, i.e. `amount < core::mem::size_of::<T>() * 8`.
+///
+/// Shifting by the width of the type or more overflows, which panics in debug
+/// mode and is masked in release mode. This macro catches it at build-time.
+/// The amount is converted to [`usize`] with `as` before comparison, so
+/// negative amounts are rejected as well.
+///
+/// # Examples
+///
+/// ```
+/// fn shl<const S: u32>(x: u16) -> u16 {
+///   # use build_assert::build_assert_shift_ok;
+///   build_assert_shift_ok!(S, u16);
+///   x << S
+/// }
+///
+/// assert_eq!(shl::<15>(1), 0x8000); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # fn shl<const S: u32>(x: u16) -> u16 {
+/// #   use build_assert::build_assert_shift_ok;
+/// #   build_assert_shift_ok!(S, u16);
+/// #   x << S
+/// # }
+/// shl::<16>(1); // Fails to compile in release mode, panics in debug mode.
+/// // shift amount 16 is out of range for `u16` of 16 bits
+/// ```
+#[macro_export]
+macro_rules! build_assert_shift_ok {
+  ($amount:expr, $ty:ty $(,)?) => {
+    $crate::build_assert!(
+      ($amount as usize) < core::mem::size_of::<$ty>() * 8,
+      "shift amount {} is out of range for `{}` of {} bits",
+      $amount,
+      core::stringify!($ty),
+      core::mem::size_of::<$ty>() * 8
+    )
+  };
+}
+
+/// Result of build-time checks, created by [`check`] and combined by
+/// [`BuildCheck::and`].
+///
+/// Call [`finish`] to raise a build-time error if any of the checks failed.
+/// Dropping a failed [`BuildCheck`] without calling [`finish`] also raises a
+/// build-time error.
+///
+/// # Examples
+///
+/// ```
+/// use build_assert::{check, finish};
+///
+/// fn foo<const A: usize, const B: usize>() {
+///   finish!(check(A > 0).and(check(B > A)));
+/// }
+///
+/// foo::<1, 2>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # use build_assert::{check, finish};
+/// # fn foo<const A: usize, const B: usize>() {
+/// #   finish!(check(A > 0).and(check(B > A)));
+/// # }
+/// foo::<2, 1>(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[derive(Debug)]
+#[must_use = "call `finish!` to raise build-time errors"]
+pub struct BuildCheck {
+  len: usize,
+  first_failure: Option<usize>,
+}
+
+impl BuildCheck {
+  /// Combines this check with another check.
+  ///
+  /// The index of the first failed check is preserved.
+  #[inline(always)]
+  pub const fn and(self, other: Self) -> Self {
+    let (len, first_failure) = (self.len, self.first_failure);
+    let (other_len, other_failure) = (other.len, other.first_failure);
+    core::mem::forget(self);
+    core::mem::forget(other);
+    Self {
+      len: len + other_len,
+      first_failure: match (first_failure, other_failure) {
+        (Some(i), _) => Some(i),
+        (None, Some(i)) => Some(len + i),
+        (None, None) => None,
+      },
+    }
+  }
+
+  /// Returns the number of checks.
+  #[inline(always)]
+  pub const fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns `true` if there are no checks.
+  #[inline(always)]
+  pub const fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Consumes this check, returns the index of the first failed check.
+  #[inline(always)]
+  pub const fn into_first_failure(self) -> Option<usize> {
+    let first_failure = self.first_failure;
+    core::mem::forget(self);
+    first_failure
+  }
+}
+
+impl Drop for BuildCheck {
+  // The index is not displayed by `build_error` in release mode.
+  #[allow(unused_variables)]
+  #[inline(always)]
+  fn drop(&mut self) {
+    if let Some(i) = self.first_failure {
+      build_error!("build check {} failed", i);
+    }
+  }
+}
+
+/// Creates a [`BuildCheck`] for the given condition.
+#[inline(always)]
+pub const fn check(cond: bool) -> BuildCheck {
+  BuildCheck {
+    len: 1,
+    first_failure: if cond { None } else { Some(0) },
+  }
+}
+
+/// Raises a build-time error if any of the checks in the given [`BuildCheck`]
+/// failed.
+///
+/// In release mode, if any of the checks failed, or the compiler or optimizer
+/// cannot ensure that all the checks passed, this macro will stop the
+/// compilation process.
+///
+/// In debug mode, if any of the checks failed, this macro will panic with the
+/// index of the first failed check.
+///
+/// See [`BuildCheck`] for examples.
+#[macro_export]
+macro_rules! finish {
+  ($check:expr $(,)?) => {
+    match $crate::BuildCheck::into_first_failure($check) {
+      // The index is not displayed by `build_error` in release mode.
+      #[allow(unused_variables)]
+      Some(i) => $crate::build_error!("build check {} failed", i),
+      None => {}
+    }
+  };
+}
+
+/// Zero-sized witness of a passed build-time check, created by
+/// [`build_proof`].
+///
+/// In safe code, a [`Proof`] can only be obtained by [`build_proof`], so
+/// functions can
+/// require a [`Proof`] parameter to statically encode that a precondition was
+/// checked. The type parameter `T` is a marker that tells which precondition
+/// is proven, it is `()` by default.
+///
+/// # Examples
+///
+/// ```
+/// use build_assert::{build_proof, Proof};
+///
+/// struct Aligned;
+///
+/// fn read_aligned(_: Proof<Aligned>, addr: usize) -> usize {
+///   addr
+/// }
+///
+/// fn foo<const ADDR: usize>() -> usize {
+///   read_aligned(build_proof!(ADDR % 8 == 0 => Aligned), ADDR)
+/// }
+///
+/// assert_eq!(foo::<64>(), 64); // Fine.
+/// ```
+///
+/// A [`Proof`] can not be constructed directly:
+///
+/// ```compile_fail
+/// # use build_assert::Proof;
+/// let proof: Proof = Proof(core::marker::PhantomData);
+/// ```
+///
+/// Nor by the constructor used by [`build_proof`] in safe code:
+///
+/// ```compile_fail
+/// # use build_assert::Proof;
+/// let proof: Proof = build_assert::__proof::new();
+/// ```
+pub struct Proof<T: ?Sized = ()>(core::marker::PhantomData<fn() -> T>);
+
+/// Constructor of [`Proof`] used by the expansion of [`build_proof`].
+///
+/// This module is not part of the public API.
+#[doc(hidden)]
+pub mod __proof {
+  use crate::Proof;
+
+  /// Creates a new proof after the check of
+  /// [`build_proof`](crate::build_proof) passed.
+  ///
+  /// **Do not call this function directly.** It may be changed or removed in
+  /// any release.
+  ///
+  /// # Safety
+  ///
+  /// The caller must have asserted the precondition that `T` stands for,
+  /// otherwise the [`Proof`] is forged.
+  #[inline(always)]
+  pub const unsafe fn new<T: ?Sized>() -> Proof<T> {
+    Proof(core::marker::PhantomData)
+  }
+}
+
+impl<T: ?Sized> Clone for Proof<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T: ?Sized> Copy for Proof<T> {}
+
+impl<T: ?Sized> core::fmt::Debug for Proof<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    write!(f, "Proof<{}>", core::any::type_name::<T>())
+  }
+}
+
+/// Asserts that a boolean expression is `true` at build-time, evaluates to a
+/// [`Proof`] of the check.
+///
+/// This macro is the same as [`build_assert`], except that it evaluates to a
+/// [`Proof`], which can be passed to functions that require the check to be
+/// done. Use `cond => T` to create a `Proof<T>` instead of `Proof<()>`.
+///
+/// # Examples
+///
+/// ```
+/// use build_assert::{build_proof, Proof};
+///
+/// struct NonEmpty;
+///
+/// fn foo<const N: usize>() -> Proof<NonEmpty> {
+///   build_proof!(N > 0 => NonEmpty, "N must be non-zero")
+/// }
+///
+/// let _ = foo::<1>(); // Fine.
+/// ```
+///
+#[cfg_attr(
+  all(build = "debug", not(feature = "strict_debug")),
+  doc = "```should_panic"
+)]
+#[cfg_attr(
+  any(build = "release", feature = "strict_debug"),
+  doc = "```compile_fail"
+)]
+/// # use build_assert::{build_proof, Proof};
+/// fn foo<const N: usize>() -> Proof {
+///   build_proof!(N > 0)
+/// }
+///
+/// let _ = foo::<0>(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[macro_export]
+macro_rules! build_proof {
+  ($cond:expr $(,)?) => {{
+    $crate::build_assert!($cond);
+    // SAFETY: the condition is asserted above.
+    unsafe { $crate::__proof::new::<()>() }
+  }};
+  ($cond:expr => $tag:ty $(,)?) => {{
+    $crate::build_assert!($cond);
+    // SAFETY: the condition is asserted above.
+    unsafe { $crate::__proof::new::<$tag>() }
+  }};
+  ($cond:expr => $tag:ty, $($arg:tt)+) => {{
+    $crate::build_assert!($cond, $($arg)+);
+    // SAFETY: the condition is asserted above.
+    unsafe { $crate::__proof::new::<$tag>() }
+  }};
+  ($cond:expr, $($arg:tt)+) => {{
+    $crate::build_assert!($cond, $($arg)+);
+    // SAFETY: the condition is asserted above.
+    unsafe { $crate::__proof::new::<()>() }
+  }};
+}
+
+/// Asserts that an arithmetic operation on integers does not overflow at
+/// build-time.
+///
+/// The operation is written as `lhs op rhs`, where `op` can be `+`, `-`, `*`,
+/// `/` or `%`, and both operands must be of the given integer type. Operands
+/// that are not a single token tree (e.g. `a.b`) must be parenthesized.
+///
+/// In release mode, if the operation overflows, or the compiler or optimizer
+/// cannot ensure that it does not overflow, this macro will stop the
+/// compilation process.
+///
+/// In debug mode, if the operation overflows, this macro will panic.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<const A: u32, const B: u32>() {
+///   # use build_assert::build_assert_no_overflow;
+///   build_assert_no_overflow!(A * B, u32);
+/// }
+///
+/// foo::<65535, 65537>(); // Fine.
+/// ```
+///
+#[cfg_attr(build = "debug", doc = "```should_panic")]
+#[cfg_attr(build = "release", doc = "```compile_fail")]
+/// # fn foo<const A: u32, const B: u32>() {
+/// #   use build_assert::build_assert_no_overflow;
+/// #   build_assert_no_overflow!(A * B, u32);
+/// # }
+/// foo::<65536, 65536>(); // Fails to compile in release mode, panics in debug mode.
+/// ```
+#[macro_export]
+macro_rules! build_assert_no_overflow {
+  ($lhs:tt + $rhs:tt, $ty:ty $(,)?) => {
+    $crate::build_assert_no_overflow!(@check checked_add, "+", $lhs, $rhs, $ty)
+  };
+  ($lhs:tt - $rhs:tt, $ty:ty $(,)?) => {
+    $crate::build_assert_no_overflow!(@check checked_sub, "-", $lhs, $rhs, $ty)
+  };
+  ($lhs:tt * $rhs:tt, $ty:ty $(,)?) => {
+    $crate::build_assert_no_overflow!(@check checked_mul, "*", $lhs, $rhs, $ty)
+  };
+  ($lhs:tt / $rhs:tt, $ty:ty $(,)?) => {
+    $crate::build_assert_no_overflow!(@check checked_div, "/", $lhs, $rhs, $ty)
+  };
+  ($lhs:tt % $rhs:tt, $ty:ty $(,)?) => {
+    $crate::build_assert_no_overflow!(@check checked_rem, "%", $lhs, $rhs, $ty)
+  };
+  (@check $method:ident, $op:literal, $lhs:expr, $rhs:expr, $ty:ty) => {
+    match ($lhs, $rhs) {
+      (lhs_val, rhs_val) => {
+        if <$ty>::$method(lhs_val, rhs_val).is_none() {
+          $crate::build_error!(
+            core::concat!("arithmetic overflow: {} ", $op, " {} overflows `{}`"),
+            lhs_val,
+            rhs_val,
+            core::stringify!($ty),
+          );
+        }
+      }
+    }
+  };
+}
+
+/// Asserts that a type implements the given traits at build-time.
+///
+/// Since unsatisfied trait bounds are always reported by the type checker,
+/// this macro **fails to compile in both debug and release mode**.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<T: Clone + Send>() {
+///   # use build_assert::build_assert_impl;
+///   build_assert_impl!(T: Clone + Send);
+/// }
+///
+/// foo::<i32>(); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// fn foo<T>() {
+///   # use build_assert::build_assert_impl;
+///   build_assert_impl!(T: Clone); // Fails to compile.
+/// }
+/// ```
+pub use build_assert_macros::build_assert_impl;
+
+/// Asserts that a type implements the given traits at build-time, reports
+/// the given message if it does not.
+///
+/// Like [`build_assert_impl`], this macro **fails to compile in both debug
+/// and release mode**, but the custom message replaces the default "trait
+/// bound is not satisfied" error, which helps to explain subtle bounds of
+/// library APIs.
+///
+/// # Examples
+///
+/// ```
+/// fn foo<T: Copy + Send>() {
+///   # use build_assert::build_assert_satisfies;
+///   build_assert_satisfies!(T, "must be Copy for SIMD", Copy + Send);
+/// }
+///
+/// foo::<i32>(); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// fn foo<T>() {
+///   # use build_assert::build_assert_satisfies;
+///   build_assert_satisfies!(T, "must be Copy for SIMD", Copy); // Fails to compile.
+///   // error[E0277]: unsatisfied bounds for `T`: must be Copy for SIMD
+/// }
+/// ```
+pub use build_assert_macros::build_assert_satisfies;
+
+/// Asserts that a trait is dyn compatible (object safe) at build-time.
+///
+/// This is useful for unsafe abstractions relying on the layout of trait
+/// objects. The error points to the trait in the macro input and explains
+/// why the trait is not dyn compatible. Since dyn compatibility is checked by
+/// the compiler, this macro **fails to compile in both debug and release
+/// mode**.
+///
+/// # Examples
+///
+/// ```
+/// trait Shape {
+///   fn area(&self) -> f64;
+/// }
+///
+/// fn foo<T>() {
+///   # use build_assert::build_assert_dyn_safe;
+///   build_assert_dyn_safe!(Shape + Send);
+///   build_assert_dyn_safe!(AsRef<T>);
+/// }
+///
+/// foo::<i32>(); // Fine.
+/// ```
+///
+/// ```compile_fail
+/// trait Shape {
+///   fn new() -> Self;
+/// }
+///
+/// # use build_assert::build_assert_dyn_safe;
+/// build_assert_dyn_safe!(Shape); // Fails to compile.
+/// // error[E0038]: the trait `Shape` is not dyn compatible
+/// ```
+pub use build_assert_macros::build_assert_dyn_safe;
+
+pub use build_assert_macros::build_uniq_sym;
+
+/// Re-exports of all assertion macros and helpers.
+///
+/// Downstream crates, including `#![no_std]` ones, can import everything at
+/// once:
+///
+/// ```
+/// use build_assert::prelude::*;
+///
+/// fn foo<const N: usize>() {
+///   build_assert!(N > 5);
+///   build_assert_eq!(N % 2, 0);
+/// }
+///
+/// foo::<10>();
+/// ```
+pub mod prelude {
+  #[doc(inline)]
+  pub use crate::{
+    build_assert, build_assert_align_ge, build_assert_all_nonzero, build_assert_block,
+    build_assert_bounds, build_assert_char_range, build_assert_congruent, build_assert_coprime,
+    build_assert_deprecated, build_assert_detailed, build_assert_distinct, build_assert_divisible,
+    build_assert_dyn_safe, build_assert_ends_with, build_assert_eq, build_assert_eq_display,
+    build_assert_eval, build_assert_even, build_assert_exactly, build_assert_expr,
+    build_assert_false, build_assert_gcd_eq, build_assert_ge, build_assert_gt, build_assert_impl,
+    build_assert_index, build_assert_le, build_assert_len_eq, build_assert_lt, build_assert_lut_eq,
+    build_assert_monotonic, build_assert_ne, build_assert_no_overflow, build_assert_no_overlap,
+    build_assert_nonzero, build_assert_odd, build_assert_on, build_assert_one_of,
+    build_assert_report, build_assert_satisfies, build_assert_shift_ok, build_assert_size,
+    build_assert_size_ge, build_assert_starts_with, build_assert_str_eq, build_assert_true,
+    build_error, build_proof, build_static_assert, build_uniq_sym, build_unreachable_if, check,
+    finish, BuildCheck, Proof,
+  };
+
+  #[cfg(feature = "error_handler")]
+  #[doc(inline)]
+  pub use crate::define_build_error_handler;
+
+  #[cfg(feature = "testing")]
+  #[doc(inline)]
+  pub use crate::assert_build_fails;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_build_assert() {
+    build_assert!(true);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "assertion failed: false")]
+  fn test_build_assert_fail() {
+    build_assert!(false);
+  }
+
+  #[test]
+  fn test_build_assert_inline_args() {
+    const N: usize = 5;
+    // Const panics raised in strict modes do not support format arguments,
+    // so the condition is not const-evaluable here.
+    let n = N;
+    build_assert!(n > 1, "n must be greater than 1, got {N}");
+  }
+
+  #[test]
+  fn test_build_assert_str_const() {
+    // Comparisons of `&str` are not const, so the condition must not be
+    // evaluated in an inline const block in strict modes.
+    const NAME: &str = "foo";
+    build_assert!(NAME == "foo");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "N must be greater than 10, got 5")]
+  fn test_build_assert_inline_args_fail() {
+    const N: usize = 5;
+    build_assert!(N > 10, "N must be greater than 10, got {N}");
+  }
+
+  fn assert_const_block<const N: usize>() {
+    build_assert!(const { N > 5 });
+    build_assert!(const { N.is_power_of_two() }, "N must be a power of two");
+  }
+
+  #[test]
+  fn test_build_assert_const_block() {
+    build_assert!(const { 1 + 1 == 2 });
+    build_assert!(const { core::mem::size_of::<u32>() == 4 },);
+    assert_const_block::<8>();
+    assert_const_block::<64>();
+  }
+
+  #[allow(clippy::let_unit_value)]
+  #[test]
+  fn test_build_assert_tail_expr() {
+    let () = { build_assert!(true) };
+    let () = { build_assert_eq!(1, 1) };
+    let () = { build_assert_ne!(1, 2, "must differ") };
+    let () = { build_assert!(const { true }) };
+  }
+
+  macro_rules! assert_stmt {
+    ($cond:expr) => {
+      build_assert!($cond);
+    };
+  }
+
+  #[test]
+  fn test_build_assert_match_arm() {
+    for i in 0..3 {
+      match i {
+        0 => build_assert!(true),
+        1 => build_assert!(true, "unreachable"),
+        _ => {
+          assert_stmt!(true);
+          assert_stmt!(i > 1);
+        }
+      }
+    }
+  }
+
+  fn assert_block<const N: usize, const M: usize>() {
+    build_assert_block! {
+      N > 0;
+      M.is_power_of_two() => "M must be a power of two, got {}", M;
+      N != M => "N and M must be different";
+      N < 10 && N == M - 1;
+      N + 1 == M
+    }
+  }
+
+  #[test]
+  fn test_build_assert_block() {
+    build_assert_block! {}
+    build_assert_block! { true; }
+    assert_block::<1, 2>();
+    assert_block::<3, 4>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "M must be a power of two, got 3")]
+  fn test_build_assert_block_fail() {
+    assert_block::<2, 3>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(
+    expected = "assertion `left != right` failed: N and M must be different\n  left: 2\n right: 2"
+  )]
+  fn test_build_assert_block_ne_fail() {
+    assert_block::<2, 2>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "assertion `left == right` failed\n  left: 9\n right: 5")]
+  fn test_build_assert_block_eq_fail() {
+    build_assert_block! {
+      1 + 8 == 5;
+    }
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "assertion failed: 1 == 1 && 2 == 3")]
+  fn test_build_assert_block_plain_fail() {
+    build_assert_block! {
+      1 == 1 && 2 == 3;
+    }
+  }
+
+  build_static_assert!(core::mem::size_of::<u64>() == 8);
+  build_static_assert!(core::mem::align_of::<u8>() == 1, "u8 must be byte-aligned");
+
+  #[test]
+  fn test_build_static_assert() {
+    build_static_assert!(i32::MAX > 0);
+  }
+
+  #[cfg(build = "debug")]
+  #[track_caller]
+  fn check_caller(cond: bool) {
+    build_assert!(cond, "check failed");
+  }
+
+  #[cfg(build = "debug")]
+  extern crate std;
+
+  /// Runs the given function, returns the file and line of the panic it
+  /// raised.
+  #[cfg(build = "debug")]
+  fn panic_location<F>(f: F) -> Option<(std::string::String, u32)>
+  where
+    F: FnOnce() + std::panic::UnwindSafe,
+  {
+    use std::{boxed::Box, cell::RefCell, panic, string::ToString, sync::Mutex, thread_local};
+
+    // The panic hook is global, so tests replacing it must not run
+    // concurrently.
+    static HOOK: Mutex<()> = Mutex::new(());
+    thread_local! {
+      static LOCATION: RefCell<Option<(std::string::String, u32)>> = const { RefCell::new(None) };
+    }
+
+    let _guard = HOOK.lock().unwrap_or_else(|e| e.into_inner());
+    let prev = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+      let location = info.location().map(|l| (l.file().to_string(), l.line()));
+      LOCATION.with(|l| *l.borrow_mut() = location);
+    }));
+    let result = panic::catch_unwind(f);
+    panic::set_hook(prev);
+    assert!(result.is_err());
+    LOCATION.with(|l| l.borrow_mut().take())
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  fn test_build_assert_track_caller() {
+    let line = line!() + 1;
+    let location = panic_location(|| check_caller(false));
+    assert_eq!(location, Some((file!().into(), line)));
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  fn test_build_assert_track_caller_pass() {
+    check_caller(true);
+  }
+
+  fn assert_const<const N: usize>() {
+    build_assert!(N > 10, "N must be greater than 10, got {}", N);
+  }
+
+  #[test]
+  fn test_assert_const() {
+    assert_const::<11>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "N must be greater than 10, got 10")]
+  fn test_assert_const_fail() {
+    assert_const::<10>();
+  }
+
+  /// Generates tests of comparison assertions from a table, including a
+  /// passing case, failing cases with and without a custom message, and a
+  /// check that the failing case fails to build in the current profile.
+  macro_rules! cmp_tests {
+    ($(
+      $name:ident: $assert:ident!(.. $($suffix:tt)*),
+      pass($pl:expr, $pr:expr), fail($fl:expr, $fr:expr),
+      $failed:literal, $msg:literal, $custom_msg:literal;
+    )*) => {$(
+      mod $name {
+        #[test]
+        fn pass() {
+          $assert!($pl, $pr $($suffix)*);
+          $assert!($pl, $pr $($suffix)*, "custom message {}", 1);
+        }
+
+        #[cfg(build = "debug")]
+        #[test]
+        #[should_panic(expected = $msg)]
+        fn fail() {
+          $assert!($fl, $fr $($suffix)*);
+        }
+
+        #[cfg(build = "debug")]
+        #[test]
+        #[should_panic(expected = $custom_msg)]
+        fn fail_custom_message() {
+          $assert!($fl, $fr $($suffix)*, "custom message {}", 1);
+        }
+
+        #[cfg(feature = "testing")]
+        #[test]
+        fn testing_build_fails() {
+          crate::assert_build_fails!(
+            core::concat!(
+              "build_assert::",
+              core::stringify!($assert),
+              "!(",
+              core::stringify!($fl, $fr $($suffix)*),
+              ");"
+            ),
+            $failed,
+          );
+        }
+      }
+    )*};
+  }
+
+  cmp_tests! {
+    cmp_eq: build_assert_eq!(..), pass(1, 1), fail("a", "b"),
+      "assertion `left == right` failed",
+      "assertion `left == right` failed\n  left: \"a\"\n right: \"b\"",
+      "assertion `left == right` failed: custom message 1\n  left: \"a\"\n right: \"b\"";
+    cmp_eq_show_diff: build_assert_eq!(..; show_diff), pass(1, 1), fail(100, 42),
+      "assertion `left == right` failed",
+      "assertion `left == right` failed\n  left: 100\n right: 42\n  diff: 58",
+      "assertion `left == right` failed: custom message 1\n  left: 100\n right: 42\n  diff: 58";
+    cmp_ne: build_assert_ne!(..), pass(1, 2), fail(1, 1),
+      "assertion `left != right` failed",
+      "assertion `left != right` failed\n  left: 1\n right: 1",
+      "assertion `left != right` failed: custom message 1\n  left: 1\n right: 1";
+    cmp_eq_display: build_assert_eq_display!(..), pass("a", "a"), fail("a", "b"),
+      "assertion `left == right` failed",
+      "assertion `left == right` failed\n  left: a\n right: b",
+      "assertion `left == right` failed: custom message 1\n  left: a\n right: b";
+    cmp_lt: build_assert_lt!(..), pass(1, 2), fail(2, 2),
+      "assertion `left < right` failed",
+      "assertion `left < right` failed\n  left: 2\n right: 2",
+      "assertion `left < right` failed: custom message 1\n  left: 2\n right: 2";
+    cmp_le: build_assert_le!(..), pass(2, 2), fail(3, 2),
+      "assertion `left <= right` failed",
+      "assertion `left <= right` failed\n  left: 3\n right: 2",
+      "assertion `left <= right` failed: custom message 1\n  left: 3\n right: 2";
+    cmp_gt: build_assert_gt!(..), pass('b', 'a'), fail('a', 'b'),
+      "assertion `left > right` failed",
+      "assertion `left > right` failed\n  left: 'a'\n right: 'b'",
+      "assertion `left > right` failed: custom message 1\n  left: 'a'\n right: 'b'";
+    cmp_ge: build_assert_ge!(..), pass(2, 2), fail(2, 3),
+      "assertion `left >= right` failed",
+      "assertion `left >= right` failed\n  left: 2\n right: 3",
+      "assertion `left >= right` failed: custom message 1\n  left: 2\n right: 3";
+  }
+
+  #[test]
+  fn test_build_assert_distinct() {
+    build_assert_distinct!(1, 2);
+    build_assert_distinct!('a', 'b', "chars must differ");
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "expected distinct values but both were 1")]
+  fn test_build_assert_distinct_fail() {
+    build_assert_distinct!(1, 1);
+  }
+
+  fn assert_const_distinct<const A: usize, const B: usize>() {
+    build_assert_distinct!(A, B, "slot {} is allocated twice", A);
+  }
+
+  #[test]
+  fn test_assert_const_distinct() {
+    assert_const_distinct::<1, 2>();
+    assert_const_distinct::<3, 0>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "expected distinct values but both were 2: slot 2 is allocated twice")]
+  fn test_assert_const_distinct_fail() {
+    assert_const_distinct::<2, 2>();
+  }
+
+  fn assert_impl<T: Clone + Send + 'static>() {
+    build_assert_impl!(T: Clone + Send);
+    build_assert_impl!(Option<T>: Clone + Send + 'static);
+  }
+
+  #[test]
+  fn test_build_assert_impl() {
+    build_assert_impl!(i32: Copy);
+    build_assert_impl!(str: ?Sized + Send);
+    assert_impl::<u8>();
+  }
+
+  fn assert_satisfies<T: Copy + Send>() {
+    build_assert_satisfies!(T, "must be Copy for SIMD", Copy + Send);
+    build_assert_satisfies!([T; 4], "lanes must be Copy", Copy);
+  }
+
+  trait Area {
+    fn area(&self) -> u32;
+  }
+
+  impl Area for u32 {
+    fn area(&self) -> u32 {
+      *self
+    }
+  }
+
+  fn assert_dyn_safe<T>() {
+    build_assert_dyn_safe!(AsRef<[T]> + Send);
+  }
+
+  #[test]
+  fn test_build_assert_dyn_safe() {
+    build_assert_dyn_safe!(Area);
+    let area: &dyn Area = &4;
+    assert_eq!(area.area(), 4);
+    build_assert_dyn_safe!(core::fmt::Debug + Send + Sync + 'static);
+    assert_dyn_safe::<u8>();
+  }
+
+  #[test]
+  fn test_build_assert_satisfies() {
+    build_assert_satisfies!(str, "must be Send", ?Sized + Send);
+    assert_satisfies::<u8>();
+  }
+
+  fn assert_index<const I: usize, const N: usize>() {
+    build_assert_index!(I, N);
+  }
+
+  #[test]
+  fn test_build_assert_index() {
+    assert_index::<0, 1>();
+    assert_index::<3, 4>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "index 4 out of bounds for length 4")]
+  fn test_build_assert_index_eq_fail() {
+    assert_index::<4, 4>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "index 5 out of bounds for length 4")]
+  fn test_build_assert_index_gt_fail() {
+    assert_index::<5, 4>();
+  }
+
+  fn assert_array_size<const LEN: usize, const N: usize>() {
+    build_assert!(LEN == core::mem::size_of::<[u8; N]>());
+    build_assert_size!([u8; N], LEN);
+  }
+
+  #[test]
+  fn test_build_assert_size() {
+    build_assert_size!(u32, 4);
+    build_assert_size!((), 0);
+    assert_array_size::<8, 8>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "size of `u64` is 8 bytes, expected 4")]
+  fn test_build_assert_size_fail() {
+    build_assert_size!(u64, 4);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "assertion failed: LEN == core::mem::size_of::<[u8; N]>()")]
+  fn test_assert_array_size_fail() {
+    assert_array_size::<8, 4>();
+  }
+
+  fn assert_eval<const N: usize>() {
+    build_assert_eval!(N > 1, "N must be greater than 1, got {}", N);
+  }
+
+  #[test]
+  fn test_build_assert_eval() {
+    build_assert_eval!(2 + 2 == 4);
+    build_assert_eval!((1 << 3) - 1 == 7, "unreachable");
+    assert_eval::<2>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "N must be greater than 1, got 1")]
+  fn test_build_assert_eval_fallback_fail() {
+    assert_eval::<1>();
+  }
+
+  fn assert_detailed<const A: usize, const B: usize, const C: usize>() {
+    build_assert_detailed!(A > 0 && B < 10 && A + B == C, "invalid layout");
+  }
+
+  #[test]
+  fn test_build_assert_detailed() {
+    build_assert_detailed!(2 + 2 == 4);
+    assert_detailed::<1, 2, 3>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "invalid layout\nassertion failed: B < 10 (conjunct 2 of 3)")]
+  fn test_build_assert_detailed_fail() {
+    assert_detailed::<1, 12, 13>();
+  }
+
+  fn assert_checks<const A: usize, const B: usize>() {
+    finish!(check(A > 0).and(check(B > A)).and(check(B < 10)));
+  }
+
+  #[test]
+  fn test_finish() {
+    let checks = check(true).and(check(1 + 1 == 2));
+    assert_eq!(checks.len(), 2);
+    finish!(checks);
+    assert_checks::<1, 2>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "build check 1 failed")]
+  fn test_finish_fail() {
+    assert_checks::<2, 1>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "build check 0 failed")]
+  fn test_finish_first_fail() {
+    assert_checks::<0, 10>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "build check 2 failed")]
+  fn test_build_check_drop_fail() {
+    let _checks = check(true).and(check(true)).and(check(false));
+  }
+
+  fn assert_no_overflow_mul<const A: u32, const B: u32>() {
+    build_assert_no_overflow!(A * B, u32);
+  }
+
+  fn assert_no_overflow_add<const A: u8, const B: u8>() {
+    build_assert_no_overflow!(A + B, u8);
+  }
+
+  #[test]
+  fn test_build_assert_no_overflow() {
+    assert_no_overflow_mul::<65535, 65537>();
+    assert_no_overflow_add::<200, 55>();
+    build_assert_no_overflow!(1 - 2, i32);
+    build_assert_no_overflow!((i8::MIN + 1) / (-1), i8);
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "arithmetic overflow: 65536 * 65536 overflows `u32`")]
+  fn test_build_assert_no_overflow_mul_fail() {
+    assert_no_overflow_mul::<65536, 65536>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "arithmetic overflow: 200 + 56 overflows `u8`")]
+  fn test_build_assert_no_overflow_add_fail() {
+    assert_no_overflow_add::<200, 56>();
+  }
+
+  #[cfg(feature = "disabled")]
+  #[test]
+  fn disabled_assertions() {
+    build_assert!(false);
+    build_assert!(false, "unreachable");
+    build_assert_eq!(1, 2);
+    build_assert_ne!(1, 1, "left: {}", 1);
+    build_assert_eval!(1 + 1 == 3);
+    finish!(check(false));
+    let _ = check(false);
+  }
+
+  #[cfg(feature = "disabled")]
+  #[test]
+  fn disabled_condition_not_evaluated() {
+    fn cond() -> bool {
+      panic!("condition evaluated")
+    }
+    build_assert!(cond());
+  }
+
+  #[cfg(feature = "no_fmt")]
+  #[derive(PartialEq)]
+  struct NoDebug(u8);
+
+  #[cfg(feature = "no_fmt")]
+  #[test]
+  fn test_no_fmt_pass() {
+    build_assert_eq!(NoDebug(1), NoDebug(1));
+    build_assert_ne!(NoDebug(1), NoDebug(2), "must differ");
+    build_assert_distinct!(NoDebug(1), NoDebug(2));
+    build_assert_eq_display!(NoDebug(1), NoDebug(1));
+  }
+
+  #[cfg(all(build = "debug", feature = "no_fmt", not(feature = "disabled")))]
+  #[test]
+  #[should_panic(expected = "assertion `left == right` failed")]
+  fn test_no_fmt_eq_fail() {
+    build_assert_eq!(NoDebug(1), NoDebug(2), "values of {}", "NoDebug");
+  }
+
+  #[cfg(all(build = "debug", feature = "no_fmt", not(feature = "disabled")))]
+  #[test]
+  #[should_panic(expected = "assertion `left != right` failed")]
+  fn test_no_fmt_ne_fail() {
+    build_assert_ne!(NoDebug(1), NoDebug(1));
+  }
+
+  #[cfg(all(build = "debug", feature = "no_fmt", not(feature = "disabled")))]
+  #[test]
+  #[should_panic(expected = "string does not start with the expected prefix")]
+  fn test_no_fmt_starts_with_fail() {
+    build_assert_starts_with!("v1.2.0", "v2.");
+  }
+
+  build_assert_report! {
+    u8::MAX as usize == 255;
+    core::mem::size_of::<u32>() == 4;
+  }
+
+  #[test]
+  fn test_report() {
+    let report = Report::new()
+      .check(true, "a")
+      .check(false, "b")
+      .check(false, "c");
+    assert_eq!(report.message(), "build assertions failed:\n  - b\n  - c");
+    assert_eq!(Report::new().check(true, "a").message(), "");
+    Report::new().check(true, "a").finish();
+  }
+
+  #[test]
+  fn test_report_truncate() {
+    let mut report = Report::new();
+    for _ in 0..200 {
+      report = report.check(false, "αβγ");
+    }
+    assert!(report.message().len() <= Report::CAPACITY);
+    assert!(report
+      .message()
+      .starts_with("build assertions failed:\n  - αβγ"));
+  }
+
+  #[derive(Debug, PartialEq)]
+  struct NonCopy(u32);
+
+  impl NonCopy {
+    fn is_valid(&self) -> bool {
+      self.0 > 0
+    }
+  }
+
+  fn is_valid(v: &NonCopy) -> bool {
+    v.is_valid()
+  }
+
+  #[test]
+  fn test_non_copy_not_moved() {
+    let v = NonCopy(1);
+    build_assert!(v.is_valid());
+    build_assert!(is_valid(&v), "invalid value: {:?}", v);
+    build_assert_eq!(v, NonCopy(1));
+    build_assert_ne!(v, NonCopy(2), "{:?} must not be 2", v);
+    build_assert_distinct!(v, NonCopy(3));
+    build_assert_block! {
+      v.is_valid();
+      v == NonCopy(1);
+    }
+    assert_eq!(v, NonCopy(1));
+    let NonCopy(n) = v;
+    assert_eq!(n, 1);
+  }
+
+  const NAME: &str = "build_assert";
+
+  #[test]
+  fn test_build_assert_str_eq() {
+    build_assert_str_eq!(NAME, "build_assert");
+    build_assert_str_eq!("", "");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "no_fmt")))]
+  #[test]
+  #[should_panic(
+    expected = "assertion `left == right` failed: strings differ at byte 6\n  left: \"build_assert\"\n right: \"build_error\""
+  )]
+  fn test_build_assert_str_eq_fail() {
+    build_assert_str_eq!(NAME, "build_error");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "no_fmt")))]
+  #[test]
+  #[should_panic(
+    expected = "assertion `left == right` failed: strings differ at byte 5\n  left: \"build_assert\"\n right: \"build\""
+  )]
+  fn test_build_assert_str_eq_len_fail() {
+    build_assert_str_eq!(NAME, "build");
+  }
+
+  const VERSION: &str = "v1.2.0-beta";
+
+  #[test]
+  fn test_build_assert_starts_ends_with() {
+    build_assert_starts_with!(VERSION, "v1.");
+    build_assert_starts_with!(VERSION, VERSION);
+    build_assert_starts_with!(VERSION, "");
+    build_assert_ends_with!(VERSION, "-beta");
+    build_assert_ends_with!(VERSION, VERSION);
+    build_assert_ends_with!("", "");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "no_fmt")))]
+  #[test]
+  #[should_panic(expected = "string \"v1.2.0-beta\" does not start with \"v2.\"")]
+  fn test_build_assert_starts_with_fail() {
+    build_assert_starts_with!(VERSION, "v2.");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "no_fmt")))]
+  #[test]
+  #[should_panic(expected = "string \"v1\" does not start with \"v1.2.0\"")]
+  fn test_build_assert_starts_with_long_prefix_fail() {
+    build_assert_starts_with!("v1", "v1.2.0");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "no_fmt")))]
+  #[test]
+  #[should_panic(expected = "string \"v1.2.0-beta\" does not end with \"-rc\"")]
+  fn test_build_assert_ends_with_fail() {
+    build_assert_ends_with!(VERSION, "-rc");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "no_fmt")))]
+  #[test]
+  #[should_panic(expected = "string \"beta\" does not end with \"-beta\"")]
+  fn test_build_assert_ends_with_long_suffix_fail() {
+    build_assert_ends_with!("beta", "-beta");
+  }
+
+  fn assert_on<const N: usize>() {
+    build_assert_on!(cfg(all()), N > 5, "N must be greater than 5");
+    build_assert_on!(cfg(any()), N > 100, "N must be greater than 100");
+    build_assert_on!(cfg(any()), undefined_fn(N));
+  }
+
+  #[test]
+  fn test_build_assert_on() {
+    assert_on::<10>();
+    build_assert_on!(cfg(target_pointer_width = "64"), usize::BITS == 64);
+    build_assert_on!(cfg(not(target_pointer_width = "64")), usize::BITS != 64);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "N must be greater than 5")]
+  fn test_build_assert_on_fail() {
+    assert_on::<0>();
+  }
+
+  fn assert_true<const B: bool>() {
+    build_assert_true!(B);
+  }
+
+  fn assert_false<const B: bool>() {
+    build_assert_false!(B);
+  }
+
+  #[test]
+  fn test_build_assert_true_false() {
+    assert_true::<true>();
+    assert_false::<false>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected const generic `B` to be true")]
+  fn test_build_assert_true_fail() {
+    assert_true::<false>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected const generic `B` to be false")]
+  fn test_build_assert_false_fail() {
+    assert_false::<true>();
+  }
+
+  macro_rules! def_uniq_const {
+    ($id:ident) => {
+      #[allow(non_upper_case_globals, dead_code)]
+      const $id: () = ();
+    };
+  }
+
+  // Fails to compile if the identifiers are not unique.
+  build_uniq_sym!(=> def_uniq_const);
+  build_uniq_sym!(=> def_uniq_const);
+  build_uniq_sym!(uniq => def_uniq_const);
+  build_uniq_sym!(uniq => def_uniq_const);
+
+  #[test]
+  fn test_build_assert_code() {
+    build_assert!(true; code = "BA0001");
+    build_assert!(true; code = "BA0002", "unreachable");
+    build_assert!(true; code = "BA0003", "unreachable {}", 1);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "disabled")))]
+  #[test]
+  #[should_panic(expected = "[BA0007] assertion failed: cond")]
+  fn test_build_assert_code_fail() {
+    let cond = false;
+    build_assert!(cond; code = "BA0007");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "disabled")))]
+  #[test]
+  #[should_panic(expected = "[BA0008] value must be positive, got 0")]
+  fn test_build_assert_code_msg_fail() {
+    let value = 0;
+    build_assert!(value > 0; code = "BA0008", "value must be positive, got {}", value);
+  }
+
+  #[cfg(all(build = "debug", feature = "log", not(feature = "disabled")))]
+  #[test]
+  fn test_log_before_panic() {
+    use std::{format, panic, string::String, sync::Mutex, vec::Vec};
+
+    /// A logger that captures the messages, and whether the current thread
+    /// was panicking when the message was logged.
+    struct CaptureLogger(Mutex<Vec<(String, bool)>>);
+
+    impl log::Log for CaptureLogger {
+      fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+      }
+
+      fn log(&self, record: &log::Record) {
+        let msg = format!("{}: {}", record.level(), record.args());
+        self.0.lock().unwrap().push((msg, std::thread::panicking()));
+      }
+
+      fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger(Mutex::new(Vec::new()));
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Error);
+
+    let n = 0;
+    let result =
+      panic::catch_unwind(|| build_assert!(n > 5, "n must be greater than 5, got {}", n));
+    assert!(result.is_err());
+    let logs = LOGGER.0.lock().unwrap();
+    assert_eq!(
+      logs.as_slice(),
+      [("ERROR: n must be greater than 5, got 0".into(), false)]
+    );
+  }
+
+  #[allow(dead_code)]
+  #[repr(align(16))]
+  struct Aligned16([u8; 32]);
+
+  fn assert_layout<T>() {
+    build_assert_size_ge!(T, 16);
+    build_assert_align_ge!(T, 8);
+  }
+
+  #[test]
+  fn test_build_assert_layout() {
+    build_assert_size_ge!(u32, 4);
+    build_assert_size_ge!([u8; 8], 2 + 2);
+    build_assert_align_ge!(u64, core::mem::align_of::<u32>());
+    assert_layout::<Aligned16>();
+    assert_layout::<u128>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected size of `T` to be at least 16 bytes, got 8")]
+  fn test_build_assert_size_ge_fail() {
+    assert_layout::<u64>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected alignment of `T` to be at least 8, got 1")]
+  fn test_build_assert_align_ge_fail() {
+    assert_layout::<[u8; 16]>();
+  }
+
+  #[cfg(all(build = "debug", feature = "test_hook", not(feature = "disabled")))]
+  #[test]
+  fn test_hook_receives_message() {
+    use std::{panic, string::String, sync::Mutex, vec::Vec};
+
+    static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    let prev = set_build_error_hook(|msg| MESSAGES.lock().unwrap().push(msg.into()));
+    assert!(prev.is_none());
+    let n = 3;
+    let result =
+      panic::catch_unwind(|| build_assert!(n > 5, "n must be greater than 5, got {}", n));
+    assert!(result.is_err());
+    let result = panic::catch_unwind(|| build_assert!(n > 5));
+    assert!(result.is_err());
+    assert!(take_build_error_hook().is_some());
+    assert!(take_build_error_hook().is_none());
+
+    let messages = MESSAGES.lock().unwrap();
+    assert!(messages.contains(&"n must be greater than 5, got 3".into()));
+    assert!(messages.contains(&"assertion failed: n > 5".into()));
+  }
+
+  fn assert_expr_tail<const N: usize>() {
+    build_assert_expr!(N > 5)
+  }
+
+  fn assert_expr_closure<const N: usize>() -> usize {
+    let check = |n: usize| build_assert_expr!(n > 5, "n must be greater than 5");
+    check(N);
+    match N {
+      n if {
+        build_assert_expr!(n < 100);
+        true
+      } =>
+      {
+        n
+      }
+      _ => 0,
+    }
+  }
 
-#[cfg(test)]
-mod tests {
   #[test]
-  fn test_build_assert() {
-    build_assert!(true);
+  fn test_build_assert_expr() {
+    let () = build_assert_expr!(true);
+    assert_expr_tail::<10>();
+    assert_eq!(assert_expr_closure::<10>(), 10);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "assertion failed: N > 5")]
+  fn test_build_assert_expr_tail_fail() {
+    assert_expr_tail::<0>();
   }
 
   #[cfg(build = "debug")]
   #[test]
-  #[should_panic(expected = "assertion failed: false")]
-  fn test_build_assert_fail() {
-    build_assert!(false);
+  #[should_panic(expected = "n must be greater than 5")]
+  fn test_build_assert_expr_closure_fail() {
+    assert_expr_closure::<0>();
   }
 
-  fn assert_const<const N: usize>() {
-    build_assert!(N > 10, "N must be greater than 10, got {}", N);
+  fn assert_len_eq<const N: usize, const M: usize>(_: [u8; N], _: [u8; M]) {
+    build_assert_len_eq!(N, M);
   }
 
   #[test]
-  fn test_assert_const() {
-    assert_const::<11>();
+  fn test_build_assert_len_eq() {
+    assert_len_eq([0; 3], [1; 3]);
+    assert_len_eq([], []);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "array length mismatch: 3 vs 2")]
+  fn test_build_assert_len_eq_fail() {
+    assert_len_eq([0; 3], [1; 2]);
+  }
+
+  fn unreachable_if<const N: usize>() -> usize {
+    match N {
+      0 | 1 => N,
+      _ => {
+        build_unreachable_if!(N > 1);
+        0
+      }
+    }
+  }
+
+  #[test]
+  fn test_build_unreachable_if() {
+    assert_eq!(unreachable_if::<0>(), 0);
+    assert_eq!(unreachable_if::<1>(), 1);
+    build_unreachable_if!(false, "custom message");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "unreachable for this configuration")]
+  fn test_build_unreachable_if_fail() {
+    unreachable_if::<2>();
+  }
+
+  fn assert_hard<const N: usize>() {
+    build_assert!(N > 5; hard);
+    build_assert!(N < 100; hard, "N is too large");
+  }
+
+  #[test]
+  fn test_build_assert_hard() {
+    build_assert!(true; hard);
+    build_assert!(u8::MAX as usize == 255; hard,);
+    assert_hard::<10>();
+  }
+
+  #[test]
+  fn test_const_cmp_first_diff_index() {
+    use const_cmp::first_diff_index;
+    assert_eq!(first_diff_index(b"", b""), None);
+    assert_eq!(first_diff_index(b"a", b"a"), None);
+    assert_eq!(first_diff_index(b"abc", b"abc"), None);
+    assert_eq!(first_diff_index(b"a", b"b"), Some(0));
+    assert_eq!(first_diff_index(b"abc", b"abd"), Some(2));
+    assert_eq!(first_diff_index(b"abc", b"ab"), Some(2));
+    assert_eq!(first_diff_index(b"", b"a"), Some(0));
+  }
+
+  #[test]
+  fn test_const_cmp_starts_ends_with() {
+    use const_cmp::{ends_with, starts_with};
+    assert!(starts_with(b"build_assert", b"build"));
+    assert!(starts_with(b"build_assert", b""));
+    assert!(!starts_with(b"build_assert", b"assert"));
+    assert!(!starts_with(b"build", b"build_assert"));
+    assert!(ends_with(b"build_assert", b"assert"));
+    assert!(ends_with(b"build_assert", b""));
+    assert!(!ends_with(b"build_assert", b"build"));
+    assert!(!ends_with(b"assert", b"build_assert"));
+  }
+
+  #[test]
+  fn test_const_cmp_version_ge() {
+    use const_cmp::version_ge;
+    assert!(version_ge("1.2.0", "1.2.0"));
+    assert!(version_ge("1.2.1", "1.2.0"));
+    assert!(version_ge("1.10.0", "1.9.9"));
+    assert!(version_ge("2.0.0", "1.99.99"));
+    assert!(version_ge("1.2", "1.2.0"));
+    assert!(version_ge("1.2.0+build.5", "1.2.0"));
+    assert!(version_ge("1.2.0", "1.2.0-rc.1"));
+    assert!(!version_ge("1.2.0-rc.1", "1.2.0"));
+    assert!(!version_ge("1.1.9", "1.2.0"));
+    assert!(!version_ge("0.0.1", "1"));
+    const { assert!(version_ge("1.2.3", "1.2.3")) };
+  }
+
+  #[test]
+  #[should_panic(expected = "malformed version")]
+  fn test_const_cmp_version_ge_malformed() {
+    const_cmp::version_ge("1..0", "1.0.0");
+  }
+
+  #[test]
+  fn test_const_cmp_gcd() {
+    use const_cmp::gcd;
+    assert_eq!(gcd(0, 0), 0);
+    assert_eq!(gcd(0, 7), 7);
+    assert_eq!(gcd(7, 0), 7);
+    assert_eq!(gcd(16, 3), 1);
+    assert_eq!(gcd(35, 64), 1);
+    assert_eq!(gcd(12, 8), 4);
+    assert_eq!(gcd(8, 12), 4);
+    assert_eq!(gcd(u128::MAX, u128::MAX), u128::MAX);
+    // Consecutive Fibonacci numbers take the most steps.
+    let (f185, f186) = (
+      205697230343233228174223751303346572685,
+      332825110087067562321196029789634457848,
+    );
+    assert_eq!(gcd(f185, f186), 1);
+    assert_eq!(gcd(f186, f185), 1);
+  }
+
+  #[test]
+  fn test_const_cmp_in_const() {
+    build_assert!(const { const_cmp::starts_with(NAME.as_bytes(), b"build") });
+    build_assert!(const { const_cmp::gcd(12, 8) == 4 });
+    const DIFF: Option<usize> = const_cmp::first_diff_index(b"abc", b"abd");
+    assert_eq!(DIFF, Some(2));
+  }
+
+  #[cfg(all(
+    feature = "emit_metadata",
+    not(feature = "disabled"),
+    target_os = "linux"
+  ))]
+  #[test]
+  fn test_emit_metadata() {
+    use core::{slice, str};
+
+    extern "C" {
+      static __start_build_assert: u8;
+      static __stop_build_assert: u8;
+    }
+
+    fn assert_meta<const N: usize>() -> u32 {
+      build_assert!(N > 5, "N is too small");
+      line!() - 1
+    }
+    let line = assert_meta::<10>();
+
+    // SAFETY: the linker defines these symbols at the bounds of the section.
+    let section = unsafe {
+      let start = core::ptr::addr_of!(__start_build_assert);
+      let stop = core::ptr::addr_of!(__stop_build_assert);
+      slice::from_raw_parts(start, stop as usize - start as usize)
+    };
+    let found = section
+      .split(|b| *b == 0)
+      .filter_map(|r| str::from_utf8(r).ok())
+      .filter_map(|r| r.strip_prefix("src/lib.rs:")?.strip_suffix(":7: N > 5"))
+      .any(|l| l.parse() == Ok(line));
+    assert!(found, "record not found in section: {section:?}");
+  }
+
+  fn assert_divisible<const N: usize, const LANES: usize>() {
+    build_assert_divisible!(N, LANES);
+  }
+
+  #[test]
+  fn test_build_assert_divisible() {
+    assert_divisible::<8, 4>();
+    assert_divisible::<0, 4>();
+    assert_divisible::<7, 1>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected 6 to be divisible by 4")]
+  fn test_build_assert_divisible_fail() {
+    assert_divisible::<6, 4>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "divisor is zero")]
+  fn test_build_assert_divisible_zero() {
+    assert_divisible::<8, 0>();
+  }
+
+  fn assert_congruent<const N: usize, const CHUNK: usize, const R: usize>() {
+    build_assert_congruent!(N, CHUNK, R);
+  }
+
+  #[test]
+  fn test_build_assert_congruent() {
+    assert_congruent::<33, 8, 1>();
+    assert_congruent::<32, 8, 0>();
+    assert_congruent::<3, 8, 3>();
+    assert_congruent::<7, 1, 0>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected 34 % 8 to be 1, got 2")]
+  fn test_build_assert_congruent_fail() {
+    assert_congruent::<34, 8, 1>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "modulus is zero")]
+  fn test_build_assert_congruent_zero() {
+    assert_congruent::<33, 0, 1>();
+  }
+
+  fn assert_even<const N: i32>() {
+    build_assert_even!(N);
+  }
+
+  fn assert_odd<const N: i32>() {
+    build_assert_odd!(N);
+  }
+
+  #[test]
+  fn test_build_assert_parity() {
+    assert_even::<0>();
+    assert_even::<2>();
+    assert_even::<-4>();
+    assert_odd::<1>();
+    assert_odd::<7>();
+    assert_odd::<-3>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected an even value, got 3")]
+  fn test_build_assert_even_fail() {
+    assert_even::<3>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected an odd value, got 0")]
+  fn test_build_assert_odd_fail() {
+    assert_odd::<0>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected an odd value, got -2")]
+  fn test_build_assert_odd_fail_negative() {
+    assert_odd::<-2>();
+  }
+
+  fn assert_coprime<const A: usize, const B: usize>() {
+    build_assert_coprime!(A, B);
+  }
+
+  fn assert_gcd_eq<const A: u64, const B: u64, const G: u64>() {
+    build_assert_gcd_eq!(A, B, G);
+  }
+
+  #[test]
+  fn test_build_assert_coprime() {
+    assert_coprime::<16, 3>();
+    assert_coprime::<1, 1>();
+    assert_coprime::<0, 1>();
+    assert_coprime::<35, 64>();
+    build_assert_coprime!(9u8, 28u8);
   }
 
   #[cfg(build = "debug")]
   #[test]
-  #[should_panic(expected = "N must be greater than 10, got 10")]
-  fn test_assert_const_fail() {
-    assert_const::<10>();
+  fn test_build_assert_coprime_runtime() {
+    let (a, b) = (core::hint::black_box(35u32), core::hint::black_box(64u32));
+    build_assert_coprime!(a, b);
   }
 
+  #[cfg(build = "debug")]
   #[test]
-  fn test_build_assert_eq() {
-    build_assert_eq!(1, 1);
+  #[should_panic(expected = "expected 16 and 6 to be coprime, got gcd 2")]
+  fn test_build_assert_coprime_fail() {
+    assert_coprime::<16, 6>();
   }
 
   #[cfg(build = "debug")]
   #[test]
-  #[should_panic(expected = "assertion `left == right` failed\n  left: 1\n right: 2")]
-  fn test_build_assert_eq_fail() {
-    build_assert_eq!(1, 2);
+  #[should_panic(expected = "expected 0 and 0 to be coprime, got gcd 0")]
+  fn test_build_assert_coprime_fail_zero() {
+    assert_coprime::<0, 0>();
+  }
+
+  #[test]
+  fn test_build_assert_gcd_eq() {
+    assert_gcd_eq::<12, 8, 4>();
+    assert_gcd_eq::<8, 12, 4>();
+    assert_gcd_eq::<0, 5, 5>();
+    assert_gcd_eq::<0, 0, 0>();
+    assert_gcd_eq::<{ u64::MAX }, { u64::MAX }, { u64::MAX }>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "expected gcd(12, 9) to be 4, got 3")]
+  fn test_build_assert_gcd_eq_fail() {
+    assert_gcd_eq::<12, 9, 4>();
+  }
+
+  fn assert_shift_ok_u16<const S: u32>() {
+    build_assert_shift_ok!(S, u16);
+  }
+
+  fn assert_shift_ok_u64<const S: i32>() {
+    build_assert_shift_ok!(S, u64);
+  }
+
+  #[test]
+  fn test_build_assert_shift_ok() {
+    assert_shift_ok_u16::<0>();
+    assert_shift_ok_u16::<15>();
+    assert_shift_ok_u64::<0>();
+    assert_shift_ok_u64::<63>();
+    build_assert_shift_ok!(7u8, i8);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "shift amount 16 is out of range for `u16` of 16 bits")]
+  fn test_build_assert_shift_ok_u16_fail() {
+    assert_shift_ok_u16::<16>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "shift amount 64 is out of range for `u64` of 64 bits")]
+  fn test_build_assert_shift_ok_u64_fail() {
+    assert_shift_ok_u64::<64>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "shift amount -1 is out of range for `u64` of 64 bits")]
+  fn test_build_assert_shift_ok_negative() {
+    assert_shift_ok_u64::<-1>();
+  }
+
+  #[cfg(feature = "const_panic")]
+  fn assert_const_panic<const N: usize>() -> usize {
+    build_assert!(N > 5, "N must be greater than 5");
+    let n = N;
+    build_assert!(n > 5, "n must be greater than 5");
+    n
+  }
+
+  #[cfg(feature = "const_panic")]
+  #[test]
+  fn test_const_panic() {
+    assert_eq!(assert_const_panic::<10>(), 10);
+  }
+
+  fn assert_nonzero<const N: usize>() -> core::num::NonZeroUsize {
+    core::num::NonZeroUsize::new(build_assert_nonzero!(N)).unwrap()
+  }
+
+  #[test]
+  fn test_build_assert_nonzero() {
+    assert_eq!(assert_nonzero::<1>().get(), 1);
+    assert_eq!(assert_nonzero::<16>().get(), 16);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "expected a non-zero value")]
+  fn test_build_assert_nonzero_fail() {
+    assert_nonzero::<0>();
+  }
+
+  #[cfg(feature = "testing")]
+  #[test]
+  fn test_testing_assert_build_fails() {
+    assert_build_fails!("build_assert::build_assert!(1 + 1 == 3, \"always fails\");");
+    assert_build_fails!(
+      "build_assert::build_assert!(1 + 1 == 3, \"always fails\");",
+      "always fails"
+    );
+  }
+
+  #[cfg(feature = "testing")]
+  #[test]
+  #[should_panic(expected = "expected the snippet to fail")]
+  fn test_testing_assert_build_fails_pass() {
+    assert_build_fails!("build_assert::build_assert!(1 + 1 == 2);");
+  }
+
+  fn assert_bounds<const N: usize>() {
+    build_assert_bounds!(N, 1, 64);
+  }
+
+  #[test]
+  fn test_build_assert_bounds() {
+    assert_bounds::<1>();
+    assert_bounds::<32>();
+    assert_bounds::<64>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "value 0 is below the minimum 1")]
+  fn test_build_assert_bounds_below() {
+    assert_bounds::<0>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "value 65 is above the maximum 64")]
+  fn test_build_assert_bounds_above() {
+    assert_bounds::<65>();
+  }
+
+  fn assert_no_overlap<const AS: usize, const AE: usize, const BS: usize, const BE: usize>() {
+    build_assert_no_overlap!(AS, AE, BS, BE);
+  }
+
+  #[test]
+  fn test_build_assert_no_overlap() {
+    assert_no_overlap::<0, 16, 16, 32>();
+    assert_no_overlap::<16, 32, 0, 16>();
+    assert_no_overlap::<0, 8, 32, 64>();
+    assert_no_overlap::<8, 8, 0, 16>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "ranges [0, 16) and [8, 32) overlap in [8, 16)")]
+  fn test_build_assert_no_overlap_fail() {
+    assert_no_overlap::<0, 16, 8, 32>();
   }
 
-  fn assert_const_eq<const A: usize, const B: usize>() {
-    build_assert_eq!(A, B, "A must be equal to B, got {A} and {B}");
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "ranges [8, 12) and [0, 32) overlap in [8, 12)")]
+  fn test_build_assert_no_overlap_contained() {
+    assert_no_overlap::<8, 12, 0, 32>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "first range [16, 8) is malformed")]
+  fn test_build_assert_no_overlap_malformed_first() {
+    assert_no_overlap::<16, 8, 32, 64>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "second range [64, 32) is malformed")]
+  fn test_build_assert_no_overlap_malformed_second() {
+    assert_no_overlap::<0, 8, 64, 32>();
+  }
+
+  const LUT: [usize; 4] = [1, 2, 4, 8];
+
+  fn assert_lut_eq<const I: usize, const N: usize>() {
+    build_assert_lut_eq!(LUT, I, N);
+  }
+
+  #[test]
+  fn test_build_assert_lut_eq() {
+    assert_lut_eq::<0, 1>();
+    assert_lut_eq::<3, 8>();
   }
 
+  #[cfg(all(build = "debug", not(feature = "no_fmt")))]
   #[test]
-  fn test_assert_const_eq() {
-    assert_const_eq::<1, 1>();
+  #[should_panic(expected = "table entry at index 2 is 4, expected 8")]
+  fn test_build_assert_lut_eq_mismatch() {
+    assert_lut_eq::<2, 8>();
   }
 
   #[cfg(build = "debug")]
   #[test]
-  #[should_panic(
-    expected = "assertion `left == right` failed: A must be equal to B, got 1 and 2\n  left: 1\n right: 2"
-  )]
-  fn test_assert_const_eq_fail() {
-    assert_const_eq::<1, 2>();
+  #[should_panic(expected = "index 4 out of bounds for table of length 4")]
+  fn test_build_assert_lut_eq_out_of_bounds() {
+    assert_lut_eq::<4, 16>();
+  }
+
+  fn assert_one_of<const D: u8>() {
+    build_assert_one_of!(D, [1, 2, 4]);
+  }
+
+  #[test]
+  fn test_build_assert_one_of() {
+    assert_one_of::<1>();
+    assert_one_of::<4>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "value 3 is not one of [1, 2, 4]")]
+  fn test_build_assert_one_of_fail() {
+    assert_one_of::<3>();
+  }
+
+  fn assert_exactly<const LANES: usize>() {
+    build_assert_exactly!(LANES, 4);
+  }
+
+  #[test]
+  fn test_build_assert_exactly() {
+    assert_exactly::<4>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "this API currently requires 4, got 8")]
+  fn test_build_assert_exactly_fail() {
+    assert_exactly::<8>();
+  }
+
+  struct Shape<const W: usize, const H: usize>;
+
+  impl<const W: usize, const H: usize> Shape<W, H> {
+    const STRIDES: [usize; 2] = [H, 1];
+  }
+
+  fn assert_all_nonzero<const W: usize, const H: usize>() {
+    build_assert_all_nonzero!(Shape::<W, H>::STRIDES);
   }
 
   #[test]
-  fn test_build_assert_ne() {
-    build_assert_ne!(1, 2);
+  fn test_build_assert_all_nonzero() {
+    const EMPTY: [u32; 0] = [];
+    build_assert_all_nonzero!(EMPTY);
+    build_assert_all_nonzero!([1i8, -1, 3]);
+    assert_all_nonzero::<4, 8>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(expected = "element at index 0 is zero")]
+  fn test_build_assert_all_nonzero_fail() {
+    assert_all_nonzero::<4, 0>();
   }
 
   #[cfg(build = "debug")]
   #[test]
-  #[should_panic(expected = "assertion `left != right` failed\n  left: 1\n right: 1")]
-  fn test_build_assert_ne_fail() {
-    build_assert_ne!(1, 1);
+  #[should_panic(expected = "element at index 2 is zero")]
+  fn test_build_assert_all_nonzero_last_fail() {
+    const DIMS: [u64; 3] = [4, 8, 0];
+    build_assert_all_nonzero!(DIMS);
   }
 
-  fn assert_const_ne<const A: usize, const B: usize>() {
-    build_assert_ne!(A, B, "A must not be equal to B, got {} and {}", A, B);
+  fn assert_monotonic<const A: usize, const B: usize, const C: usize>() {
+    build_assert_monotonic!([A, B, C]);
   }
 
   #[test]
-  fn test_assert_const_ne() {
-    assert_const_ne::<1, 2>();
+  fn test_build_assert_monotonic() {
+    assert_monotonic::<1, 2, 3>();
+    assert_monotonic::<0, 0, 0>();
+    assert_monotonic::<1, 1, 8>();
+    build_assert_monotonic!([5]);
+    build_assert_monotonic!([-3i32, -1, 2,]);
   }
 
   #[cfg(build = "debug")]
   #[test]
   #[should_panic(
-    expected = "assertion `left != right` failed: A must not be equal to B, got 1 and 1\n  left: 1\n right: 1"
+    expected = "expected non-decreasing values, but element at index 1 (1) is less than element at index 0 (2)"
   )]
-  fn test_assert_const_ne_fail() {
-    assert_const_ne::<1, 1>();
+  fn test_build_assert_monotonic_fail() {
+    assert_monotonic::<2, 1, 3>();
+  }
+
+  #[cfg(build = "debug")]
+  #[test]
+  #[should_panic(
+    expected = "expected non-decreasing values, but element at index 2 (3) is less than element at index 1 (4)"
+  )]
+  fn test_build_assert_monotonic_last_fail() {
+    assert_monotonic::<1, 4, 3>();
+  }
+
+  const LEGACY: bool = true;
+
+  // The warning is checked by `deprecated_warning` in `tests/profile_matrix.rs`.
+  #[test]
+  #[allow(deprecated)]
+  fn test_build_assert_deprecated() {
+    // The current version is below the threshold, only warns.
+    build_assert_deprecated!(!LEGACY, since = "99.0.0", "legacy layout will be removed");
+    // The condition holds.
+    build_assert_deprecated!(LEGACY, since = "0.0.1", "legacy layout is required");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "legacy layout is removed (an error since version 0.0.1)")]
+  fn test_build_assert_deprecated_reached() {
+    build_assert_deprecated!(!LEGACY, since = "0.0.1", "legacy layout is removed");
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "legacy layout is removed (an error since version 0.0.1-alpha)")]
+  fn test_build_assert_deprecated_above() {
+    build_assert_deprecated!(!LEGACY, since = "0.0.1-alpha", "legacy layout is removed");
+  }
+
+  #[test]
+  fn test_build_assert_char_range() {
+    fn lower<const C: char>() {
+      build_assert_char_range!(C, 'a'..='z');
+    }
+    fn ascii<const C: char>() {
+      build_assert_char_range!(C, '\0'..'\u{80}');
+    }
+    lower::<'a'>();
+    lower::<'m'>();
+    lower::<'z'>();
+    ascii::<'\0'>();
+    ascii::<'\x7f'>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "character 'A' (U+0041) is not in the range 'a'..='z'")]
+  fn test_build_assert_char_range_below() {
+    fn lower<const C: char>() {
+      build_assert_char_range!(C, 'a'..='z');
+    }
+    lower::<'A'>();
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "character 'é' (U+00E9) is not in the range '\\0'..'\\u{80}'")]
+  fn test_build_assert_char_range_exclusive() {
+    fn ascii<const C: char>() {
+      build_assert_char_range!(C, '\0'..'\u{80}');
+    }
+    ascii::<'é'>();
+  }
+
+  struct Aligned;
+
+  fn aligned(_: Proof<Aligned>, addr: usize) -> usize {
+    addr
+  }
+
+  fn proof_aligned<const ADDR: usize>() -> usize {
+    aligned(
+      build_proof!(ADDR & 7 == 0 => Aligned, "{} is not aligned", ADDR),
+      ADDR,
+    )
+  }
+
+  #[test]
+  fn test_build_proof() {
+    let proof: Proof = build_proof!(2 + 2 == 4);
+    let _ = (proof, proof);
+    assert_eq!(core::mem::size_of::<Proof<Aligned>>(), 0);
+    assert_eq!(proof_aligned::<64>(), 64);
+  }
+
+  #[cfg(all(build = "debug", not(feature = "strict_debug")))]
+  #[test]
+  #[should_panic(expected = "3 is not aligned")]
+  fn test_build_proof_fail() {
+    proof_aligned::<3>();
   }
 }
@@ -0,0 +1,50 @@
+//! Tests for the handler defined by `define_build_error_handler`.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo test --release --features error_handler --test error_handler
+//! ```
+
+#![cfg(feature = "error_handler")]
+
+use build_assert::{build_assert, define_build_error_handler};
+
+define_build_error_handler!(|msg| panic!("handled: {msg}"));
+
+#[inline(never)]
+fn check(n: usize) {
+  build_assert!(n > 5, "n must be greater than 5, got {}", n);
+}
+
+#[inline(never)]
+fn check_inline_args(n: usize) {
+  build_assert!(n > 5, "n must be greater than 5, got {n}");
+}
+
+#[test]
+fn test_handler_pass() {
+  check(std::hint::black_box(10));
+  check_inline_args(std::hint::black_box(10));
+}
+
+#[cfg(build = "debug")]
+#[test]
+#[should_panic(expected = "n must be greater than 5, got 1")]
+fn test_handler_debug() {
+  check(std::hint::black_box(1));
+}
+
+#[cfg(build = "release")]
+#[test]
+#[should_panic(expected = "handled: n must be greater than 5, got 1")]
+fn test_handler_release() {
+  check(std::hint::black_box(1));
+}
+
+#[cfg(build = "release")]
+#[test]
+#[should_panic(expected = "handled: n must be greater than 5, got 2")]
+fn test_handler_release_inline_args() {
+  check_inline_args(std::hint::black_box(2));
+}
@@ -0,0 +1,25 @@
+//! Tests for importing all macros from the prelude in a `#![no_std]` crate.
+
+#![no_std]
+
+use build_assert::prelude::*;
+
+fn assert_params<const N: usize, const LANES: usize>() -> usize {
+  build_assert!(N > 0, "N must be positive");
+  build_assert_eq!(N % 2, 0);
+  build_assert_ne!(N, LANES);
+  build_assert_divisible!(N, LANES);
+  build_assert_bounds!(LANES, 1, 16);
+  build_assert_size!([u8; N], N);
+  build_assert_nonzero!(N / LANES)
+}
+
+build_static_assert!(core::mem::size_of::<u32>() == 4);
+
+#[test]
+fn test_prelude() {
+  assert_eq!(assert_params::<8, 4>(), 2);
+  build_assert_impl!(u32: Copy + Send);
+  build_assert_satisfies!(u32, "must be Copy", Copy);
+  finish!(check(true).and(check(1 + 1 == 2)));
+}
@@ -0,0 +1,490 @@
+//! Builds known-failing snippets with `build_assert` in different profiles
+//! and feature combinations, and checks the kind of the raised error.
+//!
+//! These tests invoke `cargo` to build separate crates, so they are ignored
+//! by default. To run them:
+//!
+//! ```text
+//! cargo test --test profile_matrix -- --ignored
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A snippet that passes the assertion.
+const PASSING: &str = r#"
+fn foo<const N: usize>() {
+  build_assert::build_assert!(N > 5, "N must be greater than 5");
+}
+
+fn main() {
+  foo::<10>();
+  print!("{}", build_assert::__BUILD_ERROR_KIND);
+}
+"#;
+
+/// A snippet that fails the assertion.
+const FAILING: &str = r#"
+fn foo<const N: usize>() {
+  build_assert::build_assert!(N > 5, "N must be greater than 5");
+}
+
+fn main() {
+  foo::<0>();
+}
+"#;
+
+/// A snippet that fails the assertion with a condition that is not
+/// const-evaluable.
+const FAILING_NON_CONST: &str = r#"
+fn foo<const N: usize>() {
+  let n = N;
+  build_assert::build_assert!(n > 5, "n must be greater than 5");
+}
+
+fn main() {
+  foo::<0>();
+}
+"#;
+
+/// A snippet with const-evaluable conditions and a runtime condition, all
+/// of them pass the assertion.
+const NON_CONST: &str = r#"
+fn foo<const N: usize>() {
+  build_assert::build_assert!(N > 5);
+  build_assert::build_assert!(N.is_power_of_two());
+  let n = N;
+  build_assert::build_assert!(n > 5);
+}
+
+fn main() {
+  foo::<16>();
+}
+"#;
+
+/// A snippet with unsatisfied trait bounds and custom messages.
+const UNSATISFIED: &str = r#"
+fn foo<T>() {
+  build_assert::build_assert_satisfies!(T, "must be Copy for SIMD", Copy);
+}
+
+fn main() {
+  foo::<u8>();
+  build_assert::build_assert_satisfies!(String, "must be Copy for SIMD", Copy + Send);
+}
+"#;
+
+/// A snippet with a trait that is not dyn compatible.
+const NOT_DYN_SAFE: &str = r#"
+trait Shape {
+  fn new() -> Self;
+}
+
+fn main() {
+  build_assert::build_assert_dyn_safe!(Shape);
+}
+"#;
+
+/// A snippet with deprecated configurations, the version of the snippet is
+/// lower than the threshold.
+const DEPRECATED: &str = r#"
+const LEGACY: bool = true;
+
+fn main() {
+  build_assert::build_assert_deprecated!(!LEGACY, since = "1.0.0", "legacy layout will be removed");
+  build_assert::build_assert_deprecated!(LEGACY, since = "1.0.0", "legacy layout is required");
+}
+"#;
+
+/// A snippet that fails the assertion with `defmt`, the logger writes the
+/// raw frames to the standard error output.
+const FAILING_DEFMT: &str = r#"
+use std::io::Write;
+
+#[defmt::global_logger]
+struct Logger;
+
+unsafe impl defmt::Logger for Logger {
+  fn acquire() {}
+  unsafe fn flush() {}
+  unsafe fn release() {}
+  unsafe fn write(bytes: &[u8]) {
+    std::io::stderr().write_all(bytes).unwrap();
+  }
+}
+
+defmt::timestamp!("");
+
+#[defmt::panic_handler]
+fn panic() -> ! {
+  std::process::exit(42)
+}
+
+fn foo<const N: usize>() {
+  build_assert::build_assert!(N > 5, "N must be greater than {}", 5);
+}
+
+fn main() {
+  foo::<0>();
+}
+"#;
+
+/// Expected kind of the build error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorKind {
+  /// Panics at runtime.
+  Panic,
+  /// Fails to build with an inline assembly error.
+  Asm,
+  /// Fails to build with a link error.
+  Link,
+  /// Fails to build with a const panic.
+  ConstPanic,
+}
+
+impl ErrorKind {
+  /// Returns the value of `__BUILD_ERROR_KIND` for this kind.
+  fn name(self) -> &'static str {
+    match self {
+      Self::Panic => "panic",
+      Self::Asm => "asm",
+      Self::Link => "link",
+      // Falls back to inline assembly for conditions that are not
+      // const-evaluable.
+      Self::ConstPanic => "asm",
+    }
+  }
+}
+
+/// A profile and feature combination to be tested.
+struct Case {
+  name: &'static str,
+  release: bool,
+  features: &'static [&'static str],
+}
+
+impl Case {
+  /// Creates a crate containing the given snippet, returns its directory.
+  fn create(&self, snippet: &str, suffix: &str) -> PathBuf {
+    self.create_with(snippet, suffix, "")
+  }
+
+  /// Creates a crate containing the given snippet and extra dependencies,
+  /// returns its directory.
+  fn create_with(&self, snippet: &str, suffix: &str, deps: &str) -> PathBuf {
+    let dir = root().join(format!("{}_{suffix}", self.name));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    let features = self
+      .features
+      .iter()
+      .map(|f| format!("{f:?}"))
+      .collect::<Vec<_>>()
+      .join(", ");
+    let manifest = format!(
+      r#"[package]
+name = "snippet"
+version = "0.0.0"
+edition = "2021"
+
+[dependencies]
+build_assert = {{ path = {:?}, features = [{features}] }}
+{deps}
+
+[workspace]
+"#,
+      env!("CARGO_MANIFEST_DIR"),
+    );
+    fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    fs::write(dir.join("src/main.rs"), snippet).unwrap();
+    // Reuse the lock file if possible, to avoid resolving dependencies again.
+    let lock = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.lock");
+    if lock.exists() {
+      fs::copy(lock, dir.join("Cargo.lock")).unwrap();
+    }
+    dir
+  }
+
+  /// Runs `cargo run` in the given directory.
+  fn run(&self, dir: &Path) -> Output {
+    self.command(dir).output().unwrap()
+  }
+
+  /// Returns the `cargo run` command for the given directory.
+  fn command(&self, dir: &Path) -> Command {
+    let mut cmd = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+    cmd
+      .arg("run")
+      .arg("--quiet")
+      .current_dir(dir)
+      .env("CARGO_TARGET_DIR", root().join("target"))
+      .env_remove("BUILD_ERROR_SYM")
+      .env_remove("BUILD_ERROR_TAG")
+      .env_remove("BUILD_ASSERT_DUMP");
+    if self.release {
+      cmd.arg("--release");
+    }
+    cmd
+  }
+
+  /// Checks the behavior of both passing and failing snippets.
+  fn check(&self, kind: ErrorKind) {
+    // The passing snippet should build and run.
+    let output = self.run(&self.create(PASSING, "pass"));
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), kind.name());
+    // The failing snippet should raise the expected error.
+    let output = self.run(&self.create(FAILING, "fail"));
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    match kind {
+      ErrorKind::Panic => {
+        assert_eq!(output.status.code(), Some(101), "{stderr}");
+        assert!(stderr.contains("panicked"), "{stderr}");
+        assert!(stderr.contains("N must be greater than 5"), "{stderr}");
+      }
+      ErrorKind::Asm => {
+        assert!(stderr.contains("could not compile"), "{stderr}");
+        assert!(stderr.contains("build error at"), "{stderr}");
+        assert!(stderr.contains("N must be greater than 5"), "{stderr}");
+      }
+      ErrorKind::Link => {
+        assert!(stderr.contains("could not compile"), "{stderr}");
+        assert!(stderr.contains("__build_error_impl"), "{stderr}");
+      }
+      ErrorKind::ConstPanic => {
+        assert!(stderr.contains("could not compile"), "{stderr}");
+        assert!(stderr.contains("error[E0080]"), "{stderr}");
+        assert!(!stderr.contains("build error at"), "{stderr}");
+        assert!(stderr.contains("N must be greater than 5"), "{stderr}");
+      }
+    }
+  }
+}
+
+/// Returns the root directory of the generated crates.
+fn root() -> PathBuf {
+  Path::new(env!("CARGO_TARGET_TMPDIR")).join("profile_matrix")
+}
+
+/// Returns the standard error output as a string.
+fn stderr(output: &Output) -> String {
+  String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn debug() {
+  let case = Case {
+    name: "debug",
+    release: false,
+    features: &[],
+  };
+  case.check(ErrorKind::Panic);
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn release_asm() {
+  let case = Case {
+    name: "release_asm",
+    release: true,
+    features: &[],
+  };
+  case.check(ErrorKind::Asm);
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn release_no_asm() {
+  let case = Case {
+    name: "release_no_asm",
+    release: true,
+    features: &["no_asm"],
+  };
+  case.check(ErrorKind::Link);
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn release_warn_non_const() {
+  let case = Case {
+    name: "release_warn_non_const",
+    release: true,
+    features: &["warn_non_const"],
+  };
+  // Only the runtime condition should produce a warning.
+  let output = case.run(&case.create(NON_CONST, "pass"));
+  let stderr = stderr(&output);
+  assert!(output.status.success(), "{stderr}");
+  let warning = "build_assert condition is not const-evaluable; it may not fail the build";
+  assert_eq!(stderr.matches(warning).count(), 1, "{stderr}");
+  assert!(stderr.contains("src/main.rs:6:"), "{stderr}");
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn release_const_panic() {
+  let case = Case {
+    name: "release_const_panic",
+    release: true,
+    features: &["const_panic"],
+  };
+  case.check(ErrorKind::ConstPanic);
+  // Conditions that are not const-evaluable fall back to inline assembly.
+  let output = case.run(&case.create(FAILING_NON_CONST, "fail_non_const"));
+  let stderr = stderr(&output);
+  assert!(!output.status.success());
+  assert!(!stderr.contains("error[E0080]"), "{stderr}");
+  assert!(stderr.contains("build error at"), "{stderr}");
+  assert!(stderr.contains("n must be greater than 5"), "{stderr}");
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn debug_collect_sites() {
+  let case = Case {
+    name: "debug_collect_sites",
+    release: false,
+    features: &["collect_sites"],
+  };
+  let dump = root().join("debug_collect_sites.jsonl");
+  let _ = fs::remove_file(&dump);
+  let output = case
+    .command(&case.create(NON_CONST, "pass"))
+    .env("BUILD_ASSERT_DUMP", &dump)
+    .output()
+    .unwrap();
+  assert!(output.status.success(), "{}", stderr(&output));
+  let content = fs::read_to_string(&dump).unwrap();
+  let sites: Vec<_> = content
+    .lines()
+    .filter(|l| l.contains("src/main.rs"))
+    .collect();
+  assert_eq!(sites.len(), 3, "{content}");
+  assert!(sites[0].contains(r#""line":3,"#), "{content}");
+  assert!(sites[0].contains(r#""condition":"N > 5""#), "{content}");
+  assert!(sites[1].contains(r#""line":4,"#), "{content}");
+  assert!(sites[1].contains("is_power_of_two"), "{content}");
+  assert!(sites[2].contains(r#""line":6,"#), "{content}");
+  assert!(sites[2].contains(r#""condition":"n > 5""#), "{content}");
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn debug_satisfies_message() {
+  let case = Case {
+    name: "debug_satisfies_message",
+    release: false,
+    features: &[],
+  };
+  let output = case.run(&case.create(UNSATISFIED, "fail"));
+  let stderr = stderr(&output);
+  assert!(!output.status.success());
+  assert!(stderr.contains("error[E0277]"), "{stderr}");
+  assert!(
+    stderr.contains("unsatisfied bounds for `T`: must be Copy for SIMD"),
+    "{stderr}"
+  );
+  assert!(
+    stderr.contains("unsatisfied bounds for `String`: must be Copy for SIMD"),
+    "{stderr}"
+  );
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn deprecated_warning() {
+  for (name, release) in [("debug_deprecated", false), ("release_deprecated", true)] {
+    let case = Case {
+      name,
+      release,
+      features: &[],
+    };
+    // Only the unsatisfied condition should produce a warning.
+    let output = case.run(&case.create(DEPRECATED, "pass"));
+    let stderr = stderr(&output);
+    assert!(output.status.success(), "{stderr}");
+    assert_eq!(
+      stderr.matches("warning: use of deprecated").count(),
+      1,
+      "{stderr}"
+    );
+    assert!(stderr.contains("legacy layout will be removed"), "{stderr}");
+    assert!(!stderr.contains("legacy layout is required"), "{stderr}");
+    assert!(stderr.contains("src/main.rs:5:"), "{stderr}");
+  }
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn release_dyn_safe() {
+  let case = Case {
+    name: "release_dyn_safe",
+    release: true,
+    features: &[],
+  };
+  let output = case.run(&case.create(NOT_DYN_SAFE, "fail"));
+  let stderr = stderr(&output);
+  assert!(!output.status.success());
+  assert!(stderr.contains("error[E0038]"), "{stderr}");
+  assert!(
+    stderr.contains("the trait `Shape` is not dyn compatible"),
+    "{stderr}"
+  );
+  assert!(stderr.contains("src/main.rs:7:"), "{stderr}");
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn error_tag() {
+  let cases = [
+    Case {
+      name: "debug_error_tag",
+      release: false,
+      features: &[],
+    },
+    Case {
+      name: "release_asm_error_tag",
+      release: true,
+      features: &[],
+    },
+    Case {
+      name: "release_no_asm_error_tag",
+      release: true,
+      features: &["no_asm"],
+    },
+  ];
+  let expected = [
+    "[BA] N must be greater than 5",
+    "[BA] build error at src/main.rs:3:3: N must be greater than 5",
+    "__build_error_impl_BA_snippet_0",
+  ];
+  for (case, expected) in cases.iter().zip(expected) {
+    let output = case
+      .command(&case.create(FAILING, "fail"))
+      .env("BUILD_ERROR_TAG", "BA")
+      .output()
+      .unwrap();
+    let stderr = stderr(&output);
+    assert!(!output.status.success());
+    assert!(stderr.contains(expected), "{stderr}");
+  }
+}
+
+#[test]
+#[ignore = "invokes cargo, run with `--ignored`"]
+fn debug_defmt() {
+  let case = Case {
+    name: "debug_defmt",
+    release: false,
+    features: &["defmt"],
+  };
+  let dir = case.create_with(FAILING_DEFMT, "fail", r#"defmt = "1""#);
+  let output = case.run(&dir);
+  let stderr = stderr(&output);
+  // The panic handler of `defmt` exits with code 42.
+  assert_eq!(output.status.code(), Some(42), "{stderr}");
+  assert!(!stderr.contains("panicked"), "{stderr}");
+  assert!(stderr.contains("N must be greater than 5"), "{stderr}");
+}